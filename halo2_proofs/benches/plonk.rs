@@ -278,6 +278,26 @@ fn criterion_benchmark(c: &mut Criterion) {
         transcript.finalize()
     }
 
+    fn prover_with_quotient_evaluation(
+        k: u32,
+        params: &Params<G1Affine>,
+        pk: &ProvingKey<G1Affine>,
+        quotient_evaluation: QuotientEvaluation,
+    ) -> Vec<u8> {
+        let rng = OsRng;
+
+        let circuit: MyCircuit<Fp> = MyCircuit {
+            a: Some(Fp::random(rng)),
+            k,
+        };
+
+        let control = CancelFlag::new().with_quotient_evaluation(quotient_evaluation);
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_with_control(params, pk, &[circuit], &[&[]], rng, &mut transcript, &control)
+            .expect("proof generation should not fail");
+        transcript.finalize()
+    }
+
     fn verifier(params: &ParamsVerifier<Bn256>, vk: &VerifyingKey<G1Affine>, proof: &[u8]) {
         let strategy = SingleVerifier::new(params);
         let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
@@ -310,6 +330,28 @@ fn criterion_benchmark(c: &mut Criterion) {
     }
     prover_group.finish();
 
+    let mut quotient_group = c.benchmark_group("plonk-prover-quotient-evaluation");
+    quotient_group.sample_size(10);
+    for k in k_range.clone() {
+        let (params, _, pk) = keygen(k);
+
+        for (name, quotient_evaluation) in [
+            ("extended", QuotientEvaluation::Extended),
+            ("by-coset", QuotientEvaluation::ByCoset),
+        ]
+        .iter()
+        {
+            quotient_group.bench_with_input(
+                BenchmarkId::new(*name, k),
+                &(k, &params, &pk),
+                |b, &(k, params, pk)| {
+                    b.iter(|| prover_with_quotient_evaluation(k, params, pk, *quotient_evaluation));
+                },
+            );
+        }
+    }
+    quotient_group.finish();
+
     let mut verifier_group = c.benchmark_group("plonk-verifier");
     for k in k_range {
         let (params, params_verifier, pk) = keygen(k);
@@ -319,3 +319,49 @@ fn main() {
         .unwrap();
 }
 // ANCHOR_END: dev-graph
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        helpers::Serializable,
+        pairing::bn256::{Bn256, Fr as Fp, G1Affine},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier, VerifyingKey},
+        poly::commitment::{Params, ParamsVerifier},
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+    use rand_core::OsRng;
+
+    use super::MyCircuit;
+
+    /// Checks that a proof verifies with the verifying key read back without
+    /// the circuit.
+    #[test]
+    fn vk_round_trip() {
+        let k = 5;
+        let a = Fp::from(5);
+        let instance = Fp::from(2);
+        let circuit = MyCircuit {
+            a: Some(a),
+            lookup_table: vec![instance, a, a, Fp::from(0)],
+        };
+
+        let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(k);
+        let params_verifier: ParamsVerifier<Bn256> = params.verifier(0).unwrap();
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk should not fail");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk should not fail");
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(&params, &pk, &[circuit], &[&[]], OsRng, &mut transcript)
+            .expect("proof generation should not fail");
+        let proof = transcript.finalize();
+
+        let mut vk_bytes = vec![];
+        pk.get_vk().store(&mut vk_bytes).unwrap();
+        let vk = VerifyingKey::<G1Affine>::fetch(&mut &vk_bytes[..]).unwrap();
+        assert_eq!(vk.digest(), pk.get_vk().digest());
+
+        let strategy = SingleVerifier::new(&params_verifier);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+        assert!(verify_proof(&params_verifier, &vk, strategy, &[&[]], &mut transcript).is_ok());
+    }
+}
@@ -371,3 +371,52 @@ fn main() {
     let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
     assert!(prover.verify().is_err());
 }
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        helpers::Serializable,
+        pairing::bn256::{Bn256, Fr as Fp, G1Affine},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier, VerifyingKey},
+        poly::commitment::{Params, ParamsVerifier},
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+    use rand_core::OsRng;
+
+    use super::MyCircuit;
+
+    /// Checks that a proof verifies with the verifying key read back without
+    /// the circuit.
+    #[test]
+    fn vk_round_trip() {
+        let k = 4;
+        let constant = Fp::from(7);
+        let a = Fp::from(2);
+        let b = Fp::from(3);
+        let c = constant * a * a * b * b;
+        let circuit = MyCircuit {
+            constant,
+            a: Some(a),
+            b: Some(b),
+        };
+
+        let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(k);
+        let params_verifier: ParamsVerifier<Bn256> = params.verifier(1).unwrap();
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk should not fail");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk should not fail");
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(&params, &pk, &[circuit], &[&[&[c]]], OsRng, &mut transcript)
+            .expect("proof generation should not fail");
+        let proof = transcript.finalize();
+
+        let mut vk_bytes = vec![];
+        pk.get_vk().store(&mut vk_bytes).unwrap();
+        let vk = VerifyingKey::<G1Affine>::fetch(&mut &vk_bytes[..]).unwrap();
+        assert_eq!(vk.digest(), pk.get_vk().digest());
+
+        let strategy = SingleVerifier::new(&params_verifier);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+        assert!(verify_proof(&params_verifier, &vk, strategy, &[&[&[c]]], &mut transcript).is_ok());
+    }
+}
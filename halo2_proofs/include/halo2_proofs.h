@@ -0,0 +1,150 @@
+#ifndef HALO2_PROOFS_H
+#define HALO2_PROOFS_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// The outcome of a call to the C interface.
+typedef enum Halo2Status {
+  // The call succeeded, and every proof it checked is valid.
+  HALO2_STATUS_OK = 0,
+  // A pointer that must not be null was null.
+  HALO2_STATUS_NULL_POINTER = 1,
+  // The verifier parameters could not be read.
+  HALO2_STATUS_INVALID_PARAMS = 2,
+  // The verifying key could not be read.
+  HALO2_STATUS_INVALID_VERIFYING_KEY = 3,
+  // The instances are malformed, contain a non-canonical scalar, or do not
+  // match the circuit.
+  HALO2_STATUS_INVALID_INSTANCES = 4,
+  // A proof could not be read.
+  HALO2_STATUS_INVALID_PROOF = 5,
+  // A proof was read but is not valid.
+  HALO2_STATUS_VERIFICATION_FAILED = 6,
+  // The verifier parameters of a batch were not created by the same setup.
+  HALO2_STATUS_INCOMPATIBLE_PARAMS = 7,
+  // The call panicked. This is a bug.
+  HALO2_STATUS_PANIC = 8,
+} Halo2Status;
+
+// Verifier parameters read by [`halo2_params_verifier_read`].
+typedef struct Halo2ParamsVerifier Halo2ParamsVerifier;
+
+// A verifying key read by [`halo2_verifying_key_read`].
+typedef struct Halo2VerifyingKey Halo2VerifyingKey;
+
+// The instance values of a proof.
+//
+// Each value is a scalar in its canonical 32-byte little-endian
+// representation. The values are ordered by circuit instance, then by
+// instance column, then by row.
+typedef struct Halo2Instances {
+  // The `32 * num_values` bytes of the values.
+  const uint8_t *values;
+  // The number of values.
+  size_t num_values;
+  // The number of values of each instance column of each circuit instance,
+  // `num_circuits * num_columns` lengths in all.
+  const size_t *column_lengths;
+  // The number of circuit instances of the proof.
+  size_t num_circuits;
+  // The number of instance columns of the circuit.
+  size_t num_columns;
+} Halo2Instances;
+
+// A proof to check in a batch with [`halo2_verify_batch`].
+typedef struct Halo2BatchEntry {
+  // The verifier parameters of the proof.
+  const Halo2ParamsVerifier *params;
+  // The verifying key of the circuit of the proof.
+  const Halo2VerifyingKey *vk;
+  // The instance values of the proof.
+  Halo2Instances instances;
+  // The `proof_len` bytes of the proof.
+  const uint8_t *proof;
+  // The length of the proof.
+  size_t proof_len;
+} Halo2BatchEntry;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Reads verifier parameters from the `len` bytes at `bytes`, and stores a
+// handle to them in `out`, to be freed with [`halo2_params_verifier_free`].
+//
+// # Safety
+//
+// `bytes` must point to `len` readable bytes, and `out` must point to a
+// writable handle.
+Halo2Status halo2_params_verifier_read(const uint8_t *bytes,
+                                       size_t len,
+                                       Halo2ParamsVerifier **out);
+
+// Frees verifier parameters read by [`halo2_params_verifier_read`]. Does
+// nothing if `params` is null.
+//
+// # Safety
+//
+// `params` must be null or a handle that has not been freed yet.
+void halo2_params_verifier_free(Halo2ParamsVerifier *params);
+
+// Reads a verifying key from the `len` bytes at `bytes`, and stores a handle
+// to it in `out`, to be freed with [`halo2_verifying_key_free`].
+//
+// # Safety
+//
+// `bytes` must point to `len` readable bytes, and `out` must point to a
+// writable handle.
+Halo2Status halo2_verifying_key_read(const uint8_t *bytes, size_t len, Halo2VerifyingKey **out);
+
+// Frees a verifying key read by [`halo2_verifying_key_read`]. Does nothing
+// if `vk` is null.
+//
+// # Safety
+//
+// `vk` must be null or a handle that has not been freed yet.
+void halo2_verifying_key_free(Halo2VerifyingKey *vk);
+
+// Verifies the `proof_len` bytes of the proof at `proof` for the circuit of
+// `vk` and the given instances.
+//
+// Returns [`Halo2Status::Ok`] if the proof is valid.
+//
+// # Safety
+//
+// `params` and `vk` must be live handles, `instances` must point to
+// instances whose arrays are readable, and `proof` must point to
+// `proof_len` readable bytes.
+Halo2Status halo2_verify_proof(const Halo2ParamsVerifier *params,
+                               const Halo2VerifyingKey *vk,
+                               const Halo2Instances *instances,
+                               const uint8_t *proof,
+                               size_t proof_len);
+
+// Verifies the `num_entries` proofs at `entries` together, which is faster
+// than verifying them one at a time. The proofs may be for different
+// circuits, but their verifier parameters must come from the same setup.
+//
+// Returns [`Halo2Status::Ok`] if every proof is valid, and
+// [`Halo2Status::VerificationFailed`] if any proof is invalid or cannot be
+// read. In both cases, `results[i]` is set to 1 if the `i`-th proof is valid
+// and to 0 otherwise. `results` is left unchanged if any other status is
+// returned.
+//
+// # Safety
+//
+// `entries` must point to `num_entries` entries whose handles are live and
+// whose arrays are readable, and `results` must point to `num_entries`
+// writable bytes.
+Halo2Status halo2_verify_batch(const Halo2BatchEntry *entries,
+                               size_t num_entries,
+                               uint8_t *results);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif // HALO2_PROOFS_H
@@ -1,8 +1,11 @@
 //! This module provides common utilities, traits and structures for group,
 //! field and polynomial arithmetic.
 
-use std::ops::Mul;
+use alloc::vec::Vec;
+use core::ops::Mul;
+#[cfg(feature = "std")]
 use std::sync::Arc;
+#[cfg(feature = "std")]
 use std::sync::Mutex;
 
 use super::multicore;
@@ -15,6 +18,7 @@ use group::ff::PrimeField;
 use group::Group as _;
 pub use pairing::arithmetic::*;
 use pairing::bn256::G1Affine;
+#[cfg(feature = "std")]
 use rayon::prelude::*;
 
 fn multiexp_serial<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C], acc: &mut C::Curve) {
@@ -25,7 +29,15 @@ fn multiexp_serial<C: CurveAffine>(coeffs: &[C::Scalar], bases: &[C], acc: &mut
     } else if bases.len() < 32 {
         3
     } else {
-        (f64::from(bases.len() as u32)).ln().ceil() as usize
+        // ceil(ln(n)), without `f64::ln`, which needs `std`.
+        let n = f64::from(bases.len() as u32);
+        let mut c: usize = 0;
+        let mut e_c = 1.0;
+        while e_c < n {
+            e_c *= core::f64::consts::E;
+            c += 1;
+        }
+        c
     };
 
     fn get_at<F: PrimeField>(segment: usize, c: usize, bytes: &F::Repr) -> usize {
@@ -547,12 +559,15 @@ pub fn best_fft<G: Group>(a: &mut [G], omega: G::Scalar, log_n: u32) {
     cfg_if::cfg_if! {
         if #[cfg(feature = "cuda")]{
             return gpu_fft(a, omega, log_n);
-        } else {
+        } else if #[cfg(feature = "std")] {
             return best_fft_cpu(a, omega, log_n);
+        } else {
+            return best_fft_cpu_st(a, omega, log_n);
         }
     }
 }
 
+#[cfg(feature = "std")]
 pub fn best_fft_cpu<G: Group>(a: &mut [G], omega: G::Scalar, log_n: u32) {
     fn bitreverse(mut n: usize, l: usize) -> usize {
         let mut r = 0;
@@ -644,6 +659,7 @@ pub fn best_fft_cpu<G: Group>(a: &mut [G], omega: G::Scalar, log_n: u32) {
     }
 }
 
+#[cfg(feature = "std")]
 pub fn recursive_butterfly_arithmetic<G: Group>(
     a: &mut [G],
     n: usize,
@@ -805,6 +821,7 @@ fn log2_floor(num: usize) -> u32 {
     pow
 }
 
+#[cfg(feature = "std")]
 pub fn mul_acc<F: FieldExt>(f: &mut [F]) {
     let num_threads = multicore::current_num_threads();
     let len = f.len();
@@ -884,13 +901,13 @@ pub fn lagrange_interpolate<F: FieldExt>(points: &[F], evals: &[F]) -> Vec<F> {
                 product.resize(tmp.len() + 1, F::zero());
                 for ((a, b), product) in tmp
                     .iter()
-                    .chain(std::iter::once(&F::zero()))
-                    .zip(std::iter::once(&F::zero()).chain(tmp.iter()))
+                    .chain(core::iter::once(&F::zero()))
+                    .zip(core::iter::once(&F::zero()).chain(tmp.iter()))
                     .zip(product.iter_mut())
                 {
                     *product = *a * (-denom * x_k) + *b * denom;
                 }
-                std::mem::swap(&mut tmp, &mut product);
+                core::mem::swap(&mut tmp, &mut product);
             }
             assert_eq!(tmp.len(), points.len());
             assert_eq!(product.len(), points.len() - 1);
@@ -927,6 +944,7 @@ use rand_core::OsRng;
 
 #[cfg(test)]
 use pairing::bn256::Fr as Fp;
+#[cfg(test)]
 use rayon::prelude::IntoParallelRefIterator;
 
 #[test]
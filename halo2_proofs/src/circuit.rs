@@ -1,6 +1,7 @@
 //! Traits and structs for implementing circuit components.
 
-use std::{convert::TryInto, fmt, marker::PhantomData};
+use alloc::{string::String, vec::Vec};
+use core::{convert::TryInto, fmt, marker::PhantomData};
 
 use ff::Field;
 
@@ -9,7 +10,10 @@ use crate::{
     plonk::{Advice, Any, Assigned, Column, Error, Fixed, Instance, Selector, TableColumn},
 };
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod floor_planner;
+#[cfg(feature = "std")]
 pub use floor_planner::single_pass::SimpleFloorPlanner;
 
 pub mod layouter;
@@ -55,7 +59,7 @@ impl From<usize> for RegionIndex {
     }
 }
 
-impl std::ops::Deref for RegionIndex {
+impl core::ops::Deref for RegionIndex {
     type Target = usize;
 
     fn deref(&self) -> &Self::Target {
@@ -73,7 +77,7 @@ impl From<usize> for RegionStart {
     }
 }
 
-impl std::ops::Deref for RegionStart {
+impl core::ops::Deref for RegionStart {
     type Target = usize;
 
     fn deref(&self) -> &Self::Target {
@@ -92,6 +96,38 @@ pub struct Cell {
     pub column: Column<Any>,
 }
 
+/// Consecutive cells of a column, assigned by [`Region::assign_advice_slice`] or
+/// [`Region::assign_fixed_slice`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellSlice {
+    start: Cell,
+    len: usize,
+}
+
+impl CellSlice {
+    /// Returns the number of cells.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the cell at `index`, or `None` if it is out of range.
+    pub fn get(&self, index: usize) -> Option<Cell> {
+        if index < self.len {
+            Some(Cell {
+                row_offset: self.start.row_offset + index,
+                ..self.start
+            })
+        } else {
+            None
+        }
+    }
+}
+
 /// An assigned cell.
 #[derive(Clone, Debug)]
 pub struct AssignedCell<V, F: Field> {
@@ -317,6 +353,61 @@ impl<'r, F: Field> Region<'r, F> {
         })
     }
 
+    /// Assigns `values` to the rows of an advice `column` starting at `offset`.
+    ///
+    /// This is equivalent to calling [`Region::assign_advice`] for each value, but
+    /// the whole slice is passed down to the backend at once, without annotations
+    /// or closures.
+    pub fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<CellSlice, Error> {
+        let start = self.region.assign_advice_slice(column, offset, values)?;
+
+        Ok(CellSlice {
+            start,
+            len: values.len(),
+        })
+    }
+
+    /// Assigns `values` to the rows of a fixed `column` starting at `offset`.
+    ///
+    /// This is equivalent to calling [`Region::assign_fixed`] for each value, but
+    /// the whole slice is passed down to the backend at once, without annotations
+    /// or closures.
+    pub fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<CellSlice, Error> {
+        let start = self.region.assign_fixed_slice(column, offset, values)?;
+
+        Ok(CellSlice {
+            start,
+            len: values.len(),
+        })
+    }
+
+    /// Returns the value assigned to the advice `column` at `offset` within this
+    /// region, if it is known.
+    ///
+    /// The value is not known while the layouter is measuring the region, or when the
+    /// circuit is synthesized without witnesses.
+    pub fn query_advice(&self, column: Column<Advice>, offset: usize) -> Result<Option<F>, Error> {
+        self.region.query_advice(column, offset)
+    }
+
+    /// Returns the value assigned to the fixed `column` at `offset` within this
+    /// region, if it is known.
+    ///
+    /// The value is not known while the layouter is measuring the region.
+    pub fn query_fixed(&self, column: Column<Fixed>, offset: usize) -> Result<Option<F>, Error> {
+        self.region.query_fixed(column, offset)
+    }
+
     /// Constrains a cell to have a constant value.
     ///
     /// Returns an error if the cell is in a column where equality has not been enabled.
@@ -402,6 +493,43 @@ pub trait Layouter<F: Field> {
         N: Fn() -> NR,
         NR: Into<String>;
 
+    /// Assigns a batch of independent regions, one for each closure in
+    /// `assignments`, and returns their results in order.
+    ///
+    /// The layout is the same as assigning each region in turn with
+    /// [`Layouter::assign_region`], but the built-in layouters synthesize the
+    /// regions of the batch in parallel and only then place them and merge their
+    /// assignments and copy constraints, in order. For this to work, the regions of
+    /// a batch cannot refer to each other's cells, and cannot use
+    /// [`Region::assign_advice_from_instance`]: the instance values are not known
+    /// until the regions are placed, so it fails with [`Error::Synthesis`].
+    ///
+    /// The default implementation assigns the regions sequentially.
+    ///
+    /// ```ignore
+    /// let regions: Vec<_> = chunks
+    ///     .iter()
+    ///     .map(|chunk| move |mut region: Region<'_, F>| chip.assign_chunk(&mut region, chunk))
+    ///     .collect();
+    /// let cells = layouter.assign_regions(|| "chunks", &regions)?;
+    /// ```
+    fn assign_regions<A, AR, N, NR>(
+        &mut self,
+        name: N,
+        assignments: &[A],
+    ) -> Result<Vec<AR>, Error>
+    where
+        A: Fn(Region<'_, F>) -> Result<AR, Error> + Sync,
+        AR: Send,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        assignments
+            .iter()
+            .map(|assignment| self.assign_region(&name, assignment))
+            .collect()
+    }
+
     /// Assign a table region to an absolute row number.
     ///
     /// ```ignore
@@ -472,6 +600,20 @@ impl<'a, F: Field, L: Layouter<F> + 'a> Layouter<F> for NamespacedLayouter<'a, F
         self.0.assign_region(name, assignment)
     }
 
+    fn assign_regions<A, AR, N, NR>(
+        &mut self,
+        name: N,
+        assignments: &[A],
+    ) -> Result<Vec<AR>, Error>
+    where
+        A: Fn(Region<'_, F>) -> Result<AR, Error> + Sync,
+        AR: Send,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        self.0.assign_regions(name, assignments)
+    }
+
     fn assign_table<A, N, NR>(&mut self, name: N, assignment: A) -> Result<(), Error>
     where
         A: FnMut(Table<'_, F>) -> Result<(), Error>,
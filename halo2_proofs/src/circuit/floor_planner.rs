@@ -2,5 +2,8 @@
 
 pub(super) mod single_pass;
 
+mod packed;
+pub use packed::{Packed, PackingReport};
+
 mod v1;
 pub use v1::{V1Pass, V1};
@@ -0,0 +1,324 @@
+use std::cmp;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use ff::Field;
+
+use super::v1::{strategy, synthesize_planned};
+use crate::plonk::{
+    Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+    FloorPlanner, Instance, Selector,
+};
+
+/// A [`FloorPlanner`] that packs the regions of a circuit into as few rows as it can.
+///
+/// - Like [`V1`], a dual-pass layouter is used to measure regions prior to assignment,
+///   and regions are measured as rectangles, bounded on the cells they assign.
+/// - The regions are laid out in several orders with a greedy first-fit strategy, and
+///   the layout using the fewest rows is kept. If `SEARCH_STEPS` is not zero, it is
+///   then improved by trying that many swaps of two regions in the layout order. The
+///   search is bounded by steps rather than by time, so that key generation and
+///   proving always agree on the layout.
+/// - Constants are assigned in the rows left free by the regions in the constant
+///   columns, and in the rows after the regions if they do not fit there.
+///
+/// [`Packed::report`] shows how well a circuit is packed.
+///
+/// [`V1`]: super::V1
+#[derive(Debug)]
+pub struct Packed<const SEARCH_STEPS: usize = 0>;
+
+impl<const SEARCH_STEPS: usize> FloorPlanner for Packed<SEARCH_STEPS> {
+    fn synthesize<F: Field, CS: Assignment<F>, C: Circuit<F>>(
+        cs: &mut CS,
+        circuit: &C,
+        config: C::Config,
+        constants: Vec<Column<Fixed>>,
+    ) -> Result<(), Error> {
+        synthesize_planned(
+            cs,
+            circuit,
+            config,
+            constants,
+            |region_shapes| strategy::pack_regions(region_shapes, SEARCH_STEPS),
+            true,
+        )
+    }
+}
+
+impl<const SEARCH_STEPS: usize> Packed<SEARCH_STEPS> {
+    /// Lays out `circuit` with this floor planner, whichever floor planner the circuit
+    /// uses, and reports the rows it uses.
+    pub fn report<F: Field, C: Circuit<F>>(circuit: &C) -> Result<PackingReport, Error> {
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+
+        let mut usage = Usage::default();
+        Self::synthesize(&mut usage, circuit, config, cs.constants.clone())?;
+
+        Ok(PackingReport {
+            rows: usage.rows,
+            used_rows: usage
+                .columns
+                .into_iter()
+                .map(|(column, rows)| (column, rows.len()))
+                .collect(),
+        })
+    }
+}
+
+/// The rows used by a circuit, as laid out by [`Packed::report`].
+///
+/// Selectors are not included, as they are only turned into fixed columns by key
+/// generation.
+#[derive(Clone, Debug)]
+pub struct PackingReport {
+    rows: usize,
+    used_rows: BTreeMap<Column<Any>, usize>,
+}
+
+impl PackingReport {
+    /// Returns the number of rows used by the regions, constants and tables of the
+    /// circuit.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of rows assigned in `column`.
+    pub fn used_rows(&self, column: Column<Any>) -> usize {
+        self.used_rows.get(&column).cloned().unwrap_or(0)
+    }
+
+    /// Returns the fraction of the used rows that are assigned in `column`.
+    pub fn utilisation(&self, column: Column<Any>) -> f64 {
+        if self.rows == 0 {
+            0.0
+        } else {
+            self.used_rows(column) as f64 / self.rows as f64
+        }
+    }
+
+    /// Returns the utilisation of every assigned column, in column order.
+    pub fn column_utilisation(&self) -> impl Iterator<Item = (Column<Any>, f64)> + '_ {
+        self.used_rows
+            .keys()
+            .map(move |column| (*column, self.utilisation(*column)))
+    }
+}
+
+/// Records the rows assigned by a circuit.
+#[derive(Default)]
+struct Usage {
+    rows: usize,
+    columns: HashMap<Column<Any>, HashSet<usize>>,
+}
+
+impl Usage {
+    fn assign(&mut self, column: Column<Any>, rows: impl Iterator<Item = usize>) {
+        let used = self.columns.entry(column).or_default();
+        for row in rows {
+            used.insert(row);
+            self.rows = cmp::max(self.rows, row + 1);
+        }
+    }
+}
+
+impl<F: Field> Assignment<F> for Usage {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; we don't care about regions in this context.
+    }
+
+    fn exit_region(&mut self) {
+        // Do nothing; we don't care about regions in this context.
+    }
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.rows = cmp::max(self.rows, row + 1);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Option<F>, Error> {
+        Ok(None)
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Result<VR, Error>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.assign(column.into(), row..row + 1);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Result<VR, Error>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.assign(column.into(), row..row + 1);
+        Ok(())
+    }
+
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        row: usize,
+        values: &[F],
+    ) -> Result<(), Error> {
+        self.assign(column.into(), row..row + values.len());
+        Ok(())
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        values: &[F],
+    ) -> Result<(), Error> {
+        self.assign(column.into(), row..row + values.len());
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Option<Assigned<F>>,
+    ) -> Result<(), Error> {
+        // The rows of a table after its last entry only repeat its first one.
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pairing::bn256::Fr as Scalar;
+
+    use super::Packed;
+    use crate::{
+        circuit::Layouter,
+        dev::MockProver,
+        plonk::{Advice, Any, Circuit, Column, ConstraintSystem, Error, FloorPlanner},
+    };
+
+    #[derive(Clone)]
+    struct HolesConfig {
+        advice: [Column<Advice>; 3],
+    }
+
+    /// Two regions spanning two columns each, and two taller regions in one column.
+    struct HolesCircuit<FP>(std::marker::PhantomData<FP>);
+
+    impl<FP: FloorPlanner> Circuit<Scalar> for HolesCircuit<FP> {
+        type Config = HolesConfig;
+        type FloorPlanner = FP;
+
+        fn without_witnesses(&self) -> Self {
+            HolesCircuit(std::marker::PhantomData)
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Scalar>) -> Self::Config {
+            let advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let constants = meta.fixed_column();
+            meta.enable_equality(advice[0]);
+            meta.enable_constant(constants);
+
+            HolesConfig { advice }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Scalar>,
+        ) -> Result<(), Error> {
+            let regions: Vec<(&[usize], usize)> =
+                vec![(&[0, 1], 4), (&[1, 2], 4), (&[0], 5), (&[2], 5)];
+            for (columns, rows) in regions {
+                layouter.assign_region(
+                    || "region",
+                    |mut region| {
+                        for column in columns {
+                            for row in 0..rows {
+                                let column = config.advice[*column];
+                                let value = Scalar::from(row as u64);
+                                if columns == [0] && row == 0 {
+                                    region.assign_advice_from_constant(
+                                        || "cell",
+                                        column,
+                                        row,
+                                        value,
+                                    )?;
+                                } else {
+                                    region.assign_advice(|| "cell", column, row, || Ok(value))?;
+                                }
+                            }
+                        }
+                        Ok(())
+                    },
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn packs_regions() {
+        let circuit = HolesCircuit::<Packed<100>>(std::marker::PhantomData);
+        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let column = |index, column_type| Column::<Any>::new(index, column_type);
+        let report = Packed::<100>::report(&circuit).unwrap();
+        assert_eq!(report.rows(), 9);
+        assert_eq!(report.used_rows(column(0, Any::Advice)), 9);
+        assert_eq!(report.used_rows(column(1, Any::Advice)), 8);
+        assert_eq!(report.used_rows(column(0, Any::Fixed)), 1);
+        assert_eq!(report.column_utilisation().count(), 4);
+
+        // Without the search, every sort order leaves holes next to the wide regions.
+        assert_eq!(Packed::<0>::report(&circuit).unwrap().rows(), 13);
+    }
+}
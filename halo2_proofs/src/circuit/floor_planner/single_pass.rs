@@ -7,7 +7,9 @@ use ff::Field;
 
 use crate::{
     circuit::{
-        layouter::{RegionColumn, RegionLayouter, RegionShape, TableLayouter},
+        layouter::{
+            assign_regions_in_parallel, RegionColumn, RegionLayouter, RegionShape, TableLayouter,
+        },
         Cell, Layouter, Region, RegionIndex, RegionStart, Table,
     },
     plonk::{
@@ -146,6 +148,21 @@ impl<'a, F: Field, CS: Assignment<F> + 'a> Layouter<F> for SingleChipLayouter<'a
         Ok(result)
     }
 
+    fn assign_regions<A, AR, N, NR>(
+        &mut self,
+        name: N,
+        assignments: &[A],
+    ) -> Result<Vec<AR>, Error>
+    where
+        A: Fn(Region<'_, F>) -> Result<AR, Error> + Sync,
+        AR: Send,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        let first_region = self.regions.len();
+        assign_regions_in_parallel(self, first_region, name, assignments)
+    }
+
     fn assign_table<A, N, NR>(&mut self, name: N, mut assignment: A) -> Result<(), Error>
     where
         A: FnMut(Table<'_, F>) -> Result<(), Error>,
@@ -354,6 +371,56 @@ impl<'r, 'a, F: Field, CS: Assignment<F> + 'a> RegionLayouter<F>
         })
     }
 
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        self.layouter.cs.assign_advice_slice(
+            column,
+            *self.layouter.regions[*self.region_index] + offset,
+            values,
+        )?;
+
+        Ok(Cell {
+            region_index: self.region_index,
+            row_offset: offset,
+            column: column.into(),
+        })
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        self.layouter.cs.assign_fixed_slice(
+            column,
+            *self.layouter.regions[*self.region_index] + offset,
+            values,
+        )?;
+
+        Ok(Cell {
+            region_index: self.region_index,
+            row_offset: offset,
+            column: column.into(),
+        })
+    }
+
+    fn query_advice(&self, column: Column<Advice>, offset: usize) -> Result<Option<F>, Error> {
+        self.layouter
+            .cs
+            .query_advice(column, *self.layouter.regions[*self.region_index] + offset)
+    }
+
+    fn query_fixed(&self, column: Column<Fixed>, offset: usize) -> Result<Option<F>, Error> {
+        self.layouter
+            .cs
+            .query_fixed(column, *self.layouter.regions[*self.region_index] + offset)
+    }
+
     fn constrain_constant(&mut self, cell: Cell, constant: Assigned<F>) -> Result<(), Error> {
         self.constants.push((constant, cell));
         Ok(())
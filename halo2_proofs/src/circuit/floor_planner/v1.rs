@@ -5,7 +5,9 @@ use ff::Field;
 use crate::{
     circuit::{
         floor_planner::single_pass::SimpleTableLayouter,
-        layouter::{RegionColumn, RegionLayouter, RegionShape, TableLayouter},
+        layouter::{
+            assign_regions_in_parallel, RegionColumn, RegionLayouter, RegionShape, TableLayouter,
+        },
         Cell, Layouter, Region, RegionIndex, RegionStart, Table,
     },
     plonk::{
@@ -14,7 +16,7 @@ use crate::{
     },
 };
 
-mod strategy;
+pub(super) mod strategy;
 
 /// The version 1 [`FloorPlanner`] provided by `halo2`.
 ///
@@ -63,81 +65,125 @@ impl FloorPlanner for V1 {
         config: C::Config,
         constants: Vec<Column<Fixed>>,
     ) -> Result<(), Error> {
-        let mut plan = V1Plan::new(cs)?;
-
-        // First pass: measure the regions within the circuit.
-        let mut measure = MeasurementPass::new();
-        {
-            let pass = &mut measure;
-            circuit
-                .without_witnesses()
-                .synthesize(config.clone(), V1Pass::<_, CS>::measure(pass))?;
-        }
+        synthesize_planned(
+            cs,
+            circuit,
+            config,
+            constants,
+            strategy::slot_in_biggest_advice_first,
+            false,
+        )
+    }
+}
 
-        // Planning:
-        // - Position the regions.
-        let (regions, column_allocations) = strategy::slot_in_biggest_advice_first(measure.regions);
-        plan.regions = regions;
+/// Synthesizes `circuit` with the dual-pass [`V1`] layouter, positioning the measured
+/// regions with `plan_regions`.
+///
+/// Constants are assigned in the free rows of the `constants` columns within the rows
+/// used by the regions. If `extend_for_constants` is set, the constants that do not fit
+/// there are assigned in the rows after them, instead of failing with
+/// [`Error::NotEnoughColumnsForConstants`].
+pub(super) fn synthesize_planned<F, CS, C, P>(
+    cs: &mut CS,
+    circuit: &C,
+    config: C::Config,
+    constants: Vec<Column<Fixed>>,
+    plan_regions: P,
+    extend_for_constants: bool,
+) -> Result<(), Error>
+where
+    F: Field,
+    CS: Assignment<F>,
+    C: Circuit<F>,
+    P: FnOnce(Vec<RegionShape>) -> (Vec<RegionStart>, strategy::CircuitAllocations),
+{
+    let mut plan = V1Plan::new(cs)?;
+
+    // First pass: measure the regions within the circuit.
+    let mut measure = MeasurementPass::new();
+    {
+        let pass = &mut measure;
+        circuit
+            .without_witnesses()
+            .synthesize(config.clone(), V1Pass::<_, CS>::measure(pass))?;
+    }
 
-        // - Determine how many rows our planned circuit will require.
-        let first_unassigned_row = column_allocations
+    // Planning:
+    // - Position the regions.
+    let (regions, column_allocations) = plan_regions(measure.regions);
+    plan.regions = regions;
+
+    // - Determine how many rows our planned circuit will require.
+    let first_unassigned_row = column_allocations
+        .iter()
+        .map(|(_, a)| a.unbounded_interval_start())
+        .max()
+        .unwrap_or(0);
+
+    // - Position the constants within those rows, and after them if allowed.
+    let fixed_allocations: Vec<_> = constants
+        .into_iter()
+        .map(|c| {
+            (
+                c,
+                column_allocations
+                    .get(&Column::<Any>::from(c).into())
+                    .cloned()
+                    .unwrap_or_default(),
+            )
+        })
+        .collect();
+    let fixed_allocations = &fixed_allocations;
+    let last_constant_row = if extend_for_constants && !fixed_allocations.is_empty() {
+        usize::MAX
+    } else {
+        first_unassigned_row
+    };
+    let constant_positions = || {
+        fixed_allocations
             .iter()
-            .map(|(_, a)| a.unbounded_interval_start())
-            .max()
-            .unwrap_or(0);
-
-        // - Position the constants within those rows.
-        let fixed_allocations: Vec<_> = constants
-            .into_iter()
-            .map(|c| {
-                (
-                    c,
-                    column_allocations
-                        .get(&Column::<Any>::from(c).into())
-                        .cloned()
-                        .unwrap_or_default(),
-                )
-            })
-            .collect();
-        let constant_positions = || {
-            fixed_allocations.iter().flat_map(|(c, a)| {
+            .flat_map(|(c, a)| {
                 let c = *c;
                 a.free_intervals(0, Some(first_unassigned_row))
                     .flat_map(move |e| e.range().unwrap().map(move |i| (c, i)))
             })
-        };
-
-        // Second pass:
-        // - Assign the regions.
-        let mut assign = AssignmentPass::new(&mut plan);
-        {
-            let pass = &mut assign;
-            circuit.synthesize(config, V1Pass::assign(pass))?;
-        }
+            .chain(
+                (first_unassigned_row..last_constant_row)
+                    .flat_map(move |i| fixed_allocations.iter().map(move |(c, _)| (*c, i))),
+            )
+    };
 
-        // - Assign the constants.
-        if constant_positions().count() < plan.constants.len() {
-            return Err(Error::NotEnoughColumnsForConstants);
-        }
-        for ((fixed_column, fixed_row), (value, advice)) in
-            constant_positions().zip(plan.constants.into_iter())
-        {
-            plan.cs.assign_fixed(
-                || format!("Constant({:?})", value.evaluate()),
-                fixed_column,
-                fixed_row,
-                || Ok(value),
-            )?;
-            plan.cs.copy(
-                fixed_column.into(),
-                fixed_row,
-                advice.column,
-                *plan.regions[*advice.region_index] + advice.row_offset,
-            )?;
-        }
+    // Second pass:
+    // - Assign the regions.
+    let mut assign = AssignmentPass::new(&mut plan);
+    {
+        let pass = &mut assign;
+        circuit.synthesize(config, V1Pass::assign(pass))?;
+    }
 
-        Ok(())
+    // - Assign the constants.
+    let constants_count = plan.constants.len();
+    if constant_positions().take(constants_count).count() < constants_count {
+        return Err(Error::NotEnoughColumnsForConstants);
     }
+    for ((fixed_column, fixed_row), (value, advice)) in
+        constant_positions().zip(plan.constants.into_iter())
+    {
+        plan.cs.assign_fixed(
+            || format!("Constant({:?})", value.evaluate()),
+            fixed_column,
+            fixed_row,
+            || Ok(value),
+        )?;
+        plan.cs.copy(
+            fixed_column.into(),
+            fixed_row,
+            advice.column,
+            *plan.regions[*advice.region_index] + advice.row_offset,
+        )?;
+    }
+
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -175,6 +221,31 @@ impl<'p, 'a, F: Field, CS: Assignment<F> + 'a> Layouter<F> for V1Pass<'p, 'a, F,
         }
     }
 
+    fn assign_regions<A, AR, N, NR>(
+        &mut self,
+        name: N,
+        assignments: &[A],
+    ) -> Result<Vec<AR>, Error>
+    where
+        A: Fn(Region<'_, F>) -> Result<AR, Error> + Sync,
+        AR: Send,
+        N: Fn() -> NR,
+        NR: Into<String>,
+    {
+        match &mut self.0 {
+            // Measuring a region does not evaluate its witnesses, so there is nothing
+            // to gain from doing it in parallel.
+            Pass::Measurement(pass) => assignments
+                .iter()
+                .map(|assignment| pass.assign_region(assignment))
+                .collect(),
+            Pass::Assignment(pass) => {
+                let first_region = pass.region_index;
+                assign_regions_in_parallel(self, first_region, name, assignments)
+            }
+        }
+    }
+
     fn assign_table<A, N, NR>(&mut self, name: N, assignment: A) -> Result<(), Error>
     where
         A: FnMut(Table<'_, F>) -> Result<(), Error>,
@@ -470,6 +541,56 @@ impl<'r, 'a, F: Field, CS: Assignment<F> + 'a> RegionLayouter<F> for V1Region<'r
         })
     }
 
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        self.plan.cs.assign_advice_slice(
+            column,
+            *self.plan.regions[*self.region_index] + offset,
+            values,
+        )?;
+
+        Ok(Cell {
+            region_index: self.region_index,
+            row_offset: offset,
+            column: column.into(),
+        })
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        self.plan.cs.assign_fixed_slice(
+            column,
+            *self.plan.regions[*self.region_index] + offset,
+            values,
+        )?;
+
+        Ok(Cell {
+            region_index: self.region_index,
+            row_offset: offset,
+            column: column.into(),
+        })
+    }
+
+    fn query_advice(&self, column: Column<Advice>, offset: usize) -> Result<Option<F>, Error> {
+        self.plan
+            .cs
+            .query_advice(column, *self.plan.regions[*self.region_index] + offset)
+    }
+
+    fn query_fixed(&self, column: Column<Fixed>, offset: usize) -> Result<Option<F>, Error> {
+        self.plan
+            .cs
+            .query_fixed(column, *self.plan.regions[*self.region_index] + offset)
+    }
+
     fn constrain_constant(&mut self, cell: Cell, constant: Assigned<F>) -> Result<(), Error> {
         self.plan.constants.push((constant, cell));
         Ok(())
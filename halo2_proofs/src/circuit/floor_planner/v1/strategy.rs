@@ -224,6 +224,101 @@ pub fn slot_in_biggest_advice_first(
     (regions, column_allocations)
 }
 
+/// Returns the number of rows used by `column_allocations`, and the total number of
+/// rows spanned by its columns, which breaks ties between layouts of the same height.
+fn layout_cost(column_allocations: &CircuitAllocations) -> (usize, usize) {
+    column_allocations
+        .values()
+        .map(Allocations::unbounded_interval_start)
+        .fold((0, 0), |(rows, total), end| (cmp::max(rows, end), total + end))
+}
+
+/// Lays out the regions in the given order with the [`slot_in`] strategy.
+fn slot_in_ordered(
+    region_shapes: &[RegionShape],
+    order: &[usize],
+) -> (Vec<(RegionStart, RegionShape)>, CircuitAllocations) {
+    slot_in(order.iter().map(|i| region_shapes[*i].clone()).collect())
+}
+
+/// Packs the regions into as few rows as possible.
+///
+/// The regions are laid out with the [`slot_in`] strategy in several orders (by
+/// advice area, as [`slot_in_biggest_advice_first`] does, by height, by area and by
+/// width), and the order using the fewest rows is kept. It is then improved by a
+/// local search that tries `search_steps` swaps of two regions, keeping each swap
+/// that does not make the layout worse.
+///
+/// The search is bounded by a number of steps rather than by time so that every
+/// synthesis of a circuit, from key generation to proving, gets the same layout.
+pub fn pack_regions(
+    region_shapes: Vec<RegionShape>,
+    search_steps: usize,
+) -> (Vec<RegionStart>, CircuitAllocations) {
+    let advice_cols = |shape: &RegionShape| {
+        shape
+            .columns()
+            .iter()
+            .filter(|c| match c {
+                RegionColumn::Column(c) => matches!(c.column_type(), Any::Advice),
+                _ => false,
+            })
+            .count()
+    };
+    let sort_keys: [&dyn Fn(&RegionShape) -> (usize, usize); 4] = [
+        &|shape| (advice_cols(shape) * shape.row_count(), 0),
+        &|shape| (shape.row_count(), shape.columns().len()),
+        &|shape| (shape.columns().len() * shape.row_count(), 0),
+        &|shape| (shape.columns().len(), shape.row_count()),
+    ];
+
+    let mut best: Option<((usize, usize), Vec<usize>)> = None;
+    for sort_key in sort_keys.iter() {
+        // Sort the regions by decreasing key, keeping the original order of equal
+        // regions for determinism.
+        let mut order: Vec<_> = (0..region_shapes.len()).collect();
+        order.sort_by_key(|i| cmp::Reverse(sort_key(&region_shapes[*i])));
+
+        let cost = layout_cost(&slot_in_ordered(&region_shapes, &order).1);
+        if best.as_ref().map(|(best, _)| cost < *best).unwrap_or(true) {
+            best = Some((cost, order));
+        }
+    }
+    let (mut best_cost, mut order) = best.expect("there is at least one sort key");
+
+    // A fixed xorshift generator picks the swaps, so the search is deterministic.
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut next = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+    if order.len() > 1 {
+        for _ in 0..search_steps {
+            let (a, b) = (next(order.len()), next(order.len()));
+            if a == b {
+                continue;
+            }
+            order.swap(a, b);
+            let cost = layout_cost(&slot_in_ordered(&region_shapes, &order).1);
+            if cost <= best_cost {
+                best_cost = cost;
+            } else {
+                order.swap(a, b);
+            }
+        }
+    }
+
+    let (mut regions, column_allocations) = slot_in_ordered(&region_shapes, &order);
+
+    // Un-sort the regions so they match the original indexing.
+    regions.sort_unstable_by_key(|(_, region)| region.region_index().0);
+    let regions = regions.into_iter().map(|(start, _)| start).collect();
+
+    (regions, column_allocations)
+}
+
 #[test]
 fn test_slot_in() {
     use crate::plonk::Column;
@@ -263,3 +358,41 @@ fn test_slot_in() {
         vec![0.into(), 0.into(), 15.into()]
     );
 }
+
+#[test]
+fn test_pack_regions() {
+    use crate::plonk::Column;
+
+    let shape = |region_index: usize, columns: &[usize], row_count| RegionShape {
+        region_index: region_index.into(),
+        columns: columns
+            .iter()
+            .map(|c| Column::new(*c, Any::Advice).into())
+            .collect(),
+        row_count,
+    };
+    // Every sort order places the two wide regions next to each other, leaving holes
+    // next to them that the tall regions do not fit in. Only the search finds the
+    // layout that stacks each wide region on a tall one.
+    let regions = vec![
+        shape(0, &[0, 1], 4),
+        shape(1, &[1, 2], 4),
+        shape(2, &[0], 5),
+        shape(3, &[2], 5),
+    ];
+
+    let rows = |column_allocations: &CircuitAllocations| layout_cost(column_allocations).0;
+    let (_, greedy) = slot_in_biggest_advice_first(regions.clone());
+    let (_, packed) = pack_regions(regions.clone(), 0);
+    assert_eq!(rows(&greedy), 13);
+    assert!(rows(&packed) <= rows(&greedy));
+
+    // The search is deterministic.
+    let (searched_starts, searched) = pack_regions(regions.clone(), 100);
+    assert_eq!(rows(&searched), 9);
+    assert_eq!(
+        searched_starts,
+        vec![0.into(), 5.into(), 4.into(), 0.into()]
+    );
+    assert_eq!(searched_starts, pack_regions(regions, 100).0);
+}
@@ -1,12 +1,16 @@
 //! Implementations of common circuit layouters.
 
-use std::cmp;
+use alloc::{string::String, vec::Vec};
+use core::cmp;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::collections::HashSet;
-use std::fmt;
 
 use ff::Field;
+#[cfg(feature = "std")]
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use super::{Cell, RegionIndex};
+use super::{Cell, Layouter, Region, RegionIndex};
 use crate::plonk::{Advice, Any, Assigned, Column, Error, Fixed, Instance, Selector, TableColumn};
 
 /// Helper trait for implementing a custom [`Layouter`].
@@ -93,6 +97,62 @@ pub trait RegionLayouter<F: Field>: fmt::Debug {
         to: &'v mut (dyn FnMut() -> Result<Assigned<F>, Error> + 'v),
     ) -> Result<Cell, Error>;
 
+    /// Assigns `values` to the rows of an advice column starting at `offset`.
+    ///
+    /// Returns the cell at `offset`. The default implementation assigns the
+    /// cells one by one, and returns `Error::Synthesis` if `values` is empty.
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        let mut start = None;
+        for (i, value) in values.iter().enumerate() {
+            let cell = self.assign_advice(&|| String::new(), column, offset + i, &mut || {
+                Ok((*value).into())
+            })?;
+            start.get_or_insert(cell);
+        }
+        start.ok_or(Error::Synthesis)
+    }
+
+    /// Assigns `values` to the rows of a fixed column starting at `offset`.
+    ///
+    /// Returns the cell at `offset`. The default implementation assigns the
+    /// cells one by one, and returns `Error::Synthesis` if `values` is empty.
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        let mut start = None;
+        for (i, value) in values.iter().enumerate() {
+            let cell = self.assign_fixed(&|| String::new(), column, offset + i, &mut || {
+                Ok((*value).into())
+            })?;
+            start.get_or_insert(cell);
+        }
+        start.ok_or(Error::Synthesis)
+    }
+
+    /// Returns the value assigned to the advice `column` at `offset`, if known.
+    ///
+    /// The default implementation returns `None`, for layouters that do not
+    /// keep the assigned values.
+    fn query_advice(&self, _column: Column<Advice>, _offset: usize) -> Result<Option<F>, Error> {
+        Ok(None)
+    }
+
+    /// Returns the value assigned to the fixed `column` at `offset`, if known.
+    ///
+    /// The default implementation returns `None`, for layouters that do not
+    /// keep the assigned values.
+    fn query_fixed(&self, _column: Column<Fixed>, _offset: usize) -> Result<Option<F>, Error> {
+        Ok(None)
+    }
+
     /// Constrains a cell to have a constant value.
     ///
     /// Returns an error if the cell is in a column where equality has not been enabled.
@@ -124,6 +184,8 @@ pub trait TableLayouter<F: Field>: fmt::Debug {
 
 /// The shape of a region. For a region at a certain index, we track
 /// the set of columns it uses as well as the number of rows it uses.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 #[derive(Clone, Debug)]
 pub struct RegionShape {
     pub(super) region_index: RegionIndex,
@@ -170,6 +232,7 @@ impl PartialOrd for RegionColumn {
     }
 }
 
+#[cfg(feature = "std")]
 impl RegionShape {
     /// Create a new `RegionShape` for a region at `region_index`.
     pub fn new(region_index: RegionIndex) -> Self {
@@ -196,6 +259,7 @@ impl RegionShape {
     }
 }
 
+#[cfg(feature = "std")]
 impl<F: Field> RegionLayouter<F> for RegionShape {
     fn enable_selector<'v>(
         &'v mut self,
@@ -275,6 +339,48 @@ impl<F: Field> RegionLayouter<F> for RegionShape {
         })
     }
 
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        self.columns.insert(Column::<Any>::from(column).into());
+        self.row_count = cmp::max(self.row_count, offset + values.len());
+
+        Ok(Cell {
+            region_index: self.region_index,
+            row_offset: offset,
+            column: column.into(),
+        })
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        self.columns.insert(Column::<Any>::from(column).into());
+        self.row_count = cmp::max(self.row_count, offset + values.len());
+
+        Ok(Cell {
+            region_index: self.region_index,
+            row_offset: offset,
+            column: column.into(),
+        })
+    }
+
+    fn query_advice(&self, _: Column<Advice>, _: usize) -> Result<Option<F>, Error> {
+        // Values are not assigned while measuring the region.
+        Ok(None)
+    }
+
+    fn query_fixed(&self, _: Column<Fixed>, _: usize) -> Result<Option<F>, Error> {
+        // Values are not assigned while measuring the region.
+        Ok(None)
+    }
+
     fn constrain_constant(&mut self, _cell: Cell, _constant: Assigned<F>) -> Result<(), Error> {
         // Global constants don't affect the region shape.
         Ok(())
@@ -285,3 +391,348 @@ impl<F: Field> RegionLayouter<F> for RegionShape {
         Ok(())
     }
 }
+
+/// An operation recorded by a [`RegionRecord`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+enum RegionOp<F: Field> {
+    EnableSelector {
+        annotation: String,
+        selector: Selector,
+        offset: usize,
+    },
+    AssignAdvice {
+        annotation: String,
+        column: Column<Advice>,
+        offset: usize,
+        value: Option<Result<Assigned<F>, Error>>,
+    },
+    AssignFixed {
+        annotation: String,
+        column: Column<Fixed>,
+        offset: usize,
+        value: Option<Result<Assigned<F>, Error>>,
+    },
+    AssignAdviceSlice {
+        column: Column<Advice>,
+        offset: usize,
+        values: Vec<F>,
+    },
+    AssignFixedSlice {
+        column: Column<Fixed>,
+        offset: usize,
+        values: Vec<F>,
+    },
+    ConstrainConstant {
+        cell: Cell,
+        constant: Assigned<F>,
+    },
+    ConstrainEqual {
+        left: Cell,
+        right: Cell,
+    },
+}
+
+/// A region that has been synthesized without access to the constraint system.
+///
+/// The region records its assignments, evaluating the assigned values eagerly, so
+/// that it can be synthesized on any thread and replayed later into the region the
+/// layouter places it in.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub(crate) struct RegionRecord<F: Field> {
+    shape: RegionShape,
+    ops: Vec<RegionOp<F>>,
+}
+
+#[cfg(feature = "std")]
+impl<F: Field> RegionRecord<F> {
+    /// Creates an empty record for the region at `region_index`.
+    pub(crate) fn new(region_index: RegionIndex) -> Self {
+        RegionRecord {
+            shape: RegionShape::new(region_index),
+            ops: vec![],
+        }
+    }
+
+    /// Returns the value last assigned to `column` at `offset`, if any.
+    fn query(&self, column: Column<Any>, offset: usize) -> Option<F> {
+        let value = |value: &Option<Result<Assigned<F>, Error>>| match value {
+            Some(Ok(value)) => Some(value.evaluate()),
+            _ => None,
+        };
+        self.ops.iter().rev().find_map(|op| match op {
+            RegionOp::AssignAdvice {
+                column: c,
+                offset: o,
+                value: v,
+                ..
+            } if Column::<Any>::from(*c) == column && *o == offset => Some(value(v)),
+            RegionOp::AssignFixed {
+                column: c,
+                offset: o,
+                value: v,
+                ..
+            } if Column::<Any>::from(*c) == column && *o == offset => Some(value(v)),
+            RegionOp::AssignAdviceSlice {
+                column: c,
+                offset: o,
+                values,
+            } if Column::<Any>::from(*c) == column
+                && (*o..*o + values.len()).contains(&offset) =>
+            {
+                Some(Some(values[offset - *o]))
+            }
+            RegionOp::AssignFixedSlice {
+                column: c,
+                offset: o,
+                values,
+            } if Column::<Any>::from(*c) == column
+                && (*o..*o + values.len()).contains(&offset) =>
+            {
+                Some(Some(values[offset - *o]))
+            }
+            _ => None,
+        })?
+    }
+
+    /// Replays the recorded assignments into `region`.
+    ///
+    /// The values are moved into `region`, so a record can only be replayed into one
+    /// region that evaluates them.
+    pub(crate) fn replay(&mut self, region: Region<'_, F>) -> Result<(), Error> {
+        let region = region.region;
+        for op in self.ops.iter_mut() {
+            match op {
+                RegionOp::EnableSelector {
+                    annotation,
+                    selector,
+                    offset,
+                } => {
+                    region.enable_selector(&|| annotation.clone(), selector, *offset)?;
+                }
+                RegionOp::AssignAdvice {
+                    annotation,
+                    column,
+                    offset,
+                    value,
+                } => {
+                    region.assign_advice(&|| annotation.clone(), *column, *offset, &mut || {
+                        value.take().unwrap_or(Err(Error::Synthesis))
+                    })?;
+                }
+                RegionOp::AssignFixed {
+                    annotation,
+                    column,
+                    offset,
+                    value,
+                } => {
+                    region.assign_fixed(&|| annotation.clone(), *column, *offset, &mut || {
+                        value.take().unwrap_or(Err(Error::Synthesis))
+                    })?;
+                }
+                RegionOp::AssignAdviceSlice {
+                    column,
+                    offset,
+                    values,
+                } => {
+                    region.assign_advice_slice(*column, *offset, values)?;
+                }
+                RegionOp::AssignFixedSlice {
+                    column,
+                    offset,
+                    values,
+                } => {
+                    region.assign_fixed_slice(*column, *offset, values)?;
+                }
+                RegionOp::ConstrainConstant { cell, constant } => {
+                    region.constrain_constant(*cell, *constant)?;
+                }
+                RegionOp::ConstrainEqual { left, right } => {
+                    region.constrain_equal(*left, *right)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Field> RegionLayouter<F> for RegionRecord<F> {
+    fn enable_selector<'v>(
+        &'v mut self,
+        annotation: &'v (dyn Fn() -> String + 'v),
+        selector: &Selector,
+        offset: usize,
+    ) -> Result<(), Error> {
+        RegionLayouter::<F>::enable_selector(&mut self.shape, annotation, selector, offset)?;
+        self.ops.push(RegionOp::EnableSelector {
+            annotation: annotation(),
+            selector: *selector,
+            offset,
+        });
+        Ok(())
+    }
+
+    fn assign_advice<'v>(
+        &'v mut self,
+        annotation: &'v (dyn Fn() -> String + 'v),
+        column: Column<Advice>,
+        offset: usize,
+        to: &'v mut (dyn FnMut() -> Result<Assigned<F>, Error> + 'v),
+    ) -> Result<Cell, Error> {
+        let cell = RegionLayouter::<F>::assign_advice(
+            &mut self.shape,
+            annotation,
+            column,
+            offset,
+            &mut || Err(Error::Synthesis),
+        )?;
+        self.ops.push(RegionOp::AssignAdvice {
+            annotation: annotation(),
+            column,
+            offset,
+            value: Some(to()),
+        });
+        Ok(cell)
+    }
+
+    fn assign_advice_from_constant<'v>(
+        &'v mut self,
+        annotation: &'v (dyn Fn() -> String + 'v),
+        column: Column<Advice>,
+        offset: usize,
+        constant: Assigned<F>,
+    ) -> Result<Cell, Error> {
+        let advice = self.assign_advice(annotation, column, offset, &mut || Ok(constant))?;
+        self.constrain_constant(advice, constant)?;
+
+        Ok(advice)
+    }
+
+    fn assign_advice_from_instance<'v>(
+        &mut self,
+        _: &'v (dyn Fn() -> String + 'v),
+        _: Column<Instance>,
+        _: usize,
+        _: Column<Advice>,
+        _: usize,
+    ) -> Result<(Cell, Option<F>), Error> {
+        // Instance values cannot be queried without the constraint system.
+        Err(Error::Synthesis)
+    }
+
+    fn assign_fixed<'v>(
+        &'v mut self,
+        annotation: &'v (dyn Fn() -> String + 'v),
+        column: Column<Fixed>,
+        offset: usize,
+        to: &'v mut (dyn FnMut() -> Result<Assigned<F>, Error> + 'v),
+    ) -> Result<Cell, Error> {
+        let cell = RegionLayouter::<F>::assign_fixed(
+            &mut self.shape,
+            annotation,
+            column,
+            offset,
+            &mut || Err(Error::Synthesis),
+        )?;
+        self.ops.push(RegionOp::AssignFixed {
+            annotation: annotation(),
+            column,
+            offset,
+            value: Some(to()),
+        });
+        Ok(cell)
+    }
+
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        let cell = self.shape.assign_advice_slice(column, offset, values)?;
+        self.ops.push(RegionOp::AssignAdviceSlice {
+            column,
+            offset,
+            values: values.to_vec(),
+        });
+        Ok(cell)
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        offset: usize,
+        values: &[F],
+    ) -> Result<Cell, Error> {
+        let cell = self.shape.assign_fixed_slice(column, offset, values)?;
+        self.ops.push(RegionOp::AssignFixedSlice {
+            column,
+            offset,
+            values: values.to_vec(),
+        });
+        Ok(cell)
+    }
+
+    fn query_advice(&self, column: Column<Advice>, offset: usize) -> Result<Option<F>, Error> {
+        Ok(self.query(column.into(), offset))
+    }
+
+    fn query_fixed(&self, column: Column<Fixed>, offset: usize) -> Result<Option<F>, Error> {
+        Ok(self.query(column.into(), offset))
+    }
+
+    fn constrain_constant(&mut self, cell: Cell, constant: Assigned<F>) -> Result<(), Error> {
+        self.ops.push(RegionOp::ConstrainConstant { cell, constant });
+        Ok(())
+    }
+
+    fn constrain_equal(&mut self, left: Cell, right: Cell) -> Result<(), Error> {
+        self.ops.push(RegionOp::ConstrainEqual { left, right });
+        Ok(())
+    }
+}
+
+/// Synthesizes `assignments` in parallel as the regions `first_region`,
+/// `first_region + 1`, ..., and then assigns them through `layouter` in order.
+///
+/// `first_region` must be the index that `layouter` gives to the next region it
+/// assigns, so that the cells returned by the assignments refer to the regions they
+/// are placed in.
+#[cfg(feature = "std")]
+pub(crate) fn assign_regions_in_parallel<F, L, A, AR, N, NR>(
+    layouter: &mut L,
+    first_region: usize,
+    name: N,
+    assignments: &[A],
+) -> Result<Vec<AR>, Error>
+where
+    F: Field,
+    L: Layouter<F>,
+    A: Fn(Region<'_, F>) -> Result<AR, Error> + Sync,
+    AR: Send,
+    N: Fn() -> NR,
+    NR: Into<String>,
+{
+    let records = assignments
+        .par_iter()
+        .enumerate()
+        .map(|(i, assignment)| {
+            let mut record = RegionRecord::new((first_region + i).into());
+            let result = {
+                let region: &mut dyn RegionLayouter<F> = &mut record;
+                assignment(region.into())
+            }?;
+            Ok((record, result))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    records
+        .into_iter()
+        .map(|(mut record, result)| {
+            layouter.assign_region(&name, |region| record.replay(region))?;
+            Ok(result)
+        })
+        .collect()
+}
@@ -10,10 +10,10 @@ use ff::Field;
 
 use crate::plonk::Assigned;
 use crate::{
-    arithmetic::{FieldExt, Group},
+    arithmetic::{CurveAffine, FieldExt, Group},
     plonk::{
         permutation, Advice, Any, Assignment, Circuit, Column, ColumnType, ConstraintSystem, Error,
-        Expression, Fixed, FloorPlanner, Instance, Selector, VirtualCell,
+        Expression, Fixed, FloorPlanner, Instance, ProvingKey, Selector, VirtualCell,
     },
     poly::Rotation,
 };
@@ -131,7 +131,7 @@ pub enum VerifyFailure {
         /// The region in which this cell should be assigned.
         region: metadata::Region,
         /// The column in which this cell should be assigned.
-        column: Column<Any>,
+        column: metadata::Column,
         /// The offset (relative to the start of the region) at which this cell should be
         /// assigned. This may be negative (for example, if a selector enables a gate at
         /// offset 0, but the gate uses `Rotation::prev()`).
@@ -157,7 +157,7 @@ pub enum VerifyFailure {
     /// A lookup input did not exist in its corresponding table.
     Lookup {
         /// The name of the lookup that is not satisfied.
-        name: &'static str,
+        name: String,
         /// The index of the lookup that is not satisfied. These indices are assigned in
         /// the order in which `ConstraintSystem::lookup` is called during
         /// `Circuit::configure`.
@@ -196,7 +196,7 @@ impl fmt::Display for VerifyFailure {
             } => {
                 write!(
                     f,
-                    "{} uses {}, which requires cell in column {:?} at offset {} to be assigned.",
+                    "{} uses {}, which requires cell in column {} at offset {} to be assigned.",
                     region, gate, column, offset
                 )
             }
@@ -232,7 +232,7 @@ impl fmt::Display for VerifyFailure {
             Self::Permutation { column, row } => {
                 write!(
                     f,
-                    "Equality constraint not satisfied by cell ({:?}, {})",
+                    "Equality constraint not satisfied by cell ({}, {})",
                     column, row
                 )
             }
@@ -509,6 +509,35 @@ pub struct MockProver<F: Group + Field> {
     usable_rows: Range<usize>,
 }
 
+impl<F: Field + Group> MockProver<F> {
+    /// Checks that `len` rows of `column` starting at `row` can be assigned, and
+    /// tracks them in the current region. Returns the range of rows.
+    fn assign_slice(
+        &mut self,
+        column: Column<Any>,
+        row: usize,
+        len: usize,
+    ) -> Result<Range<usize>, Error> {
+        let rows = row..row + len;
+        if len == 0 {
+            return Ok(rows);
+        }
+        if !self.usable_rows.contains(&row) || !self.usable_rows.contains(&(rows.end - 1)) {
+            return Err(Error::not_enough_rows_available(self.k));
+        }
+
+        if let Some(region) = self.current_region.as_mut() {
+            region.update_extent(column, row);
+            region.update_extent(column, rows.end - 1);
+            for row in rows.clone() {
+                region.track_cell(column, row);
+            }
+        }
+
+        Ok(rows)
+    }
+}
+
 impl<F: Field + Group> Assignment<F> for MockProver<F> {
     fn enter_region<NR, N>(&mut self, name: N)
     where
@@ -565,6 +594,36 @@ impl<F: Field + Group> Assignment<F> for MockProver<F> {
             .ok_or(Error::BoundsFailure)
     }
 
+    fn query_advice(&self, column: Column<Advice>, row: usize) -> Result<Option<F>, Error> {
+        if !self.usable_rows.contains(&row) {
+            return Err(Error::not_enough_rows_available(self.k));
+        }
+
+        self.advice
+            .get(column.index())
+            .and_then(|column| column.get(row))
+            .map(|cell| match cell {
+                CellValue::Assigned(v) => Some(*v),
+                _ => None,
+            })
+            .ok_or(Error::BoundsFailure)
+    }
+
+    fn query_fixed(&self, column: Column<Fixed>, row: usize) -> Result<Option<F>, Error> {
+        if !self.usable_rows.contains(&row) {
+            return Err(Error::not_enough_rows_available(self.k));
+        }
+
+        self.fixed
+            .get(column.index())
+            .and_then(|column| column.get(row))
+            .map(|cell| match cell {
+                CellValue::Assigned(v) => Some(*v),
+                _ => None,
+            })
+            .ok_or(Error::BoundsFailure)
+    }
+
     fn assign_advice<V, VR, A, AR>(
         &mut self,
         _: A,
@@ -627,6 +686,44 @@ impl<F: Field + Group> Assignment<F> for MockProver<F> {
         Ok(())
     }
 
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        row: usize,
+        values: &[F],
+    ) -> Result<(), Error> {
+        let cells = self.assign_slice(column.into(), row, values.len())?;
+        let cells = self
+            .advice
+            .get_mut(column.index())
+            .and_then(|v| v.get_mut(cells))
+            .ok_or(Error::BoundsFailure)?;
+        for (cell, value) in cells.iter_mut().zip(values.iter()) {
+            *cell = CellValue::Assigned(*value);
+        }
+
+        Ok(())
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        values: &[F],
+    ) -> Result<(), Error> {
+        let cells = self.assign_slice(column.into(), row, values.len())?;
+        let cells = self
+            .fixed
+            .get_mut(column.index())
+            .and_then(|v| v.get_mut(cells))
+            .ok_or(Error::BoundsFailure)?;
+        for (cell, value) in cells.iter_mut().zip(values.iter()) {
+            *cell = CellValue::Assigned(*value);
+        }
+
+        Ok(())
+    }
+
     fn copy(
         &mut self,
         left_column: Column<Any>,
@@ -755,6 +852,87 @@ impl<F: FieldExt> MockProver<F> {
         Ok(prover)
     }
 
+    /// Builds a `MockProver` for the circuit of `pk` from advice columns computed
+    /// without synthesizing the circuit, as passed to [`create_proof_from_columns`].
+    /// Each advice column holds the values of its first rows; the remaining usable
+    /// rows are zero.
+    ///
+    /// No regions are recorded, so failures are reported by row and unassigned
+    /// cells are not detected.
+    ///
+    /// [`create_proof_from_columns`]: crate::plonk::create_proof_from_columns
+    pub fn from_columns<C: CurveAffine<ScalarExt = F>>(
+        pk: &ProvingKey<C>,
+        advice: Vec<Vec<F>>,
+        instance: Vec<Vec<F>>,
+    ) -> Result<Self, Error> {
+        let vk = pk.get_vk();
+        let k = vk.get_domain().k();
+        let n = 1 << k;
+        let cs = vk.cs.clone();
+
+        let blinding_factors = cs.blinding_factors();
+        let usable_rows = n - (blinding_factors + 1);
+
+        if instance.len() != cs.num_instance_columns {
+            return Err(Error::InvalidInstances);
+        }
+        if advice.len() != cs.num_advice_columns {
+            return Err(Error::InvalidAdvice);
+        }
+
+        let instance = instance
+            .into_iter()
+            .map(|mut instance| {
+                if instance.len() > usable_rows {
+                    return Err(Error::InstanceTooLarge);
+                }
+
+                instance.resize(n, F::zero());
+                Ok(instance)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let advice = advice
+            .into_iter()
+            .map(|column| {
+                if column.len() > usable_rows {
+                    return Err(Error::not_enough_rows_available(k));
+                }
+
+                let mut cells: Vec<_> = column.into_iter().map(CellValue::Assigned).collect();
+                cells.resize(usable_rows, CellValue::Assigned(F::zero()));
+                // Poison unusable rows.
+                cells.extend((usable_rows..n).map(CellValue::Poison));
+                Ok(cells)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The selectors of the proving key are already compressed into fixed columns.
+        let fixed = pk
+            .fixed_values()
+            .iter()
+            .map(|column| column.iter().map(|v| CellValue::Assigned(*v)).collect())
+            .collect();
+
+        let mut permutation = permutation::keygen::Assembly::new(n, &cs.permutation);
+        permutation.mapping = pk.permutation_mapping();
+
+        Ok(MockProver {
+            k,
+            n: n as u32,
+            cs,
+            regions: vec![],
+            current_region: None,
+            fixed,
+            advice,
+            instance,
+            selectors: vec![],
+            permutation,
+            usable_rows: 0..usable_rows,
+        })
+    }
+
     /// Returns `Ok(())` if this `MockProver` is satisfied, or a list of errors indicating
     /// the reasons that the circuit is not satisfied.
     pub fn verify(&self) -> Result<(), Vec<VerifyFailure>> {
@@ -817,7 +995,7 @@ impl<F: FieldExt> MockProver<F> {
                                     Some(VerifyFailure::CellNotAssigned {
                                         gate: (gate_index, gate.name()).into(),
                                         region: (r_i, r.name.clone()).into(),
-                                        column: cell.column,
+                                        column: metadata::Column::named(cell.column, &self.cs),
                                         offset: cell_row as isize - r.rows.unwrap().0 as isize,
                                     })
                                 }
@@ -897,6 +1075,7 @@ impl<F: FieldExt> MockProver<F> {
                                         Some(poly).into_iter(),
                                     ),
                                     cell_values: util::cell_values(
+                                        &self.cs,
                                         gate,
                                         poly,
                                         &load(n, row, &self.cs.fixed_queries, &self.fixed),
@@ -993,7 +1172,7 @@ impl<F: FieldExt> MockProver<F> {
                                 None
                             } else {
                                 Some(VerifyFailure::Lookup {
-                                    name: lookup.name,
+                                    name: lookup.name.clone(),
                                     lookup_index,
                                     location: FailureLocation::find_expressions(
                                         &self.cs,
@@ -1037,8 +1216,10 @@ impl<F: FieldExt> MockProver<F> {
                             None
                         } else {
                             Some(VerifyFailure::Permutation {
-                                column: (*self.cs.permutation.get_columns().get(column).unwrap())
-                                    .into(),
+                                column: metadata::Column::named(
+                                    self.cs.permutation.get_columns()[column],
+                                    &self.cs,
+                                ),
                                 row,
                             })
                         }
@@ -1078,7 +1259,7 @@ mod tests {
     use crate::{
         circuit::{Layouter, SimpleFloorPlanner},
         plonk::{
-            Advice, Any, Circuit, Column, ConstraintSystem, Error, Expression, Selector,
+            Advice, Any, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Selector,
             TableColumn,
         },
         poly::Rotation,
@@ -1150,12 +1331,304 @@ mod tests {
             Err(vec![VerifyFailure::CellNotAssigned {
                 gate: (0, "Equality check").into(),
                 region: (0, "Faulty synthesis".to_owned()).into(),
-                column: Column::new(1, Any::Advice),
+                column: (Any::Advice, 1).into(),
                 offset: 1,
             }])
         );
     }
 
+    #[test]
+    fn slice_assignment() {
+        const K: u32 = 4;
+
+        #[derive(Clone)]
+        struct SliceCircuitConfig {
+            a: Column<Advice>,
+            f: Column<Fixed>,
+            q: Selector,
+        }
+
+        struct SliceCircuit {
+            advice: Vec<Fp>,
+        }
+
+        impl Circuit<Fp> for SliceCircuit {
+            type Config = SliceCircuitConfig;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+                let a = meta.advice_column();
+                let f = meta.fixed_column();
+                let q = meta.selector();
+                meta.enable_equality(a);
+
+                meta.create_gate("Equality check", |cells| {
+                    let a = cells.query_advice(a, Rotation::cur());
+                    let f = cells.query_fixed(f, Rotation::cur());
+                    let q = cells.query_selector(q);
+
+                    vec![q * (a - f)]
+                });
+
+                SliceCircuitConfig { a, f, q }
+            }
+
+            fn without_witnesses(&self) -> Self {
+                Self {
+                    advice: self.advice.clone(),
+                }
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<Fp>,
+            ) -> Result<(), Error> {
+                layouter.assign_region(
+                    || "Slices",
+                    |mut region| {
+                        let fixed = [1u64, 2, 1].map(Fp::from);
+                        for offset in 0..fixed.len() {
+                            config.q.enable(&mut region, offset)?;
+                        }
+                        region.assign_fixed_slice(config.f, 0, &fixed)?;
+                        let cells = region.assign_advice_slice(config.a, 0, &self.advice)?;
+                        assert_eq!(cells.len(), 3);
+                        region.constrain_equal(cells.get(0).unwrap(), cells.get(2).unwrap())
+                    },
+                )
+            }
+        }
+
+        let circuit = SliceCircuit {
+            advice: [1u64, 2, 1].map(Fp::from).to_vec(),
+        };
+        let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let circuit = SliceCircuit {
+            advice: [1u64, 3, 1].map(Fp::from).to_vec(),
+        };
+        let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn parallel_regions() {
+        use std::marker::PhantomData;
+
+        use crate::circuit::{floor_planner::V1, AssignedCell, Region};
+        use crate::plonk::FloorPlanner;
+
+        const K: u32 = 5;
+
+        #[derive(Clone)]
+        struct AddConfig {
+            a: Column<Advice>,
+            b: Column<Advice>,
+            c: Column<Advice>,
+            q: Selector,
+        }
+
+        struct AddCircuit<FP> {
+            rows: Vec<(u64, u64)>,
+            parallel: bool,
+            _marker: PhantomData<FP>,
+        }
+
+        impl<FP: FloorPlanner> Circuit<Fp> for AddCircuit<FP> {
+            type Config = AddConfig;
+            type FloorPlanner = FP;
+
+            fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+                let a = meta.advice_column();
+                let b = meta.advice_column();
+                let c = meta.advice_column();
+                let constants = meta.fixed_column();
+                let q = meta.selector();
+                meta.enable_equality(a);
+                meta.enable_equality(c);
+                meta.enable_constant(constants);
+
+                meta.create_gate("Addition", |cells| {
+                    let a = cells.query_advice(a, Rotation::cur());
+                    let b = cells.query_advice(b, Rotation::cur());
+                    let c = cells.query_advice(c, Rotation::cur());
+                    let q = cells.query_selector(q);
+
+                    vec![q * (a + b - c)]
+                });
+
+                AddConfig { a, b, c, q }
+            }
+
+            fn without_witnesses(&self) -> Self {
+                Self {
+                    rows: self.rows.clone(),
+                    parallel: self.parallel,
+                    _marker: PhantomData,
+                }
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<Fp>,
+            ) -> Result<(), Error> {
+                let seed = layouter.assign_region(
+                    || "Seed",
+                    |mut region| {
+                        region.assign_advice_from_constant(|| "seed", config.a, 0, Fp::one())
+                    },
+                )?;
+
+                let config = &config;
+                let seed = seed.cell();
+                let regions: Vec<_> = self
+                    .rows
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| {
+                        move |mut region: Region<'_, Fp>| -> Result<AssignedCell<Fp, Fp>, Error> {
+                            config.q.enable(&mut region, 0)?;
+                            let a =
+                                region.assign_advice(|| "a", config.a, 0, || Ok(Fp::from(x)))?;
+                            region.assign_advice(|| "b", config.b, 0, || Ok(Fp::from(y)))?;
+                            let c = region.assign_advice(
+                                || "c",
+                                config.c,
+                                0,
+                                || Ok(Fp::from(x + y)),
+                            )?;
+                            if i == 0 {
+                                region.constrain_equal(seed, a.cell())?;
+                            }
+                            region.assign_advice_from_constant(
+                                || "index",
+                                config.a,
+                                1,
+                                Fp::from(i as u64),
+                            )?;
+                            Ok(c)
+                        }
+                    })
+                    .collect();
+
+                let sums = if self.parallel {
+                    layouter.assign_regions(|| "Row", &regions)?
+                } else {
+                    regions
+                        .iter()
+                        .map(|region| layouter.assign_region(|| "Row", region))
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+
+                layouter.assign_region(
+                    || "Last",
+                    |mut region| {
+                        let last = sums.last().unwrap();
+                        last.copy_advice(|| "last", &mut region, config.c, 0)?;
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        fn run<FP: FloorPlanner>(parallel: bool) -> MockProver<Fp> {
+            let circuit = AddCircuit::<FP> {
+                rows: vec![(1, 2), (3, 4), (5, 6), (7, 8)],
+                parallel,
+                _marker: PhantomData,
+            };
+            let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+            prover
+        }
+
+        fn assert_same_layout(sequential: MockProver<Fp>, parallel: MockProver<Fp>) {
+            assert_eq!(sequential.advice, parallel.advice);
+            assert_eq!(sequential.fixed, parallel.fixed);
+            assert_eq!(sequential.selectors, parallel.selectors);
+            assert_eq!(sequential.permutation.mapping, parallel.permutation.mapping);
+        }
+
+        assert_same_layout(
+            run::<SimpleFloorPlanner>(false),
+            run::<SimpleFloorPlanner>(true),
+        );
+        assert_same_layout(run::<V1>(false), run::<V1>(true));
+    }
+
+    #[test]
+    fn query_region_values() {
+        use crate::circuit::Region;
+
+        const K: u32 = 4;
+
+        #[derive(Clone)]
+        struct SumConfig {
+            a: Column<Advice>,
+            f: Column<Fixed>,
+            q: Selector,
+        }
+
+        struct SumCircuit {}
+
+        impl Circuit<Fp> for SumCircuit {
+            type Config = SumConfig;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+                let a = meta.advice_column();
+                let f = meta.fixed_column();
+                let q = meta.selector();
+
+                meta.create_gate("Sum", |cells| {
+                    let a_cur = cells.query_advice(a, Rotation::cur());
+                    let a_next = cells.query_advice(a, Rotation::next());
+                    let f = cells.query_fixed(f, Rotation::cur());
+                    let q = cells.query_selector(q);
+
+                    vec![q * (a_cur + f - a_next)]
+                });
+
+                SumConfig { a, f, q }
+            }
+
+            fn without_witnesses(&self) -> Self {
+                Self {}
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<Fp>,
+            ) -> Result<(), Error> {
+                let assign = |mut region: Region<'_, Fp>| -> Result<(), Error> {
+                    config.q.enable(&mut region, 0)?;
+                    region.assign_advice(|| "a", config.a, 0, || Ok(Fp::from(3)))?;
+                    region.assign_fixed(|| "f", config.f, 0, || Ok(Fp::from(5)))?;
+
+                    // Read the values back instead of keeping them around.
+                    let sum = region
+                        .query_advice(config.a, 0)?
+                        .zip(region.query_fixed(config.f, 0)?)
+                        .map(|(a, f)| a + f);
+                    region.assign_advice(|| "sum", config.a, 1, || sum.ok_or(Error::Synthesis))?;
+                    assert_eq!(region.query_advice(config.a, 2)?, None);
+                    Ok(())
+                };
+
+                layouter.assign_region(|| "Sum", assign)?;
+                layouter.assign_regions(|| "Sum", &[assign])?;
+                Ok(())
+            }
+        }
+
+        let prover = MockProver::run(K, &SumCircuit {}, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
     #[test]
     fn bad_lookup() {
         const K: u32 = 4;
@@ -1255,7 +1728,7 @@ mod tests {
         assert_eq!(
             prover.verify(),
             Err(vec![VerifyFailure::Lookup {
-                name: "lookup",
+                name: "lookup".to_owned(),
                 lookup_index: 0,
                 location: FailureLocation::InRegion {
                     region: (2, "Faulty synthesis").into(),
@@ -1264,4 +1737,80 @@ mod tests {
             }])
         );
     }
+
+    #[test]
+    fn named_columns() {
+        const K: u32 = 4;
+
+        #[derive(Clone)]
+        struct NamedCircuitConfig {
+            a: Column<Advice>,
+            b: Column<Advice>,
+            q: Selector,
+        }
+
+        struct NamedCircuit {}
+
+        impl Circuit<Fp> for NamedCircuit {
+            type Config = NamedCircuitConfig;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+                let a = meta.named_advice_column("a".to_owned());
+                let b = meta.advice_column();
+                let q = meta.selector();
+                meta.annotate_column(b, "b");
+
+                meta.create_gate(format!("{} equals {}", "a", "b"), |cells| {
+                    let a = cells.query_advice(a, Rotation::cur());
+                    let b = cells.query_advice(b, Rotation::cur());
+                    let q = cells.query_selector(q);
+
+                    vec![(format!("{} = {}", "a", "b"), q * (a - b))]
+                });
+
+                NamedCircuitConfig { a, b, q }
+            }
+
+            fn without_witnesses(&self) -> Self {
+                Self {}
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<Fp>,
+            ) -> Result<(), Error> {
+                layouter.assign_region(
+                    || "Faulty synthesis",
+                    |mut region| {
+                        config.q.enable(&mut region, 0)?;
+                        region.assign_advice(|| "a", config.a, 0, || Ok(Fp::one()))?;
+                        region.assign_advice(|| "b", config.b, 0, || Ok(Fp::from(2)))?;
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        let prover = MockProver::run(K, &NamedCircuit {}, vec![]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert_eq!(
+            failures,
+            vec![VerifyFailure::ConstraintNotSatisfied {
+                constraint: ((0, "a equals b").into(), 0, "a = b").into(),
+                location: FailureLocation::InRegion {
+                    region: (0, "Faulty synthesis").into(),
+                    offset: 0,
+                },
+                cell_values: vec![
+                    (((Any::Advice, 0, "a").into(), 0).into(), "1".to_owned()),
+                    (((Any::Advice, 1, "b").into(), 0).into(), "0x2".to_owned()),
+                ],
+            }]
+        );
+        assert!(failures[0]
+            .to_string()
+            .contains("- Column('Advice', 1, 'b')@0 = 0x2"));
+    }
 }
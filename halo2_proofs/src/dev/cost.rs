@@ -13,7 +13,7 @@ use group::prime::PrimeGroup;
 use crate::{
     plonk::{
         Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
-        FloorPlanner, Instance, Selector,
+        FloorPlanner, Instance, InstanceEvaluation, Selector,
     },
     poly::Rotation,
 };
@@ -104,6 +104,14 @@ impl<F: Field> Assignment<F> for Assembly {
         Ok(())
     }
 
+    fn assign_advice_slice(&mut self, _: Column<Advice>, _: usize, _: &[F]) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn assign_fixed_slice(&mut self, _: Column<Fixed>, _: usize, _: &[F]) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
         Ok(())
     }
@@ -152,6 +160,12 @@ impl<G: PrimeGroup, ConcreteCircuit: Circuit<G::Scalar>> CircuitCost<G, Concrete
 
         assert!((1 << k) >= cs.minimum_rows());
 
+        // The instance columns are not opened if the verifier evaluates them.
+        let instance_queries: &[(Column<Instance>, Rotation)] = match cs.instance_evaluation() {
+            InstanceEvaluation::Committed => &cs.instance_queries,
+            InstanceEvaluation::Direct => &[],
+        };
+
         // Figure out how many point sets we have due to queried cells.
         let mut column_queries: HashMap<Column<Any>, HashSet<i32>> = HashMap::new();
         for (c, r) in iter::empty()
@@ -160,7 +174,7 @@ impl<G: PrimeGroup, ConcreteCircuit: Circuit<G::Scalar>> CircuitCost<G, Concrete
                     .iter()
                     .map(|(c, r)| (Column::<Any>::from(*c), *r)),
             )
-            .chain(cs.instance_queries.iter().map(|(c, r)| ((*c).into(), *r)))
+            .chain(instance_queries.iter().map(|(c, r)| ((*c).into(), *r)))
             .chain(cs.fixed_queries.iter().map(|(c, r)| ((*c).into(), *r)))
             .chain(
                 cs.permutation
@@ -197,7 +211,7 @@ impl<G: PrimeGroup, ConcreteCircuit: Circuit<G::Scalar>> CircuitCost<G, Concrete
             k,
             max_deg,
             advice_columns: cs.num_advice_columns,
-            instance_queries: cs.instance_queries.len(),
+            instance_queries: instance_queries.len(),
             advice_queries: cs.advice_queries.len(),
             fixed_queries: cs.fixed_queries.len(),
             lookups: cs.lookups.len(),
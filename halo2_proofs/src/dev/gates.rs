@@ -3,26 +3,46 @@ use std::{
     fmt::{self, Write},
 };
 
-use ff::PrimeField;
+use ff::{Field, PrimeField};
 
 use crate::{
     dev::util,
-    plonk::{Circuit, ConstraintSystem},
+    plonk::{Any, Circuit, Column, ConstraintSystem},
 };
 
 #[derive(Debug)]
 struct Constraint {
-    name: &'static str,
+    name: String,
     expression: String,
     queries: BTreeSet<String>,
 }
 
 #[derive(Debug)]
 struct Gate {
-    name: &'static str,
+    name: String,
     constraints: Vec<Constraint>,
 }
 
+/// Formats a query of a column by its name, or by its type and index if it is unnamed.
+fn query<F: Field>(
+    cs: &ConstraintSystem<F>,
+    column_type: Any,
+    index: usize,
+    rotation: i32,
+) -> String {
+    match cs.column_name(Column::new(index, column_type)) {
+        Some(name) => format!("{}@{}", name, rotation),
+        None => {
+            let prefix = match column_type {
+                Any::Advice => "A",
+                Any::Fixed => "F",
+                Any::Instance => "I",
+            };
+            format!("{}{}@{}", prefix, index, rotation)
+        }
+    }
+}
+
 /// A struct for collecting and displaying the gates within a circuit.
 ///
 /// # Examples
@@ -56,6 +76,7 @@ struct Gate {
 ///         let b = meta.advice_column();
 ///         let c = meta.advice_column();
 ///         let s = meta.selector();
+///         meta.annotate_column(c, "c");
 ///
 ///         meta.create_gate("R1CS constraint", |meta| {
 ///             let a = meta.query_advice(a, Rotation::cur());
@@ -81,7 +102,7 @@ struct Gate {
 ///     format!("{}", gates),
 ///     r#####"R1CS constraint:
 /// - R1CS:
-///   S0 * (A0@0 * A1@0 - A2@0)
+///   S0 * (A0@0 * A1@0 - c@0)
 /// Total gates: 1
 /// Total custom constraint polynomials: 1
 /// Total negations: 1
@@ -109,19 +130,19 @@ impl CircuitGates {
             .gates
             .iter()
             .map(|gate| Gate {
-                name: gate.name(),
+                name: gate.name().to_owned(),
                 constraints: gate
                     .polynomials()
                     .iter()
                     .enumerate()
                     .map(|(i, constraint)| Constraint {
-                        name: gate.constraint_name(i),
+                        name: gate.constraint_name(i).to_owned(),
                         expression: constraint.evaluate(
                             &util::format_value,
                             &|selector| format!("S{}", selector.0),
-                            &|_, column, rotation| format!("F{}@{}", column, rotation.0),
-                            &|_, column, rotation| format!("A{}@{}", column, rotation.0),
-                            &|_, column, rotation| format!("I{}@{}", column, rotation.0),
+                            &|_, column, rotation| query(&cs, Any::Fixed, column, rotation.0),
+                            &|_, column, rotation| query(&cs, Any::Advice, column, rotation.0),
+                            &|_, column, rotation| query(&cs, Any::Instance, column, rotation.0),
                             &|a| {
                                 if a.contains(' ') {
                                     format!("-({})", a)
@@ -158,17 +179,17 @@ impl CircuitGates {
                             &|_| BTreeSet::default(),
                             &|selector| vec![format!("S{}", selector.0)].into_iter().collect(),
                             &|_, column, rotation| {
-                                vec![format!("F{}@{}", column, rotation.0)]
+                                vec![query(&cs, Any::Fixed, column, rotation.0)]
                                     .into_iter()
                                     .collect()
                             },
                             &|_, column, rotation| {
-                                vec![format!("A{}@{}", column, rotation.0)]
+                                vec![query(&cs, Any::Advice, column, rotation.0)]
                                     .into_iter()
                                     .collect()
                             },
                             &|_, column, rotation| {
-                                vec![format!("I{}@{}", column, rotation.0)]
+                                vec![query(&cs, Any::Instance, column, rotation.0)]
                                     .into_iter()
                                     .collect()
                             },
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use ff::Field;
 use tabbycat::{AttrList, Edge, GraphBuilder, GraphType, Identity, StmtList};
 
@@ -11,7 +13,8 @@ pub mod layout;
 /// Builds a dot graph string representing the given circuit.
 ///
 /// The graph is built from calls to [`Layouter::namespace`] both within the circuit, and
-/// inside the gadgets and chips that it uses.
+/// inside the gadgets and chips that it uses. Each namespace is labelled with the names
+/// of the named columns that it assigns cells in.
 ///
 /// [`Layouter::namespace`]: crate::circuit::Layouter#method.namespace
 pub fn circuit_dot_graph<F: Field, ConcreteCircuit: Circuit<F>>(
@@ -21,18 +24,29 @@ pub fn circuit_dot_graph<F: Field, ConcreteCircuit: Circuit<F>>(
     let mut cs = ConstraintSystem::default();
     let config = ConcreteCircuit::configure(&mut cs);
     let mut graph = Graph::default();
-    ConcreteCircuit::FloorPlanner::synthesize(&mut graph, circuit, config, cs.constants).unwrap();
+    ConcreteCircuit::FloorPlanner::synthesize(&mut graph, circuit, config, cs.constants.clone())
+        .unwrap();
 
     // Construct the node labels. We need to store these, because tabbycat operates on
     // string references, and we need those references to live long enough.
     let node_labels: Vec<_> = graph
         .nodes
         .into_iter()
-        .map(|(name, gadget_name)| {
-            if let Some(gadget_name) = gadget_name {
+        .zip(graph.columns.iter())
+        .map(|((name, gadget_name), columns)| {
+            let label = if let Some(gadget_name) = gadget_name {
                 format!("[{}] {}", gadget_name, name)
             } else {
                 name
+            };
+            let column_names: Vec<_> = columns
+                .iter()
+                .filter_map(|column| cs.column_name(*column))
+                .collect();
+            if column_names.is_empty() {
+                label
+            } else {
+                format!("{} ({})", label, column_names.join(", "))
             }
         })
         .collect();
@@ -67,6 +81,9 @@ struct Graph {
     /// Graph nodes in the namespace, structured as `(name, gadget_name)`.
     nodes: Vec<(String, Option<String>)>,
 
+    /// The columns in which each node assigns cells, as a set for each of `nodes`.
+    columns: Vec<BTreeSet<Column<Any>>>,
+
     /// Directed edges in the graph, as pairs of indices into `nodes`.
     edges: Vec<(usize, usize)>,
 
@@ -74,6 +91,14 @@ struct Graph {
     current_namespace: Vec<usize>,
 }
 
+impl Graph {
+    fn track(&mut self, column: Column<Any>) {
+        if let Some(node) = self.current_namespace.last() {
+            self.columns[*node].insert(column);
+        }
+    }
+}
+
 impl<F: Field> Assignment<F> for Graph {
     fn enter_region<NR, N>(&mut self, _: N)
     where
@@ -103,7 +128,7 @@ impl<F: Field> Assignment<F> for Graph {
     fn assign_advice<V, VR, A, AR>(
         &mut self,
         _: A,
-        _: Column<Advice>,
+        column: Column<Advice>,
         _: usize,
         _: V,
     ) -> Result<(), Error>
@@ -113,14 +138,14 @@ impl<F: Field> Assignment<F> for Graph {
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
-        // Do nothing; we don't care about cells in this context.
+        self.track(column.into());
         Ok(())
     }
 
     fn assign_fixed<V, VR, A, AR>(
         &mut self,
         _: A,
-        _: Column<Fixed>,
+        column: Column<Fixed>,
         _: usize,
         _: V,
     ) -> Result<(), Error>
@@ -130,7 +155,7 @@ impl<F: Field> Assignment<F> for Graph {
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
-        // Do nothing; we don't care about cells in this context.
+        self.track(column.into());
         Ok(())
     }
 
@@ -162,6 +187,7 @@ impl<F: Field> Assignment<F> for Graph {
         // Store the new node.
         let new_node = self.nodes.len();
         self.nodes.push((name_fn().into(), None));
+        self.columns.push(BTreeSet::new());
 
         // Create an edge from the parent, if any.
         if let Some(parent) = self.current_namespace.last() {
@@ -17,7 +17,8 @@ use crate::plonk::{
 ///
 /// Cells that have been assigned to by the circuit will be shaded. If any cells are
 /// assigned to more than once (which is usually a mistake), they will be shaded darker
-/// than the surrounding cells.
+/// than the surrounding cells. Columns that were given names are labelled with them at
+/// the bottom of the view.
 ///
 /// # Examples
 ///
@@ -315,6 +316,14 @@ impl CircuitLayout {
                         ("sans-serif", 15.0).into_font(),
                     )),
             )?;
+            for (column, name) in &cs.column_names {
+                root.draw(
+                    &(EmptyElement::at((
+                        column_index(&cs, RegionColumn::Column(*column)),
+                        view_bottom,
+                    )) + Text::new(name.clone(), (5, -20), ("sans-serif", 15.0).into_font())),
+                )?;
+            }
         }
         Ok(())
     }
@@ -1,6 +1,8 @@
 //! Metadata about circuits.
 
-use crate::plonk::{self, Any};
+use ff::Field;
+
+use crate::plonk::{self, Any, ConstraintSystem};
 use std::fmt;
 
 /// Metadata about a column within a circuit.
@@ -10,17 +12,50 @@ pub struct Column {
     column_type: Any,
     /// The index of the column.
     index: usize,
+    /// The name of the column, if it was given one during `Circuit::configure`.
+    name: Option<String>,
+}
+
+impl Column {
+    /// Looks up the name of `column` in `cs`.
+    pub(super) fn named<F: Field>(column: plonk::Column<Any>, cs: &ConstraintSystem<F>) -> Self {
+        Column {
+            name: cs.column_name(column).map(|name| name.to_owned()),
+            ..column.into()
+        }
+    }
 }
 
 impl fmt::Display for Column {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Column('{:?}', {})", self.column_type, self.index)
+        match &self.name {
+            Some(name) => write!(
+                f,
+                "Column('{:?}', {}, '{}')",
+                self.column_type, self.index, name
+            ),
+            None => write!(f, "Column('{:?}', {})", self.column_type, self.index),
+        }
     }
 }
 
 impl From<(Any, usize)> for Column {
     fn from((column_type, index): (Any, usize)) -> Self {
-        Column { column_type, index }
+        Column {
+            column_type,
+            index,
+            name: None,
+        }
+    }
+}
+
+impl From<(Any, usize, &str)> for Column {
+    fn from((column_type, index, name): (Any, usize, &str)) -> Self {
+        Column {
+            column_type,
+            index,
+            name: Some(name.to_owned()),
+        }
     }
 }
 
@@ -29,6 +64,7 @@ impl From<plonk::Column<Any>> for Column {
         Column {
             column_type: *column.column_type(),
             index: column.index(),
+            name: None,
         }
     }
 }
@@ -90,7 +126,7 @@ pub struct Gate {
     index: usize,
     /// The name of the active gate. These are specified by the gate creator (such as
     /// a chip implementation), and is not enforced to be unique.
-    name: &'static str,
+    name: String,
 }
 
 impl fmt::Display for Gate {
@@ -99,9 +135,12 @@ impl fmt::Display for Gate {
     }
 }
 
-impl From<(usize, &'static str)> for Gate {
-    fn from((index, name): (usize, &'static str)) -> Self {
-        Gate { index, name }
+impl From<(usize, &str)> for Gate {
+    fn from((index, name): (usize, &str)) -> Self {
+        Gate {
+            index,
+            name: name.to_owned(),
+        }
     }
 }
 
@@ -116,7 +155,7 @@ pub struct Constraint {
     index: usize,
     /// The name of the constraint. This is specified by the gate creator (such as a chip
     /// implementation), and is not enforced to be unique.
-    name: &'static str,
+    name: String,
 }
 
 impl fmt::Display for Constraint {
@@ -136,9 +175,13 @@ impl fmt::Display for Constraint {
     }
 }
 
-impl From<(Gate, usize, &'static str)> for Constraint {
-    fn from((gate, index, name): (Gate, usize, &'static str)) -> Self {
-        Constraint { gate, index, name }
+impl From<(Gate, usize, &str)> for Constraint {
+    fn from((gate, index, name): (Gate, usize, &str)) -> Self {
+        Constraint {
+            gate,
+            index,
+            name: name.to_owned(),
+        }
     }
 }
 
@@ -5,7 +5,7 @@ use pairing::arithmetic::FieldExt;
 
 use super::{metadata, Value};
 use crate::{
-    plonk::{Any, Expression, Gate, VirtualCell},
+    plonk::{Any, ConstraintSystem, Expression, Gate, VirtualCell},
     poly::Rotation,
 };
 
@@ -27,6 +27,7 @@ pub(super) fn format_value<F: Field>(v: F) -> String {
 }
 
 fn cell_value<'a, F: FieldExt>(
+    cs: &'a ConstraintSystem<F>,
     virtual_cells: &'a [VirtualCell],
     column_type: Any,
     load: impl Fn(usize, usize, Rotation) -> Value<F> + 'a,
@@ -42,7 +43,7 @@ fn cell_value<'a, F: FieldExt>(
             // None indicates a selector, which we don't bother showing.
             .map(|cell| {
                 (
-                    cell.clone().into(),
+                    (metadata::Column::named(cell.column, cs), cell.rotation.0).into(),
                     match load(query_index, column_index, rotation) {
                         Value::Real(v) => format_value(v),
                         Value::Poison => unreachable!(),
@@ -55,6 +56,7 @@ fn cell_value<'a, F: FieldExt>(
 }
 
 pub(super) fn cell_values<'a, F: FieldExt>(
+    cs: &ConstraintSystem<F>,
     gate: &Gate<F>,
     poly: &Expression<F>,
     load_fixed: impl Fn(usize, usize, Rotation) -> Value<F> + 'a,
@@ -65,9 +67,9 @@ pub(super) fn cell_values<'a, F: FieldExt>(
     let cell_values = poly.evaluate(
         &|_| BTreeMap::default(),
         &|_| panic!("virtual selectors are removed during optimization"),
-        &cell_value(virtual_cells, Any::Fixed, load_fixed),
-        &cell_value(virtual_cells, Any::Advice, load_advice),
-        &cell_value(virtual_cells, Any::Instance, load_instance),
+        &cell_value(cs, virtual_cells, Any::Fixed, load_fixed),
+        &cell_value(cs, virtual_cells, Any::Advice, load_advice),
+        &cell_value(cs, virtual_cells, Any::Instance, load_instance),
         &|a| a,
         &|mut a, mut b| {
             a.append(&mut b);
@@ -0,0 +1,354 @@
+//! A C interface for verifying proofs over BN256 created with the BLAKE2b
+//! transcript, for use from other languages.
+//!
+//! The interface is built into the shared library of the crate with
+//!
+//! ```text
+//! cargo build --release --features ffi
+//! ```
+//!
+//! and declared in `include/halo2_proofs.h`, which is generated from this
+//! module with
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/halo2_proofs.h
+//! ```
+//!
+//! The `ffi` test fails if the header is out of date.
+//!
+//! Verifier parameters are read as written by [`ParamsVerifier::write`], and
+//! verifying keys as written by [`Serializable::store`], which includes the
+//! constraint system so that no circuit is needed to read them. Every function
+//! returns a [`Halo2Status`], and none of them unwinds into the caller.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use ff::PrimeField;
+use pairing::bn256::{Bn256, Fr, G1Affine};
+use rand_core::OsRng;
+
+use crate::helpers::Serializable;
+use crate::plonk::{verify_proof, Error, MultiCircuitBatchVerifier, SingleVerifier, VerifyingKey};
+use crate::poly::commitment::ParamsVerifier;
+use crate::transcript::{Blake2bRead, Challenge255};
+
+/// The outcome of a call to the C interface.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Halo2Status {
+    /// The call succeeded, and every proof it checked is valid.
+    Ok = 0,
+    /// A pointer that must not be null was null.
+    NullPointer = 1,
+    /// The verifier parameters could not be read.
+    InvalidParams = 2,
+    /// The verifying key could not be read.
+    InvalidVerifyingKey = 3,
+    /// The instances are malformed, contain a non-canonical scalar, or do not
+    /// match the circuit.
+    InvalidInstances = 4,
+    /// A proof could not be read.
+    InvalidProof = 5,
+    /// A proof was read but is not valid.
+    VerificationFailed = 6,
+    /// The verifier parameters of a batch were not created by the same setup.
+    IncompatibleParams = 7,
+    /// The call panicked. This is a bug.
+    Panic = 8,
+}
+
+impl From<Error> for Halo2Status {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::InvalidInstances | Error::InstanceTooLarge => Halo2Status::InvalidInstances,
+            Error::Transcript(_) => Halo2Status::InvalidProof,
+            Error::IncompatibleParams => Halo2Status::IncompatibleParams,
+            _ => Halo2Status::VerificationFailed,
+        }
+    }
+}
+
+/// Verifier parameters read by [`halo2_params_verifier_read`].
+#[derive(Debug)]
+pub struct Halo2ParamsVerifier {
+    params: ParamsVerifier<Bn256>,
+}
+
+/// A verifying key read by [`halo2_verifying_key_read`].
+#[derive(Debug)]
+pub struct Halo2VerifyingKey {
+    vk: VerifyingKey<G1Affine>,
+}
+
+/// The instance values of a proof.
+///
+/// Each value is a scalar in its canonical 32-byte little-endian
+/// representation. The values are ordered by circuit instance, then by
+/// instance column, then by row.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Halo2Instances {
+    /// The `32 * num_values` bytes of the values.
+    pub values: *const u8,
+    /// The number of values.
+    pub num_values: usize,
+    /// The number of values of each instance column of each circuit instance,
+    /// `num_circuits * num_columns` lengths in all.
+    pub column_lengths: *const usize,
+    /// The number of circuit instances of the proof.
+    pub num_circuits: usize,
+    /// The number of instance columns of the circuit.
+    pub num_columns: usize,
+}
+
+/// A proof to check in a batch with [`halo2_verify_batch`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Halo2BatchEntry {
+    /// The verifier parameters of the proof.
+    pub params: *const Halo2ParamsVerifier,
+    /// The verifying key of the circuit of the proof.
+    pub vk: *const Halo2VerifyingKey,
+    /// The instance values of the proof.
+    pub instances: Halo2Instances,
+    /// The `proof_len` bytes of the proof.
+    pub proof: *const u8,
+    /// The length of the proof.
+    pub proof_len: usize,
+}
+
+/// Runs `f`, turning a panic into [`Halo2Status::Panic`].
+fn catch(f: impl FnOnce() -> Result<(), Halo2Status>) -> Halo2Status {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => Halo2Status::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => Halo2Status::Panic,
+    }
+}
+
+/// Returns the `len` elements at `data`, which may only be null if `len` is
+/// zero.
+unsafe fn slice<'a, T>(data: *const T, len: usize) -> Result<&'a [T], Halo2Status> {
+    if len == 0 {
+        Ok(&[])
+    } else if data.is_null() {
+        Err(Halo2Status::NullPointer)
+    } else {
+        Ok(std::slice::from_raw_parts(data, len))
+    }
+}
+
+/// Returns the value behind `data`, which must not be null.
+unsafe fn deref<'a, T>(data: *const T) -> Result<&'a T, Halo2Status> {
+    data.as_ref().ok_or(Halo2Status::NullPointer)
+}
+
+/// Reads the instance values of each circuit instance and column.
+unsafe fn read_instances(instances: &Halo2Instances) -> Result<Vec<Vec<Vec<Fr>>>, Halo2Status> {
+    let invalid = Halo2Status::InvalidInstances;
+    let num_bytes = instances.num_values.checked_mul(32).ok_or(invalid)?;
+    let num_lengths = instances
+        .num_circuits
+        .checked_mul(instances.num_columns)
+        .ok_or(invalid)?;
+    let bytes = slice(instances.values, num_bytes)?;
+    let lengths = slice(instances.column_lengths, num_lengths)?;
+    let total = lengths
+        .iter()
+        .try_fold(0usize, |total, len| total.checked_add(*len));
+    if total != Some(instances.num_values) {
+        return Err(invalid);
+    }
+
+    let mut values = bytes.chunks_exact(32).map(|bytes| {
+        let mut repr = <Fr as PrimeField>::Repr::default();
+        repr.as_mut().copy_from_slice(bytes);
+        Option::from(Fr::from_repr(repr)).ok_or(invalid)
+    });
+    (0..instances.num_circuits)
+        .map(|circuit| {
+            let columns = &lengths[circuit * instances.num_columns..][..instances.num_columns];
+            columns
+                .iter()
+                .map(|len| values.by_ref().take(*len).collect::<Result<Vec<_>, _>>())
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect()
+}
+
+/// Calls `f` with the instance values borrowed as the verifier takes them.
+fn with_instances<T>(instances: &[Vec<Vec<Fr>>], f: impl FnOnce(&[&[&[Fr]]]) -> T) -> T {
+    let columns: Vec<Vec<&[Fr]>> = instances
+        .iter()
+        .map(|columns| columns.iter().map(|values| &values[..]).collect())
+        .collect();
+    let instances: Vec<&[&[Fr]]> = columns.iter().map(|columns| &columns[..]).collect();
+    f(&instances)
+}
+
+/// Reads verifier parameters from the `len` bytes at `bytes`, and stores a
+/// handle to them in `out`, to be freed with [`halo2_params_verifier_free`].
+///
+/// # Safety
+///
+/// `bytes` must point to `len` readable bytes, and `out` must point to a
+/// writable handle.
+#[no_mangle]
+pub unsafe extern "C" fn halo2_params_verifier_read(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut Halo2ParamsVerifier,
+) -> Halo2Status {
+    catch(|| {
+        if out.is_null() {
+            return Err(Halo2Status::NullPointer);
+        }
+        let mut bytes = slice(bytes, len)?;
+        let params = ParamsVerifier::read(&mut bytes).map_err(|_| Halo2Status::InvalidParams)?;
+        if !bytes.is_empty() {
+            return Err(Halo2Status::InvalidParams);
+        }
+        *out = Box::into_raw(Box::new(Halo2ParamsVerifier { params }));
+        Ok(())
+    })
+}
+
+/// Frees verifier parameters read by [`halo2_params_verifier_read`]. Does
+/// nothing if `params` is null.
+///
+/// # Safety
+///
+/// `params` must be null or a handle that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn halo2_params_verifier_free(params: *mut Halo2ParamsVerifier) {
+    if !params.is_null() {
+        drop(Box::from_raw(params));
+    }
+}
+
+/// Reads a verifying key from the `len` bytes at `bytes`, and stores a handle
+/// to it in `out`, to be freed with [`halo2_verifying_key_free`].
+///
+/// # Safety
+///
+/// `bytes` must point to `len` readable bytes, and `out` must point to a
+/// writable handle.
+#[no_mangle]
+pub unsafe extern "C" fn halo2_verifying_key_read(
+    bytes: *const u8,
+    len: usize,
+    out: *mut *mut Halo2VerifyingKey,
+) -> Halo2Status {
+    catch(|| {
+        if out.is_null() {
+            return Err(Halo2Status::NullPointer);
+        }
+        let mut bytes = slice(bytes, len)?;
+        let vk = VerifyingKey::fetch(&mut bytes).map_err(|_| Halo2Status::InvalidVerifyingKey)?;
+        if !bytes.is_empty() {
+            return Err(Halo2Status::InvalidVerifyingKey);
+        }
+        *out = Box::into_raw(Box::new(Halo2VerifyingKey { vk }));
+        Ok(())
+    })
+}
+
+/// Frees a verifying key read by [`halo2_verifying_key_read`]. Does nothing
+/// if `vk` is null.
+///
+/// # Safety
+///
+/// `vk` must be null or a handle that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn halo2_verifying_key_free(vk: *mut Halo2VerifyingKey) {
+    if !vk.is_null() {
+        drop(Box::from_raw(vk));
+    }
+}
+
+/// Verifies the `proof_len` bytes of the proof at `proof` for the circuit of
+/// `vk` and the given instances.
+///
+/// Returns [`Halo2Status::Ok`] if the proof is valid.
+///
+/// # Safety
+///
+/// `params` and `vk` must be live handles, `instances` must point to
+/// instances whose arrays are readable, and `proof` must point to
+/// `proof_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn halo2_verify_proof(
+    params: *const Halo2ParamsVerifier,
+    vk: *const Halo2VerifyingKey,
+    instances: *const Halo2Instances,
+    proof: *const u8,
+    proof_len: usize,
+) -> Halo2Status {
+    catch(|| {
+        let params = deref(params)?;
+        let vk = deref(vk)?;
+        let instances = read_instances(deref(instances)?)?;
+        let proof = slice(proof, proof_len)?;
+
+        let strategy = SingleVerifier::new(&params.params);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        with_instances(&instances, |instances| {
+            verify_proof(&params.params, &vk.vk, strategy, instances, &mut transcript)
+        })
+        .map_err(Halo2Status::from)
+    })
+}
+
+/// Verifies the `num_entries` proofs at `entries` together, which is faster
+/// than verifying them one at a time. The proofs may be for different
+/// circuits, but their verifier parameters must come from the same setup.
+///
+/// Returns [`Halo2Status::Ok`] if every proof is valid, and
+/// [`Halo2Status::VerificationFailed`] if any proof is invalid or cannot be
+/// read. In both cases, `results[i]` is set to 1 if the `i`-th proof is valid
+/// and to 0 otherwise. `results` is left unchanged if any other status is
+/// returned.
+///
+/// # Safety
+///
+/// `entries` must point to `num_entries` entries whose handles are live and
+/// whose arrays are readable, and `results` must point to `num_entries`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn halo2_verify_batch(
+    entries: *const Halo2BatchEntry,
+    num_entries: usize,
+    results: *mut u8,
+) -> Halo2Status {
+    catch(|| {
+        let entries = slice(entries, num_entries)?;
+        if num_entries != 0 && results.is_null() {
+            return Err(Halo2Status::NullPointer);
+        }
+
+        let mut batch = MultiCircuitBatchVerifier::new();
+        for entry in entries.iter() {
+            let params = deref(entry.params)?;
+            let vk = deref(entry.vk)?;
+            let instances = read_instances(&entry.instances)?;
+            let proof = slice(entry.proof, entry.proof_len)?;
+
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+            with_instances(&instances, |instances| {
+                batch.add_proof(&params.params, &vk.vk, instances, &mut transcript)
+            })?;
+        }
+
+        let invalid = batch.finalize(OsRng).err().unwrap_or_default();
+        ptr::write_bytes(results, 1, num_entries);
+        for index in invalid.iter() {
+            *results.add(*index) = 0;
+        }
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(Halo2Status::VerificationFailed)
+        }
+    })
+}
@@ -1,45 +1,107 @@
+use crate::arithmetic::{CurveAffine, FieldExt};
+use crate::io::{self, Read, Write};
 use crate::plonk::circuit::FloorPlanner;
-use crate::{
-    arithmetic::{CurveAffine, FieldExt},
-    plonk::{generate_pk_info, keygen_pk_from_info},
-    poly::batch_invert_assigned,
-};
 use crate::{
     plonk::{
-        self,
-        permutation::{self, keygen::Assembly},
-        Advice, Any, Assigned, Assignment, Circuit, Column, ColumnType, ConstraintSystem, Error,
-        Expression, Fixed, Gate, Instance, ProvingKey, Selector, VerifyingKey, VirtualCell,
+        self, permutation, Advice, Any, Assigned, Assignment, Circuit, Column, ColumnType,
+        ConstraintSystem, Error, Expression, Fixed, Gate, Instance, InstanceEvaluation, Selector,
+        VerifyingKey, VirtualCell,
     },
     poly::{commitment::Params, EvaluationDomain, LagrangeCoeff, Polynomial, Rotation},
     transcript::EncodedChallenge,
 };
+#[cfg(feature = "std")]
+use crate::{
+    plonk::{generate_pk_info, keygen_pk_from_info, permutation::keygen::Assembly, ProvingKey},
+    poly::batch_invert_assigned,
+};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::convert::TryInto;
+use core::marker::PhantomData;
+use core::ops::RangeTo;
 use ff::Field;
+#[cfg(feature = "std")]
 use memmap::{MmapMut, MmapOptions};
-use num;
 use num_derive::FromPrimitive;
-use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
-use std::io::Seek;
-use std::marker::PhantomData;
+#[cfg(feature = "std")]
+use rayon::prelude::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+#[cfg(feature = "std")]
 use std::{
     fs::{File, OpenOptions},
-    io,
-    ops::RangeTo,
+    io::{Seek, SeekFrom},
 };
 
 pub(crate) trait CurveRead: CurveAffine {
+    /// Parses a compressed element using `from_bytes`, rejecting any encoding
+    /// other than the one `to_bytes` produces for the parsed point.
+    fn from_canonical_bytes(compressed: &Self::Repr) -> Option<Self> {
+        Option::<Self>::from(Self::from_bytes(compressed))
+            .filter(|point| point.to_bytes().as_ref() == compressed.as_ref())
+    }
+
     /// Reads a compressed element from the buffer and attempts to parse it
-    /// using `from_bytes`.
+    /// using `from_canonical_bytes`.
     fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
         let mut compressed = Self::Repr::default();
         reader.read_exact(compressed.as_mut())?;
-        Option::from(Self::from_bytes(&compressed))
+        Self::from_canonical_bytes(&compressed)
             .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "invalid point encoding in proof"))
     }
 }
 
 impl<C: CurveAffine> CurveRead for C {}
 
+/// Returns the error of a reader given malformed input.
+pub(crate) fn invalid_data(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+/// Reads a scalar from its canonical representation, as written by `to_repr`.
+pub(crate) fn read_scalar<F: FieldExt, R: io::Read>(reader: &mut R) -> io::Result<F> {
+    let mut repr = F::Repr::default();
+    reader.read_exact(repr.as_mut())?;
+    Option::from(F::from_repr(repr)).ok_or_else(|| invalid_data("non-canonical scalar"))
+}
+
+/// Reads `len` bytes, which are only allocated as they are read so that a
+/// corrupted length cannot allocate more than the input holds.
+pub(crate) fn read_exact_vec<R: io::Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    const CHUNK: usize = 1 << 16;
+    let mut bytes = vec![];
+    while bytes.len() < len {
+        let start = bytes.len();
+        bytes.resize(start + core::cmp::min(CHUNK, len - start), 0);
+        reader.read_exact(&mut bytes[start..])?;
+    }
+    Ok(bytes)
+}
+
+/// The largest constraint degree of a verifying key that is read, far beyond
+/// that of any practical circuit. [`EvaluationDomain::new`] allocates a
+/// scalar per degree.
+const MAX_CONSTRAINT_DEGREE: u32 = 1 << 16;
+
+/// Checks that a domain of size `2^k` for constraints of degree `j` fits in
+/// the two-adic subgroup of the scalar field, so that
+/// [`EvaluationDomain::new`] accepts it.
+pub(crate) fn check_domain_size<F: FieldExt>(j: u32, k: u32) -> io::Result<()> {
+    if k > F::S {
+        return Err(invalid_data(
+            "k is larger than the two-adicity of the field",
+        ));
+    }
+    if j == 0 || j > MAX_CONSTRAINT_DEGREE || (j - 1) as u64 > 1u64 << (F::S - k) {
+        return Err(invalid_data("invalid constraint degree"));
+    }
+    Ok(())
+}
+
 pub trait Serializable: Clone {
     /// Reads a compressed element from the buffer and attempts to parse it
     /// using `from_bytes`.
@@ -49,6 +111,7 @@ pub trait Serializable: Clone {
     fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
 }
 
+#[cfg(feature = "std")]
 pub trait ParaSerializable: Clone {
     /// Reads a compressed element from the buffer and attempts to parse it
     /// using `from_bytes`.
@@ -60,7 +123,7 @@ pub trait ParaSerializable: Clone {
 
 fn read_u32<R: io::Read>(reader: &mut R) -> io::Result<u32> {
     let mut r = [0u8; 4];
-    reader.read(&mut r)?;
+    reader.read_exact(&mut r)?;
     Ok(u32::from_le_bytes(r))
 }
 
@@ -78,10 +141,8 @@ impl Serializable for u32 {
 impl Serializable for String {
     fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Self> {
         let len = read_u32(reader)?;
-        let mut s = vec![0; len as usize];
-        reader.read_exact(&mut s)?;
-        let u = String::from_utf8(s.to_vec()).unwrap();
-        Ok(u)
+        let s = read_exact_vec(reader, len as usize)?;
+        String::from_utf8(s).map_err(|_| invalid_data("invalid UTF-8 in string"))
     }
     fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         let bytes = self.as_bytes();
@@ -102,36 +163,57 @@ impl Serializable for (String, u32) {
     }
 }
 
+impl Serializable for (Column<Any>, String) {
+    fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        Ok((Column::<Any>::fetch(reader)?, String::fetch(reader)?))
+    }
+    fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.store(writer)?;
+        self.1.store(writer)?;
+        Ok(())
+    }
+}
 
+#[cfg(feature = "std")]
 impl ParaSerializable for Vec<Vec<(u32, u32)>> {
     fn vec_fetch(fd: &mut File) -> io::Result<Self> {
         let columns = read_u32(fd)?;
-        let mut offset = 0;
+        let mut offset = 0u64;
         let mut offsets = vec![];
         for _ in 0..columns {
             let l = read_u32(fd)?;
             offsets.push((offset, l));
-            offset = offset + l;
+            offset = offset + l as u64;
         }
         let position = fd.stream_position()?;
-        let res:Vec<Vec<(u32,u32)>> = (0..columns)
+        // The mappings must lie within the file, as reading past its end
+        // through a memory map is not an error but a fault.
+        let end = position + offset * 8;
+        if end > fd.metadata()?.len() {
+            return Err(invalid_data("permutation mapping is longer than the file"));
+        }
+        let res: Vec<Vec<(u32, u32)>> = (0..columns)
             .into_par_iter()
-            .map(|i| {
+            .map(|i| -> io::Result<Vec<(u32, u32)>> {
+                let (offset, len) = offsets[i as usize];
+                if len == 0 {
+                    return Ok(vec![]);
+                }
                 let mmap = unsafe {
                     MmapOptions::new()
-                        .offset(position + (offsets[i as usize].0 as u64 * 8))
-                        .len(offsets[i as usize].1 as usize * 8)
-                        .map(&fd)
-                        .unwrap()
+                        .offset(position + offset * 8)
+                        .len(len as usize * 8)
+                        .map(&fd)?
                 };
-                let s: &[(u32, u32)] = unsafe {
-                    std::slice::from_raw_parts(mmap.as_ptr() as *const (u32, u32), offsets[i as usize].1 as usize)
-                };
-                let mut s2 = vec![];
-                s2.extend_from_slice(s);
-                s2
+                Ok(mmap
+                    .chunks(8)
+                    .map(|pair| unsafe {
+                        std::ptr::read_unaligned(pair.as_ptr() as *const (u32, u32))
+                    })
+                    .collect())
             })
-            .collect();
+            .collect::<io::Result<_>>()?;
+        fd.seek(SeekFrom::Start(end))?;
         Ok(res)
     }
 
@@ -171,42 +253,77 @@ impl ParaSerializable for Vec<Vec<(u32, u32)>> {
 impl<B:Clone, F: FieldExt> Serializable for Polynomial<F, B> {
     fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Self> {
         let u = read_u32(reader)?;
-        let mut buf = vec![0u8; u as usize * 32];
-        reader.read_exact(&mut buf)?;
-        let s: &[F] = unsafe {
-            std::slice::from_raw_parts(buf.as_ptr() as *const F, u as usize)
-        };
-        Ok(Polynomial::new(s.to_vec()))
+        if u as u64 > 1u64 << F::S {
+            return Err(invalid_data("polynomial is larger than any domain"));
+        }
+        let size = F::Repr::default().as_ref().len();
+        let buf = read_exact_vec(reader, u as usize * size)?;
+        let values = buf
+            .chunks(size)
+            .map(|mut chunk| read_scalar(&mut chunk))
+            .collect::<io::Result<_>>()?;
+        Ok(Polynomial::new(values))
     }
     fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         let u = self.values.len() as u32;
         u.store(writer)?;
-        let s: &[u8] = unsafe {
-            std::slice::from_raw_parts(self.values.as_ptr() as *const u8, u as usize * 32)
-        };
-        writer.write(s)?;
+        for value in self.values.iter() {
+            writer.write_all(value.to_repr().as_ref())?;
+        }
         Ok(())
     }
 }
 
-
-
+const VK_MAGIC: [u8; 4] = *b"H2VK";
+const VK_VERSION: u32 = 1;
+
+/// The self-describing format of verifying keys, from which a verifier can
+/// load the key of any circuit without its [`Circuit`] implementation.
+///
+/// | Field                 | Encoding                                      |
+/// |-----------------------|-----------------------------------------------|
+/// | magic                 | `H2VK`                                        |
+/// | version               | `u32`, currently 1                            |
+/// | `j`, `k`              | `u32` each, the degree and size of the domain |
+/// | constraint system     | as written by `write_cs`                      |
+/// | fixed commitments     | one compressed point per fixed column         |
+/// | permutation           | one compressed point per permutation column   |
+///
+/// The constraint system includes the gates, queries, lookups, permutation
+/// columns, constants, selectors, column names and minimum degree, so that
+/// the key read has the same [`VerifyingKey::digest`] as the key written.
 impl<C: CurveAffine> Serializable for VerifyingKey<C> {
     fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         let j = (self.domain.get_quotient_poly_degree() + 1) as u32; // quotient_poly_degree is j-1
         let k = self.domain.k() as u32;
+        writer.write_all(&VK_MAGIC)?;
+        VK_VERSION.store(writer)?;
         writer.write(&mut j.to_le_bytes())?;
         writer.write(&mut k.to_le_bytes())?;
         write_cs::<C, W>(&self.cs, writer)?;
-        self.write(writer)?;
+        self.write_commitments(writer)?;
         Ok(())
     }
 
     fn fetch<R: io::Read>(reader: &mut R) -> io::Result<VerifyingKey<C>> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != VK_MAGIC {
+            return Err(invalid_data("not a verifying key"));
+        }
+        if read_u32(reader)? != VK_VERSION {
+            return Err(invalid_data("unsupported verifying key version"));
+        }
         let j = read_u32(reader)?;
         let k = read_u32(reader)?;
-        let domain: EvaluationDomain<C::Scalar> = EvaluationDomain::new(j, k);
+        check_domain_size::<C::Scalar>(j, k)?;
         let cs = read_cs::<C, R>(reader)?;
+        if (j as usize) < cs.degree() {
+            return Err(invalid_data(
+                "constraint degree is smaller than the circuit's",
+            ));
+        }
+        let domain: EvaluationDomain<C::Scalar> = EvaluationDomain::new(j, k);
 
         let fixed_commitments: Vec<_> = (0..cs.num_fixed_columns)
             .map(|_| C::read(reader))
@@ -242,13 +359,13 @@ impl<T: Serializable> Serializable for Vec<T> {
 }
 
 impl Serializable for Column<Any> {
-    fn store<W: io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+    fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         writer.write(&mut (self.index as u32).to_le_bytes())?;
         writer.write(&mut (*self.column_type() as u32).to_le_bytes())?;
         Ok(())
     }
 
-    fn fetch<R: io::Read>(reader: &mut R) -> std::io::Result<Self> {
+    fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Self> {
         let index = read_u32(reader)?;
         let typ = read_u32(reader)?;
         let typ = if typ == Any::Advice as u32 {
@@ -258,7 +375,7 @@ impl Serializable for Column<Any> {
         } else if typ == Any::Fixed as u32 {
             Any::Fixed
         } else {
-            unreachable!()
+            return Err(invalid_data("invalid column type"));
         };
         Ok(Column {
             index: index as usize,
@@ -267,10 +384,24 @@ impl Serializable for Column<Any> {
     }
 }
 
-fn write_arguments<W: std::io::Write>(
-    columns: &Vec<Column<Any>>,
-    writer: &mut W,
-) -> std::io::Result<()> {
+impl Serializable for Selector {
+    fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        (self.0 as u32).store(writer)?;
+        (self.1 as u32).store(writer)
+    }
+
+    fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let index = read_u32(reader)? as usize;
+        let simple = match read_u32(reader)? {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid_data("invalid selector kind")),
+        };
+        Ok(Selector(index, simple))
+    }
+}
+
+fn write_arguments<W: io::Write>(columns: &Vec<Column<Any>>, writer: &mut W) -> io::Result<()> {
     writer.write(&mut (columns.len() as u32).to_le_bytes())?;
     for c in columns.iter() {
         c.store(writer)?
@@ -278,9 +409,7 @@ fn write_arguments<W: std::io::Write>(
     Ok(())
 }
 
-fn read_arguments<R: std::io::Read>(
-    reader: &mut R,
-) -> std::io::Result<plonk::permutation::Argument> {
+fn read_arguments<R: io::Read>(reader: &mut R) -> io::Result<plonk::permutation::Argument> {
     let len = read_u32(reader)?;
     let mut cols = vec![];
     for _ in 0..len {
@@ -289,18 +418,12 @@ fn read_arguments<R: std::io::Read>(
     Ok(plonk::permutation::Argument { columns: cols })
 }
 
-fn write_column<T: ColumnType, W: std::io::Write>(
-    column: &Column<T>,
-    writer: &mut W,
-) -> std::io::Result<()> {
+fn write_column<T: ColumnType, W: io::Write>(column: &Column<T>, writer: &mut W) -> io::Result<()> {
     writer.write(&mut (column.index as u32).to_le_bytes())?;
     Ok(())
 }
 
-fn read_column<T: ColumnType, R: std::io::Read>(
-    reader: &mut R,
-    t: T,
-) -> std::io::Result<Column<T>> {
+fn read_column<T: ColumnType, R: io::Read>(reader: &mut R, t: T) -> io::Result<Column<T>> {
     let index = read_u32(reader)? as usize;
     Ok(Column {
         index,
@@ -308,10 +431,10 @@ fn read_column<T: ColumnType, R: std::io::Read>(
     })
 }
 
-fn write_queries<T: ColumnType, W: std::io::Write>(
+fn write_queries<T: ColumnType, W: io::Write>(
     columns: &Vec<(Column<T>, Rotation)>,
     writer: &mut W,
-) -> std::io::Result<()> {
+) -> io::Result<()> {
     writer.write(&mut (columns.len() as u32).to_le_bytes())?;
     for (c, rotation) in columns.iter() {
         write_column(c, writer)?;
@@ -320,10 +443,7 @@ fn write_queries<T: ColumnType, W: std::io::Write>(
     Ok(())
 }
 
-fn write_virtual_cells<W: std::io::Write>(
-    columns: &Vec<VirtualCell>,
-    writer: &mut W,
-) -> std::io::Result<()> {
+fn write_virtual_cells<W: io::Write>(columns: &Vec<VirtualCell>, writer: &mut W) -> io::Result<()> {
     writer.write(&mut (columns.len() as u32).to_le_bytes())?;
     for cell in columns.iter() {
         cell.column.store(writer)?;
@@ -332,10 +452,10 @@ fn write_virtual_cells<W: std::io::Write>(
     Ok(())
 }
 
-fn read_queries<T: ColumnType, R: std::io::Read>(
+fn read_queries<T: ColumnType, R: io::Read>(
     reader: &mut R,
     t: T,
-) -> std::io::Result<Vec<(Column<T>, Rotation)>> {
+) -> io::Result<Vec<(Column<T>, Rotation)>> {
     let mut queries = vec![];
     let len = read_u32(reader)?;
     for _ in 0..len {
@@ -347,7 +467,7 @@ fn read_queries<T: ColumnType, R: std::io::Read>(
     Ok(queries)
 }
 
-fn read_virtual_cells<R: std::io::Read>(reader: &mut R) -> std::io::Result<Vec<VirtualCell>> {
+fn read_virtual_cells<R: io::Read>(reader: &mut R) -> io::Result<Vec<VirtualCell>> {
     let mut vcells = vec![];
     let len = read_u32(reader)?;
     for _ in 0..len {
@@ -359,23 +479,20 @@ fn read_virtual_cells<R: std::io::Read>(reader: &mut R) -> std::io::Result<Vec<V
     Ok(vcells)
 }
 
-fn write_fixed_column<W: std::io::Write>(
-    column: &Column<Fixed>,
-    writer: &mut W,
-) -> std::io::Result<()> {
+fn write_fixed_column<W: io::Write>(column: &Column<Fixed>, writer: &mut W) -> io::Result<()> {
     writer.write(&mut (column.index as u32).to_le_bytes())?;
     Ok(())
 }
 
-fn read_fixed_column<R: std::io::Read>(reader: &mut R) -> std::io::Result<Column<Fixed>> {
+fn read_fixed_column<R: io::Read>(reader: &mut R) -> io::Result<Column<Fixed>> {
     let index = read_u32(reader)?;
     Ok(Column::<Fixed>::new(index as usize, Fixed))
 }
 
-fn write_fixed_columns<W: std::io::Write>(
+fn write_fixed_columns<W: io::Write>(
     columns: &Vec<Column<Fixed>>,
     writer: &mut W,
-) -> std::io::Result<()> {
+) -> io::Result<()> {
     writer.write(&mut (columns.len() as u32).to_le_bytes())?;
     for c in columns.iter() {
         write_fixed_column(c, writer)?;
@@ -383,7 +500,7 @@ fn write_fixed_columns<W: std::io::Write>(
     Ok(())
 }
 
-fn read_fixed_columns<R: std::io::Read>(reader: &mut R) -> std::io::Result<Vec<Column<Fixed>>> {
+fn read_fixed_columns<R: io::Read>(reader: &mut R) -> io::Result<Vec<Column<Fixed>>> {
     let len = read_u32(reader)?;
     let mut columns = vec![];
     for _ in 0..len {
@@ -392,7 +509,9 @@ fn read_fixed_columns<R: std::io::Read>(reader: &mut R) -> std::io::Result<Vec<C
     Ok(columns)
 }
 
-fn write_cs<C: CurveAffine, W: io::Write>(
+/// Writes a constraint system, with its gates, queries, lookups, permutation
+/// columns, constants, selectors and column names.
+pub fn write_cs<C: CurveAffine, W: io::Write>(
     cs: &ConstraintSystem<C::Scalar>,
     writer: &mut W,
 ) -> io::Result<()> {
@@ -414,13 +533,35 @@ fn write_cs<C: CurveAffine, W: io::Write>(
     for p in cs.lookups.iter() {
         p.input_expressions.store(writer)?;
         p.table_expressions.store(writer)?;
+        p.name.store(writer)?;
     }
     cs.named_advices.store(writer)?;
+    cs.column_names
+        .iter()
+        .map(|(column, name)| (*column, name.clone()))
+        .collect::<Vec<_>>()
+        .store(writer)?;
     write_gates::<C, W>(&cs.gates, writer)?;
+    let instance_evaluation: u32 = match cs.instance_evaluation {
+        InstanceEvaluation::Committed => 0,
+        InstanceEvaluation::Direct => 1,
+    };
+    writer.write(&mut instance_evaluation.to_le_bytes())?;
+    match cs.minimum_degree {
+        None => 0u32.store(writer)?,
+        Some(degree) => {
+            1u32.store(writer)?;
+            (degree as u32).store(writer)?;
+        }
+    }
     Ok(())
 }
 
-fn read_cs<C: CurveAffine, R: io::Read>(reader: &mut R) -> io::Result<ConstraintSystem<C::Scalar>> {
+/// Reads a constraint system as written by [`write_cs`], checking that every
+/// column and query it refers to exists.
+pub fn read_cs<C: CurveAffine, R: io::Read>(
+    reader: &mut R,
+) -> io::Result<ConstraintSystem<C::Scalar>> {
     let num_advice_columns = read_u32(reader)? as usize;
     let num_instance_columns = read_u32(reader)? as usize;
     let num_selectors = read_u32(reader)? as usize;
@@ -445,15 +586,34 @@ fn read_cs<C: CurveAffine, R: io::Read>(reader: &mut R) -> io::Result<Constraint
     for _ in 0..nb_lookup {
         let input_expressions = Vec::<Expression<C::Scalar>>::fetch(reader)?;
         let table_expressions = Vec::<Expression<C::Scalar>>::fetch(reader)?;
+        let name = String::fetch(reader)?;
         lookups.push(plonk::lookup::Argument {
-            name: "",
+            name,
             input_expressions,
             table_expressions,
         });
     }
     let named_advices = Vec::fetch(reader)?;
+    let column_names = Vec::<(Column<Any>, String)>::fetch(reader)?
+        .into_iter()
+        .collect();
     let gates = read_gates::<C, R>(reader)?;
-    Ok(ConstraintSystem {
+    let instance_evaluation = match read_u32(reader)? {
+        0 => InstanceEvaluation::Committed,
+        1 => InstanceEvaluation::Direct,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "invalid instance evaluation",
+            ))
+        }
+    };
+    let minimum_degree = match read_u32(reader)? {
+        0 => None,
+        1 => Some(read_u32(reader)? as usize),
+        _ => return Err(invalid_data("invalid minimum degree")),
+    };
+    let cs = ConstraintSystem {
         num_fixed_columns,
         num_advice_columns,
         num_instance_columns,
@@ -465,35 +625,144 @@ fn read_cs<C: CurveAffine, R: io::Read>(reader: &mut R) -> io::Result<Constraint
         instance_queries,
         fixed_queries,
         named_advices,
+        column_names,
         permutation,
         lookups,
         constants,
-        minimum_degree: None,
-    })
+        minimum_degree,
+        instance_evaluation,
+    };
+    check_cs(&cs)?;
+    Ok(cs)
 }
 
-fn write_gates<C: CurveAffine, W: std::io::Write>(
+/// Checks that every column and query referred to by a constraint system that
+/// was read exists, so that indexing with them cannot panic.
+fn check_cs<F: FieldExt>(cs: &ConstraintSystem<F>) -> io::Result<()> {
+    let column_exists = |column: &Column<Any>| {
+        column.index
+            < match column.column_type() {
+                Any::Advice => cs.num_advice_columns,
+                Any::Instance => cs.num_instance_columns,
+                Any::Fixed => cs.num_fixed_columns,
+            }
+    };
+    fn queries_exist<T: ColumnType>(queries: &[(Column<T>, Rotation)], num_columns: usize) -> bool {
+        queries.iter().all(|(column, _)| column.index < num_columns)
+    }
+    fn expression_exists<F: Field>(expression: &Expression<F>, cs: &ConstraintSystem<F>) -> bool {
+        let query_exists = |queries: usize, query_index: &usize, columns: usize, index: &usize| {
+            *query_index < queries && *index < columns
+        };
+        match expression {
+            Expression::Constant(_) => true,
+            Expression::Selector(selector) => selector.0 < cs.num_selectors,
+            Expression::Fixed {
+                query_index,
+                column_index,
+                ..
+            } => query_exists(
+                cs.fixed_queries.len(),
+                query_index,
+                cs.num_fixed_columns,
+                column_index,
+            ),
+            Expression::Advice {
+                query_index,
+                column_index,
+                ..
+            } => query_exists(
+                cs.advice_queries.len(),
+                query_index,
+                cs.num_advice_columns,
+                column_index,
+            ),
+            Expression::Instance {
+                query_index,
+                column_index,
+                ..
+            } => query_exists(
+                cs.instance_queries.len(),
+                query_index,
+                cs.num_instance_columns,
+                column_index,
+            ),
+            Expression::Negated(a) | Expression::Scaled(a, _) => expression_exists(a, cs),
+            Expression::Sum(a, b) | Expression::Product(a, b) => {
+                expression_exists(a, cs) && expression_exists(b, cs)
+            }
+        }
+    }
+
+    let valid = cs.num_advice_queries.len() == cs.num_advice_columns
+        && cs
+            .selector_map
+            .iter()
+            .chain(cs.constants.iter())
+            .all(|column| column.index < cs.num_fixed_columns)
+        && queries_exist(&cs.advice_queries, cs.num_advice_columns)
+        && queries_exist(&cs.instance_queries, cs.num_instance_columns)
+        && queries_exist(&cs.fixed_queries, cs.num_fixed_columns)
+        && cs.permutation.columns.iter().all(column_exists)
+        && cs
+            .named_advices
+            .iter()
+            .all(|(_, index)| (*index as usize) < cs.num_advice_columns)
+        && cs.column_names.keys().all(column_exists)
+        && cs.gates.iter().all(|gate| {
+            gate.polynomials()
+                .iter()
+                .all(|poly| expression_exists(poly, cs))
+                && gate
+                    .queried_cells
+                    .iter()
+                    .all(|cell| column_exists(&cell.column))
+                && gate
+                    .queried_selectors()
+                    .iter()
+                    .all(|selector| selector.0 < cs.num_selectors)
+        })
+        && cs.lookups.iter().all(|lookup| {
+            lookup.input_expressions.len() == lookup.table_expressions.len()
+                && lookup
+                    .input_expressions
+                    .iter()
+                    .chain(lookup.table_expressions.iter())
+                    .all(|expression| expression_exists(expression, cs))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid_data("constraint system refers to missing columns"))
+    }
+}
+
+fn write_gates<C: CurveAffine, W: io::Write>(
     gates: &Vec<Gate<C::Scalar>>,
     writer: &mut W,
-) -> std::io::Result<()> {
+) -> io::Result<()> {
     writer.write(&mut (gates.len() as u32).to_le_bytes())?;
     for gate in gates.iter() {
         gate.polys.store(writer)?;
         write_virtual_cells(&gate.queried_cells, writer)?;
+        gate.name().to_string().store(writer)?;
+        gate.constraint_names().to_vec().store(writer)?;
+        gate.queried_selectors().to_vec().store(writer)?;
     }
     Ok(())
 }
 
-fn read_gates<C: CurveAffine, R: std::io::Read>(
-    reader: &mut R,
-) -> std::io::Result<Vec<Gate<C::Scalar>>> {
+fn read_gates<C: CurveAffine, R: io::Read>(reader: &mut R) -> io::Result<Vec<Gate<C::Scalar>>> {
     let nb_gates = read_u32(reader)?;
     let mut gates = vec![];
     for _ in 0..nb_gates {
-        gates.push(Gate::new_with_polys_and_queries(
-            Vec::<Expression<C::Scalar>>::fetch(reader)?,
-            read_virtual_cells(reader)?,
-        ));
+        let polys = Vec::<Expression<C::Scalar>>::fetch(reader)?;
+        let queried_cells = read_virtual_cells(reader)?;
+        let name = String::fetch(reader)?;
+        let constraint_names = Vec::<String>::fetch(reader)?;
+        let queried_selectors = Vec::fetch(reader)?;
+        let gate = Gate::new_with_polys_and_queries(polys, queried_selectors, queried_cells);
+        gates.push(gate.with_names(name, constraint_names));
     }
     Ok(gates)
 }
@@ -508,6 +777,7 @@ enum ExpressionCode {
     Sum,
     Product,
     Scaled,
+    Selector,
 }
 
 fn expression_code<F: FieldExt>(e: &Expression<F>) -> ExpressionCode {
@@ -532,68 +802,89 @@ fn expression_code<F: FieldExt>(e: &Expression<F>) -> ExpressionCode {
         Expression::Sum(_, _) => ExpressionCode::Sum,
         Expression::Product(_, _) => ExpressionCode::Product,
         Expression::Scaled(_, _) => ExpressionCode::Scaled,
-        Expression::Selector(_) => unreachable!(),
+        Expression::Selector(_) => ExpressionCode::Selector,
     }
 }
 
-impl<F: FieldExt> Serializable for Expression<F> {
-    fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Expression<F>> {
-        let code = read_u32(reader)?;
-        match num::FromPrimitive::from_u32(code).unwrap() {
-            ExpressionCode::Constant => {
-                let scalar = F::read(reader)?;
-                Ok(Expression::Constant(scalar))
-            }
-            ExpressionCode::Fixed => {
-                let query_index = read_u32(reader)? as usize;
-                let column_index = read_u32(reader)? as usize;
-                let rotation = Rotation(read_u32(reader)? as i32);
-                Ok(Expression::Fixed {
-                    query_index,
-                    column_index,
-                    rotation,
-                })
-            }
-            ExpressionCode::Advice => {
-                let query_index = read_u32(reader)? as usize;
-                let column_index = read_u32(reader)? as usize;
-                let rotation = Rotation(read_u32(reader)? as i32);
-                Ok(Expression::Advice {
-                    query_index,
-                    column_index,
-                    rotation,
-                })
-            }
-            ExpressionCode::Instance => {
-                let query_index = read_u32(reader)? as usize;
-                let column_index = read_u32(reader)? as usize;
-                let rotation = Rotation(read_u32(reader)? as i32);
-                Ok(Expression::Instance {
-                    query_index,
-                    column_index,
-                    rotation,
-                })
-            }
-            ExpressionCode::Negated => Ok(Expression::Negated(Box::new(Self::fetch(reader)?))),
+/// The deepest expression that is read, so that a malicious input cannot
+/// overflow the stack.
+const MAX_EXPRESSION_DEPTH: usize = 1024;
 
-            ExpressionCode::Sum => {
-                let a = Self::fetch(reader)?;
-                let b = Self::fetch(reader)?;
-                Ok(Expression::Sum(Box::new(a), Box::new(b)))
-            }
+fn fetch_expression<F: FieldExt, R: io::Read>(
+    reader: &mut R,
+    depth: usize,
+) -> io::Result<Expression<F>> {
+    if depth > MAX_EXPRESSION_DEPTH {
+        return Err(invalid_data("expression is nested too deeply"));
+    }
+    let code = read_u32(reader)?;
+    match num_traits::FromPrimitive::from_u32(code)
+        .ok_or_else(|| invalid_data("invalid expression"))?
+    {
+        ExpressionCode::Constant => {
+            let scalar = read_scalar(reader)?;
+            Ok(Expression::Constant(scalar))
+        }
+        ExpressionCode::Fixed => {
+            let query_index = read_u32(reader)? as usize;
+            let column_index = read_u32(reader)? as usize;
+            let rotation = Rotation(read_u32(reader)? as i32);
+            Ok(Expression::Fixed {
+                query_index,
+                column_index,
+                rotation,
+            })
+        }
+        ExpressionCode::Advice => {
+            let query_index = read_u32(reader)? as usize;
+            let column_index = read_u32(reader)? as usize;
+            let rotation = Rotation(read_u32(reader)? as i32);
+            Ok(Expression::Advice {
+                query_index,
+                column_index,
+                rotation,
+            })
+        }
+        ExpressionCode::Instance => {
+            let query_index = read_u32(reader)? as usize;
+            let column_index = read_u32(reader)? as usize;
+            let rotation = Rotation(read_u32(reader)? as i32);
+            Ok(Expression::Instance {
+                query_index,
+                column_index,
+                rotation,
+            })
+        }
+        ExpressionCode::Negated => {
+            let a = fetch_expression(reader, depth + 1)?;
+            Ok(Expression::Negated(Box::new(a)))
+        }
 
-            ExpressionCode::Product => {
-                let a = Self::fetch(reader)?;
-                let b = Self::fetch(reader)?;
-                Ok(Expression::Product(Box::new(a), Box::new(b)))
-            }
+        ExpressionCode::Sum => {
+            let a = fetch_expression(reader, depth + 1)?;
+            let b = fetch_expression(reader, depth + 1)?;
+            Ok(Expression::Sum(Box::new(a), Box::new(b)))
+        }
 
-            ExpressionCode::Scaled => {
-                let a = Self::fetch(reader)?;
-                let f = F::read(reader)?;
-                Ok(Expression::Scaled(Box::new(a), f))
-            }
+        ExpressionCode::Product => {
+            let a = fetch_expression(reader, depth + 1)?;
+            let b = fetch_expression(reader, depth + 1)?;
+            Ok(Expression::Product(Box::new(a), Box::new(b)))
+        }
+
+        ExpressionCode::Scaled => {
+            let a = fetch_expression(reader, depth + 1)?;
+            let f = read_scalar(reader)?;
+            Ok(Expression::Scaled(Box::new(a), f))
         }
+
+        ExpressionCode::Selector => Ok(Expression::Selector(Selector::fetch(reader)?)),
+    }
+}
+
+impl<F: FieldExt> Serializable for Expression<F> {
+    fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Expression<F>> {
+        fetch_expression(reader, 0)
     }
 
     fn store<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
@@ -649,20 +940,27 @@ impl<F: FieldExt> Serializable for Expression<F> {
                 writer.write(&mut f.to_repr().as_ref())?;
                 Ok(())
             }
-            Expression::Selector(_) => unreachable!(),
+            Expression::Selector(selector) => selector.store(writer),
         }
     }
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub struct AssignWitnessCollection<'a, C: CurveAffine> {
     pub k: u32,
     pub advice: Vec<Polynomial<Assigned<C::Scalar>, LagrangeCoeff>>,
+    /// Whether each advice cell has been assigned, so that queries of
+    /// unassigned cells return `None` as in [`MockProver`].
+    ///
+    /// [`MockProver`]: crate::dev::MockProver
+    pub assigned: Vec<Vec<bool>>,
     pub instances: &'a [&'a [C::Scalar]],
     pub usable_rows: RangeTo<usize>,
     pub _marker: std::marker::PhantomData<C>,
 }
 
+#[cfg(feature = "std")]
 impl<'a, C: CurveAffine> Assignment<C::Scalar> for AssignWitnessCollection<'a, C> {
     fn enter_region<NR, N>(&mut self, _: N)
     where
@@ -702,6 +1000,27 @@ impl<'a, C: CurveAffine> Assignment<C::Scalar> for AssignWitnessCollection<'a, C
             .ok_or(Error::BoundsFailure)
     }
 
+    fn query_advice(
+        &self,
+        column: Column<Advice>,
+        row: usize,
+    ) -> Result<Option<C::Scalar>, Error> {
+        if !self.usable_rows.contains(&row) {
+            return Err(Error::not_enough_rows_available(self.k));
+        }
+
+        self.advice
+            .get(column.index())
+            .and_then(|column| column.get(row))
+            .zip(
+                self.assigned
+                    .get(column.index())
+                    .and_then(|column| column.get(row)),
+            )
+            .map(|(v, assigned)| if *assigned { Some(v.evaluate()) } else { None })
+            .ok_or(Error::BoundsFailure)
+    }
+
     fn assign_advice<V, VR, A, AR>(
         &mut self,
         _: A,
@@ -724,6 +1043,7 @@ impl<'a, C: CurveAffine> Assignment<C::Scalar> for AssignWitnessCollection<'a, C
             .get_mut(column.index())
             .and_then(|v| v.get_mut(row))
             .ok_or(Error::BoundsFailure)? = to()?.into();
+        self.assigned[column.index()][row] = true;
 
         Ok(())
     }
@@ -746,6 +1066,42 @@ impl<'a, C: CurveAffine> Assignment<C::Scalar> for AssignWitnessCollection<'a, C
         Ok(())
     }
 
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        row: usize,
+        values: &[C::Scalar],
+    ) -> Result<(), Error> {
+        if row + values.len() > self.usable_rows.end {
+            return Err(Error::not_enough_rows_available(self.k));
+        }
+
+        let cells = self
+            .advice
+            .get_mut(column.index())
+            .and_then(|v| v[..].get_mut(row..row + values.len()))
+            .ok_or(Error::BoundsFailure)?;
+        for (cell, value) in cells.iter_mut().zip(values.iter()) {
+            *cell = Assigned::Trivial(*value);
+        }
+        for assigned in &mut self.assigned[column.index()][row..row + values.len()] {
+            *assigned = true;
+        }
+
+        Ok(())
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: &[C::Scalar],
+    ) -> Result<(), Error> {
+        // We only care about advice columns here
+
+        Ok(())
+    }
+
     fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
         // We only care about advice columns here
 
@@ -786,6 +1142,7 @@ impl<B: Clone, F: FieldExt> Serializable for Polynomial<Assigned<F>, B> {
     }
 }
 
+#[cfg(feature = "std")]
 impl ParaSerializable for Assembly {
     fn vec_fetch(fd: &mut File) -> io::Result<Self> {
         let assembly = Assembly {
@@ -807,16 +1164,497 @@ impl ParaSerializable for Assembly {
     }
 }
 
+#[cfg(feature = "std")]
+const WITNESS_MAGIC: [u8; 4] = *b"H2WT";
+#[cfg(feature = "std")]
+const WITNESS_VERSION: u32 = 4;
+#[cfg(feature = "std")]
+const WITNESS_ENDIANNESS: u32 = 0x0102_0304;
+
+/// The encoding of the advice columns of a witness file.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WitnessEncoding {
+    /// Every cell is stored as a scalar in its canonical representation, so
+    /// that every column has the same length.
+    Raw,
+    /// Each column is stored as runs of zero rows, each followed by a run of
+    /// non-zero cells packed to the bit width of the column.
+    Compact,
+}
+
+#[cfg(feature = "std")]
+impl WitnessEncoding {
+    fn code(self) -> u32 {
+        match self {
+            WitnessEncoding::Raw => 0,
+            WitnessEncoding::Compact => 1,
+        }
+    }
+
+    fn from_code(code: u32) -> Option<Self> {
+        match code {
+            0 => Some(WitnessEncoding::Raw),
+            1 => Some(WitnessEncoding::Compact),
+            _ => None,
+        }
+    }
+}
+
+/// A circuit instance read from a witness file.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct StoredWitness<F: FieldExt> {
+    /// The values of the instance columns.
+    pub instances: Vec<Vec<F>>,
+    /// The advice columns. Their unusable rows are zero.
+    pub advice: Vec<Polynomial<F, LagrangeCoeff>>,
+    /// The number of bits of the largest cell of each advice column.
+    pub max_bits: Vec<usize>,
+}
+
+/// Identifies a curve by the BLAKE2b-256 hash of its base and scalar moduli.
+#[cfg(feature = "std")]
+fn witness_curve_id<C: CurveAffine>() -> [u8; 32] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Halo2-Curve-Id!!")
+        .to_state()
+        .update(C::Base::MODULUS.as_bytes())
+        .update(&[0])
+        .update(C::Scalar::MODULUS.as_bytes())
+        .finalize();
+    hash.as_bytes().try_into().unwrap()
+}
+
+#[cfg(feature = "std")]
+fn witness_checksum<F>(values: &[F], max_bits: u32) -> [u8; 32] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Halo2-WitnessCol")
+        .to_state()
+        .update(scalar_bytes(values))
+        .update(&max_bits.to_le_bytes())
+        .finalize();
+    hash.as_bytes().try_into().unwrap()
+}
+
+/// Returns the size in bytes of the canonical representation of a scalar.
+#[cfg(feature = "std")]
+fn repr_size<F: FieldExt>() -> usize {
+    F::Repr::default().as_ref().len()
+}
+
+/// Returns the in-memory representation of `values`.
+#[cfg(feature = "std")]
+fn scalar_bytes<F>(values: &[F]) -> &[u8] {
+    unsafe {
+        std::slice::from_raw_parts(
+            values.as_ptr() as *const u8,
+            values.len() * std::mem::size_of::<F>(),
+        )
+    }
+}
+
+/// Returns the number of bits of the canonical representation of `value`.
+#[cfg(feature = "std")]
+pub(crate) fn scalar_bits<F: FieldExt>(value: &F) -> usize {
+    let repr = value.to_repr();
+    repr.as_ref()
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, byte)| **byte != 0)
+        .map_or(0, |(i, byte)| i * 8 + 8 - byte.leading_zeros() as usize)
+}
+
+/// Writes the low `width` bits of the little-endian `value` to `out`,
+/// starting at bit `offset`. These bits of `out` must be zero.
+#[cfg(feature = "std")]
+fn write_bits(out: &mut [u8], offset: usize, value: &[u8], width: usize) {
+    let shift = offset % 8;
+    let mut pos = offset / 8;
+    let mut remaining = width;
+    for &byte in value.iter() {
+        if remaining == 0 {
+            break;
+        }
+        let take = remaining.min(8);
+        let bits = (byte as u16 & ((1 << take) - 1)) << shift;
+        out[pos] |= bits as u8;
+        if shift + take > 8 {
+            out[pos + 1] |= (bits >> 8) as u8;
+        }
+        pos += 1;
+        remaining -= take;
+    }
+}
+
+/// Reads `width` bits of `bytes`, starting at bit `offset`, into the
+/// little-endian `value`.
+#[cfg(feature = "std")]
+fn read_bits(bytes: &[u8], offset: usize, width: usize, value: &mut [u8]) {
+    let shift = offset % 8;
+    let mut pos = offset / 8;
+    let mut remaining = width;
+    for byte in value.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+        let take = remaining.min(8);
+        let mut bits = (bytes[pos] as u16) >> shift;
+        if shift + take > 8 {
+            bits |= (bytes[pos + 1] as u16) << (8 - shift);
+        }
+        *byte = (bits & ((1 << take) - 1)) as u8;
+        pos += 1;
+        remaining -= take;
+    }
+}
+
+/// Encodes `column` with [`WitnessEncoding::Compact`]: a sequence of runs,
+/// each made of the number of zero rows and the number of non-zero cells
+/// that follow them, as little-endian `u32`s, and then those cells packed to
+/// `width` bits each and padded to a whole byte.
+#[cfg(feature = "std")]
+fn encode_compact<F: FieldExt>(column: &[F], width: usize) -> Vec<u8> {
+    let mut out = vec![];
+    let mut row = 0;
+    while row < column.len() {
+        let zeros_start = row;
+        while row < column.len() && column[row] == F::zero() {
+            row += 1;
+        }
+        let cells_start = row;
+        while row < column.len() && column[row] != F::zero() {
+            row += 1;
+        }
+
+        out.extend_from_slice(&((cells_start - zeros_start) as u32).to_le_bytes());
+        out.extend_from_slice(&((row - cells_start) as u32).to_le_bytes());
+        let packed_start = out.len();
+        out.resize(packed_start + ((row - cells_start) * width + 7) / 8, 0);
+        for (i, cell) in column[cells_start..row].iter().enumerate() {
+            write_bits(
+                &mut out[packed_start..],
+                i * width,
+                cell.to_repr().as_ref(),
+                width,
+            );
+        }
+    }
+    out
+}
+
+/// Decodes a column of `n` rows written by [`encode_compact`], or returns
+/// `None` if `bytes` is not a valid encoding.
+#[cfg(feature = "std")]
+fn decode_compact<F: FieldExt>(bytes: &[u8], n: usize, width: usize) -> Option<Vec<F>> {
+    if width > F::Repr::default().as_ref().len() * 8 {
+        return None;
+    }
+
+    let mut column = vec![F::zero(); n];
+    let mut row = 0;
+    let mut pos = 0;
+    while row < n {
+        let zeros = u32::from_le_bytes(bytes.get(pos..pos + 4)?.try_into().unwrap()) as usize;
+        let count = u32::from_le_bytes(bytes.get(pos + 4..pos + 8)?.try_into().unwrap()) as usize;
+        pos += 8;
+        if zeros == 0 && count == 0 {
+            return None;
+        }
+        row = row.checked_add(zeros)?;
+        if count > n.checked_sub(row)? {
+            return None;
+        }
+
+        let packed = bytes.get(pos..pos + (count * width + 7) / 8)?;
+        for (i, cell) in column[row..row + count].iter_mut().enumerate() {
+            let mut repr = F::Repr::default();
+            read_bits(packed, i * width, width, repr.as_mut());
+            *cell = Option::from(F::from_repr(repr))?;
+        }
+        pos += packed.len();
+        row += count;
+    }
+
+    if pos == bytes.len() {
+        Some(column)
+    } else {
+        None
+    }
+}
+
+/// The description of an advice column in the header of a witness file.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct WitnessColumn {
+    checksum: [u8; 32],
+    max_bits: u32,
+    len: u64,
+}
+
+/// The header of a witness, see [`AssignWitnessCollection::store_witness`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct WitnessHeader<F> {
+    k: u32,
+    scalar_size: u32,
+    encoding: WitnessEncoding,
+    curve_id: [u8; 32],
+    vk_digest: [u8; 32],
+    columns: Vec<WitnessColumn>,
+    instances: Vec<Vec<F>>,
+}
+
+#[cfg(feature = "std")]
+impl<F: FieldExt> WitnessHeader<F> {
+    fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&WITNESS_MAGIC)?;
+        writer.write_all(&WITNESS_VERSION.to_le_bytes())?;
+        writer.write_all(&WITNESS_ENDIANNESS.to_ne_bytes())?;
+        writer.write_all(&self.k.to_le_bytes())?;
+        writer.write_all(&self.scalar_size.to_le_bytes())?;
+        writer.write_all(&self.encoding.code().to_le_bytes())?;
+        writer.write_all(&self.curve_id)?;
+        writer.write_all(&self.vk_digest)?;
+        writer.write_all(&(self.columns.len() as u32).to_le_bytes())?;
+        for column in self.columns.iter() {
+            writer.write_all(&column.checksum)?;
+            writer.write_all(&column.max_bits.to_le_bytes())?;
+            writer.write_all(&column.len.to_le_bytes())?;
+        }
+
+        writer.write_all(&(self.instances.len() as u32).to_le_bytes())?;
+        for values in self.instances.iter() {
+            writer.write_all(&(values.len() as u32).to_le_bytes())?;
+        }
+        let instances = self.instances.concat();
+        writer.write_all(&witness_checksum(&instances[..], 0))?;
+        for value in instances.iter() {
+            writer.write_all(value.to_repr().as_ref())?;
+        }
+        Ok(())
+    }
+
+    fn read<R: io::Read>(reader: &mut R) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidWitness(reason.to_string());
+        let read_bytes = |reader: &mut R, buf: &mut [u8]| {
+            reader
+                .read_exact(buf)
+                .map_err(|e| Error::InvalidWitness(format!("truncated header: {}", e)))
+        };
+        let read_u32 = |reader: &mut R| -> Result<u32, Error> {
+            let mut buf = [0u8; 4];
+            read_bytes(reader, &mut buf)?;
+            Ok(u32::from_le_bytes(buf))
+        };
+
+        let mut magic = [0u8; 4];
+        read_bytes(reader, &mut magic)?;
+        if magic != WITNESS_MAGIC {
+            return Err(invalid("not a witness file"));
+        }
+        let version = read_u32(reader)?;
+        if version != WITNESS_VERSION {
+            return Err(Error::InvalidWitness(format!(
+                "unsupported version {}, expected {}",
+                version, WITNESS_VERSION
+            )));
+        }
+        let mut endianness = [0u8; 4];
+        read_bytes(reader, &mut endianness)?;
+        if u32::from_ne_bytes(endianness) != WITNESS_ENDIANNESS {
+            return Err(invalid("written on a machine with a different byte order"));
+        }
+
+        let k = read_u32(reader)?;
+        let scalar_size = read_u32(reader)?;
+        let encoding = WitnessEncoding::from_code(read_u32(reader)?)
+            .ok_or_else(|| invalid("unknown column encoding"))?;
+        let mut curve_id = [0u8; 32];
+        read_bytes(reader, &mut curve_id)?;
+        let mut vk_digest = [0u8; 32];
+        read_bytes(reader, &mut vk_digest)?;
+
+        let num_columns = read_u32(reader)?;
+        let columns = (0..num_columns)
+            .map(|_| -> Result<WitnessColumn, Error> {
+                let mut checksum = [0u8; 32];
+                read_bytes(reader, &mut checksum)?;
+                let max_bits = read_u32(reader)?;
+                let mut len = [0u8; 8];
+                read_bytes(reader, &mut len)?;
+                Ok(WitnessColumn {
+                    checksum,
+                    max_bits,
+                    len: u64::from_le_bytes(len),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        // The size of the canonical representation of the instance values
+        // depends on the curve they were written for.
+        if scalar_size as usize != repr_size::<F>() {
+            return Err(invalid("created for a different curve"));
+        }
+        let num_instance_columns = read_u32(reader)?;
+        let lengths = (0..num_instance_columns)
+            .map(|_| -> Result<usize, Error> {
+                let len = read_u32(reader)? as u64;
+                if k >= 32 || len > 1 << k {
+                    return Err(invalid("more instance values than rows"));
+                }
+                Ok(len as usize)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut checksum = [0u8; 32];
+        read_bytes(reader, &mut checksum)?;
+        // The values are only allocated as they are read, so that a corrupted
+        // length cannot allocate more than the file holds.
+        let instances = lengths
+            .into_iter()
+            .map(|len| {
+                (0..len)
+                    .map(|_| {
+                        read_scalar(reader).map_err(|e| {
+                            Error::InvalidWitness(format!("invalid instance value: {}", e))
+                        })
+                    })
+                    .collect::<Result<Vec<F>, Error>>()
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        if witness_checksum(&instances.concat()[..], 0) != checksum {
+            return Err(invalid("checksum mismatch in instance values"));
+        }
+
+        Ok(WitnessHeader {
+            k,
+            scalar_size,
+            encoding,
+            curve_id,
+            vk_digest,
+            columns,
+            instances,
+        })
+    }
+
+    /// Checks that the witness was created for `pk` and `params`.
+    fn validate<C: CurveAffine>(
+        &self,
+        params: &Params<C>,
+        pk: &ProvingKey<C>,
+    ) -> Result<(), Error> {
+        let vk = pk.get_vk();
+        if self.curve_id != witness_curve_id::<C>()
+            || self.scalar_size as usize != repr_size::<C::Scalar>()
+        {
+            return Err(Error::InvalidWitness(
+                "created for a different curve".to_string(),
+            ));
+        }
+        if self.k != params.k || self.k != vk.domain.k() {
+            return Err(Error::InvalidWitness(format!(
+                "created for k = {}, but the parameters have k = {} and the proving key k = {}",
+                self.k,
+                params.k,
+                vk.domain.k()
+            )));
+        }
+        if self.vk_digest != vk.digest() {
+            return Err(Error::InvalidWitness(
+                "created for a different circuit".to_string(),
+            ));
+        }
+        if self.columns.len() != vk.cs.num_advice_columns {
+            return Err(Error::InvalidWitness(format!(
+                "has {} advice columns, but the circuit has {}",
+                self.columns.len(),
+                vk.cs.num_advice_columns
+            )));
+        }
+        if self.instances.len() != vk.cs.num_instance_columns {
+            return Err(Error::InvalidWitness(format!(
+                "has {} instance columns, but the circuit has {}",
+                self.instances.len(),
+                vk.cs.num_instance_columns
+            )));
+        }
+
+        let column_size = (1u64 << self.k) * repr_size::<C::Scalar>() as u64;
+        for (i, column) in self.columns.iter().enumerate() {
+            let valid_len = match self.encoding {
+                WitnessEncoding::Raw => column.len == column_size,
+                WitnessEncoding::Compact => column.len >= 8,
+            };
+            if !valid_len {
+                return Err(Error::InvalidWitness(format!(
+                    "invalid length of advice column {}",
+                    i
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the position in `fd` of the end of the advice columns that
+    /// follow this header, checking that the file is long enough.
+    fn data_end(&self, fd: &mut File) -> Result<u64, Error> {
+        let data_end = self
+            .columns
+            .iter()
+            .try_fold(fd.stream_position()?, |end, column| end.checked_add(column.len));
+        match data_end {
+            Some(data_end) if data_end <= fd.metadata()?.len() => Ok(data_end),
+            _ => Err(Error::InvalidWitness("truncated advice columns".to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl<'a, C: CurveAffine> AssignWitnessCollection<'a, C> {
+    /// Synthesizes `circuit` and writes its witness to `fd`, at the current
+    /// position. A witness bundle is a sequence of such witnesses, one for
+    /// each circuit instance of a proof.
+    ///
+    /// The witness starts with a header, in which all integers are
+    /// little-endian:
+    ///
+    /// | bytes      | content                                                   |
+    /// |------------|-----------------------------------------------------------|
+    /// | 4          | magic `H2WT`                                              |
+    /// | 4          | format version, currently 4                               |
+    /// | 4          | `0x01020304`, in the byte order of the prover             |
+    /// | 4          | `k`                                                       |
+    /// | 4          | size in bytes of a scalar `s`                             |
+    /// | 4          | encoding of the advice columns, 0 = raw, 1 = compact      |
+    /// | 32         | curve id: BLAKE2b-256 of the base and scalar moduli       |
+    /// | 32         | digest of the verifying key, see [`VerifyingKey::digest`] |
+    /// | 4          | number of advice columns `m`                              |
+    /// | 44 * `m`   | for each advice column: BLAKE2b-256 checksum of its cells |
+    /// |            | and bit width (32), bit width of its largest cell (4) and |
+    /// |            | length in bytes of its encoding (8)                       |
+    /// | 4          | number of instance columns `p`                            |
+    /// | 4 * `p`    | number of values `l_i` in each instance column            |
+    /// | 32         | BLAKE2b-256 checksum of the instance values               |
+    /// | s * Σ l_i  | the instance values, column after column                  |
+    ///
+    /// Scalars are written in their canonical representation, as returned by
+    /// `to_repr`. The header is followed by the `m` advice columns. With
+    /// [`WitnessEncoding::Raw`], each is made of the `2^k` scalars. With
+    /// [`WitnessEncoding::Compact`], each is a sequence of runs of zero rows,
+    /// each followed by the number of non-zero cells after them and those
+    /// cells, truncated to the bit width of the column and packed together.
     pub fn store_witness<ConcreteCircuit: Circuit<C::Scalar>>(
         params: &Params<C>,
         pk: &ProvingKey<C>,
         instances: &[&[C::Scalar]],
         unusable_rows_start: usize,
         circuit: &ConcreteCircuit,
+        encoding: WitnessEncoding,
         fd: &mut File,
     ) -> Result<(), Error> {
-        use std::io::prelude::*;
         let mut meta = ConstraintSystem::default();
         let config = ConcreteCircuit::configure(&mut meta);
 
@@ -825,6 +1663,7 @@ impl<'a, C: CurveAffine> AssignWitnessCollection<'a, C> {
         let mut witness = AssignWitnessCollection::<C> {
             k: params.k,
             advice: vec![domain.empty_lagrange_assigned(); meta.num_advice_columns],
+            assigned: vec![vec![false; params.n as usize]; meta.num_advice_columns],
             instances,
             // The prover will not be allowed to assign values to advice
             // cells that exist within inactive rows, which include some
@@ -842,62 +1681,163 @@ impl<'a, C: CurveAffine> AssignWitnessCollection<'a, C> {
             meta.constants.clone(),
         )?;
 
-        let bundlesize = params.k + 5;
         let advice = batch_invert_assigned(witness.advice);
-        fd.set_len(4 + (1u64 << bundlesize)).unwrap();
-        fd.write(&(advice.len() as u32).to_le_bytes())?;
-        fd.set_len(4 + ((advice.len() as u64) << bundlesize))
-            .unwrap();
-        {
-            advice.into_par_iter().enumerate().for_each(|(i, s2)| {
-                let mut mmap = unsafe {
-                    MmapOptions::new()
-                        .offset(4 + ((i as u64) << bundlesize))
-                        .len(1 << bundlesize)
-                        .map_mut(&fd)
-                        .unwrap()
-                };
-                let s: &[u8] = unsafe {
-                    std::slice::from_raw_parts(
-                        s2.as_ptr() as *const C::Scalar as *const u8,
-                        1 << bundlesize,
-                    )
-                };
-                (&mut mmap).copy_from_slice(s);
-            });
+        let max_bits: Vec<usize> = advice
+            .par_iter()
+            .map(|column| column.iter().map(scalar_bits).max().unwrap_or(0))
+            .collect();
+        let column_size = (1u64 << params.k) * repr_size::<C::Scalar>() as u64;
+        let encoded: Vec<Vec<u8>> = match encoding {
+            WitnessEncoding::Raw => vec![],
+            WitnessEncoding::Compact => advice
+                .par_iter()
+                .zip(max_bits.par_iter())
+                .map(|(column, max_bits)| encode_compact(&column[..], *max_bits))
+                .collect(),
+        };
+
+        let header = WitnessHeader {
+            k: params.k,
+            scalar_size: repr_size::<C::Scalar>() as u32,
+            encoding,
+            curve_id: witness_curve_id::<C>(),
+            vk_digest: pk.get_vk().digest(),
+            columns: advice
+                .par_iter()
+                .zip(max_bits.par_iter())
+                .enumerate()
+                .map(|(i, (column, max_bits))| WitnessColumn {
+                    checksum: witness_checksum(&column[..], *max_bits as u32),
+                    max_bits: *max_bits as u32,
+                    len: encoded.get(i).map_or(column_size, |e| e.len() as u64),
+                })
+                .collect(),
+            instances: instances.iter().map(|values| values.to_vec()).collect(),
+        };
+        header.write(fd)?;
+
+        match encoding {
+            WitnessEncoding::Raw => {
+                let data_start = fd.stream_position()?;
+                let data_end = data_start + advice.len() as u64 * column_size;
+                fd.set_len(data_end)?;
+                advice
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(i, s2)| -> io::Result<()> {
+                        let mut mmap = unsafe {
+                            MmapOptions::new()
+                                .offset(data_start + i as u64 * column_size)
+                                .len(column_size as usize)
+                                .map_mut(&fd)?
+                        };
+                        for (bytes, value) in mmap
+                            .chunks_exact_mut(repr_size::<C::Scalar>())
+                            .zip(s2.iter())
+                        {
+                            bytes.copy_from_slice(value.to_repr().as_ref());
+                        }
+                        mmap.flush()
+                    })
+                    .collect::<io::Result<()>>()?;
+                fd.seek(SeekFrom::Start(data_end))?;
+            }
+            WitnessEncoding::Compact => {
+                for column in encoded.iter() {
+                    fd.write_all(column)?;
+                }
+            }
         }
-        println!("witness stored!");
 
-        //witness.advice.store(writer)?;
         Ok(())
     }
 
+    /// Reads a witness written by [`Self::store_witness`] from the current
+    /// position of `fd`, checking that it was created for `params` and `pk`
+    /// and that it matches its checksums. The advice columns are decoded in
+    /// parallel.
     pub fn fetch_witness(
         params: &Params<C>,
+        pk: &ProvingKey<C>,
         fd: &mut File,
-    ) -> Result<Vec<Polynomial<C::Scalar, LagrangeCoeff>>, Error> {
-        let len = read_u32(fd)?;
-        let bundlesize = params.k + 5;
-        let advice: Vec<Polynomial<_, LagrangeCoeff>> = (0..len)
-            .into_par_iter()
-            .map(|i| {
+    ) -> Result<StoredWitness<C::Scalar>, Error> {
+        let header = WitnessHeader::read(fd)?;
+        header.validate(params, pk)?;
+
+        let n = 1usize << params.k;
+        let data_end = header.data_end(fd)?;
+        let offsets: Vec<u64> = header
+            .columns
+            .iter()
+            .scan(fd.stream_position()?, |offset, column| {
+                let start = *offset;
+                *offset += column.len;
+                Some(start)
+            })
+            .collect();
+
+        let advice = header
+            .columns
+            .par_iter()
+            .zip(offsets.par_iter())
+            .enumerate()
+            .map(|(i, (column, offset))| -> Result<_, Error> {
                 let mmap = unsafe {
                     MmapOptions::new()
-                        .offset(4 + ((i as u64) << bundlesize))
-                        .len(1 << bundlesize)
-                        .map(&fd)
-                        .unwrap()
+                        .offset(*offset)
+                        .len(column.len as usize)
+                        .map(&fd)?
                 };
-                let s: &[C::Scalar] = unsafe {
-                    std::slice::from_raw_parts(mmap.as_ptr() as *const C::Scalar, 1 << params.k)
+                let values = match header.encoding {
+                    WitnessEncoding::Raw => mmap
+                        .chunks_exact(repr_size::<C::Scalar>())
+                        .map(|mut bytes| read_scalar::<C::Scalar, _>(&mut bytes))
+                        .collect::<io::Result<Vec<_>>>()
+                        .map_err(|_| {
+                            Error::InvalidWitness(format!("invalid advice column {}", i))
+                        })?,
+                    WitnessEncoding::Compact => {
+                        decode_compact(&mmap[..], n, column.max_bits as usize).ok_or_else(
+                            || Error::InvalidWitness(format!("invalid advice column {}", i)),
+                        )?
+                    }
                 };
-                let mut s2 = vec![];
-                s2.extend_from_slice(s);
-                Polynomial::new(s2)
+                if witness_checksum(&values[..], column.max_bits) != column.checksum {
+                    return Err(Error::InvalidWitness(format!(
+                        "checksum mismatch in advice column {}",
+                        i
+                    )));
+                }
+                Ok(Polynomial::new(values))
             })
-            .collect();
+            .collect::<Result<Vec<_>, Error>>()?;
+        fd.seek(SeekFrom::Start(data_end))?;
+
+        Ok(StoredWitness {
+            instances: header.instances,
+            advice,
+            max_bits: header
+                .columns
+                .iter()
+                .map(|column| column.max_bits as usize)
+                .collect(),
+        })
+    }
+
+    /// Reads only the instance values of a witness written by
+    /// [`Self::store_witness`] from the current position of `fd`, and skips
+    /// its advice columns.
+    pub fn fetch_witness_instances(
+        params: &Params<C>,
+        pk: &ProvingKey<C>,
+        fd: &mut File,
+    ) -> Result<Vec<Vec<C::Scalar>>, Error> {
+        let header = WitnessHeader::read(fd)?;
+        header.validate(params, pk)?;
+        let data_end = header.data_end(fd)?;
+        fd.seek(SeekFrom::Start(data_end))?;
 
-        Ok(advice)
+        Ok(header.instances)
     }
 }
 
@@ -919,15 +1859,17 @@ fn assigned_code<F: FieldExt>(e: &Assigned<F>) -> AssignedCode {
 impl<F: FieldExt> Serializable for Assigned<F> {
     fn fetch<R: io::Read>(reader: &mut R) -> io::Result<Assigned<F>> {
         let code = read_u32(reader)?;
-        match num::FromPrimitive::from_u32(code).unwrap() {
+        match num_traits::FromPrimitive::from_u32(code)
+            .ok_or_else(|| invalid_data("invalid assigned value"))?
+        {
             AssignedCode::Zero => Ok(Assigned::Zero),
             AssignedCode::Trivial => {
-                let scalar = F::read(reader)?;
+                let scalar = read_scalar(reader)?;
                 Ok(Assigned::Trivial(scalar))
             }
             AssignedCode::Rational => {
-                let p = F::read(reader)?;
-                let q = F::read(reader)?;
+                let p = read_scalar(reader)?;
+                let q = read_scalar(reader)?;
                 Ok(Assigned::Rational(p, q))
             }
         }
@@ -950,6 +1892,7 @@ impl<F: FieldExt> Serializable for Assigned<F> {
     }
 }
 
+#[cfg(feature = "std")]
 pub fn store_pk_info<C: CurveAffine, ConcreteCircuit>(
     params: &Params<C>,
     vk: &VerifyingKey<C>,
@@ -973,6 +1916,41 @@ where
     Ok(())
 }
 
+/// Checks that the fixed columns and permutation read by [`fetch_pk_info`] fit
+/// the circuit of `vk`, so that building the proving key from them cannot
+/// panic.
+#[cfg(feature = "std")]
+fn check_pk_info<C: CurveAffine>(
+    params: &Params<C>,
+    vk: &VerifyingKey<C>,
+    fixed: &[Polynomial<C::Scalar, LagrangeCoeff>],
+    permutation: &Assembly,
+) -> io::Result<()> {
+    let n = params.n as usize;
+    let num_columns = vk.cs.permutation.columns.len();
+    if vk.domain.k() != params.k {
+        return Err(invalid_data("verifying key does not match the parameters"));
+    }
+    if vk.cs.num_selectors != 0 {
+        return Err(invalid_data("circuits with selectors are not supported"));
+    }
+    if fixed.len() != vk.cs.num_fixed_columns || fixed.iter().any(|poly| poly.len() != n) {
+        return Err(invalid_data("fixed columns do not match the circuit"));
+    }
+    if permutation.mapping.len() != num_columns
+        || permutation.mapping.iter().any(|column| {
+            column.len() != n
+                || column
+                    .iter()
+                    .any(|(column, row)| *column as usize >= num_columns || *row as usize >= n)
+        })
+    {
+        return Err(invalid_data("permutation does not match the circuit"));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
 pub fn fetch_pk_info<C: CurveAffine>(
     params: &Params<C>,
     vk: &VerifyingKey<C>,
@@ -985,6 +1963,240 @@ pub fn fetch_pk_info<C: CurveAffine>(
     let timer = start_timer!(|| "test fetch permutation ...");
     let permutation = Assembly::vec_fetch(reader)?;
     end_timer!(timer);
-    let pkey = keygen_pk_from_info(params, vk, fixed, permutation).unwrap();
+    check_pk_info(params, vk, &fixed, &permutation)?;
+    let pkey = keygen_pk_from_info(params, vk.clone(), fixed, permutation)
+        .map_err(|e| invalid_data(&format!("invalid proving key info: {}", e)))?;
     Ok(pkey)
 }
+
+#[cfg(test)]
+mod tests {
+    use pairing::bn256::{Fr, G1Affine};
+
+    use super::{read_cs, write_cs};
+    use crate::{
+        plonk::{ConstraintSystem, Expression},
+        poly::Rotation,
+    };
+
+    #[test]
+    fn cs_names_round_trip() {
+        let mut cs = ConstraintSystem::<Fr>::default();
+        let a = cs.named_advice_column("a".to_owned());
+        let b = cs.advice_column();
+        let f = cs.named_fixed_column("f");
+        let i = cs.named_instance_column("i");
+        cs.annotate_column(b, "b");
+        cs.create_gate("sum", |cells| {
+            let a = cells.query_advice(a, Rotation::cur());
+            let b = cells.query_advice(b, Rotation::cur());
+            let f = cells.query_fixed(f, Rotation::cur());
+            vec![("a + b = f", a + b - f)]
+        });
+        cs.lookup_any("instance", |cells| {
+            vec![(
+                cells.query_advice(a, Rotation::cur()),
+                cells.query_instance(i, Rotation::cur()),
+            )]
+        });
+
+        let mut bytes = vec![];
+        write_cs::<G1Affine, _>(&cs, &mut bytes).unwrap();
+        let read = read_cs::<G1Affine, _>(&mut &bytes[..]).unwrap();
+
+        assert_eq!(read.column_name(a), Some("a"));
+        assert_eq!(read.column_name(b), Some("b"));
+        assert_eq!(read.column_name(f), Some("f"));
+        assert_eq!(read.column_name(i), Some("i"));
+        assert_eq!(read.named_advices, cs.named_advices);
+        assert_eq!(read.gates[0].name(), "sum");
+        assert_eq!(read.gates[0].constraint_name(0), "a + b = f");
+        assert_eq!(read.lookups[0].name, "instance");
+        assert!(matches!(
+            read.lookups[0].table_expressions[0],
+            Expression::Instance { .. }
+        ));
+    }
+
+    #[test]
+    fn cs_round_trip() {
+        let mut cs = ConstraintSystem::<Fr>::default();
+        let a = cs.advice_column();
+        let f = cs.fixed_column();
+        let s = cs.selector();
+        let t = cs.complex_selector();
+        cs.enable_constant(f);
+        cs.enable_equality(a);
+        cs.set_minimum_degree(5);
+        cs.create_gate("selected", |cells| {
+            let a = cells.query_advice(a, Rotation::cur());
+            let s = cells.query_selector(s);
+            let t = cells.query_selector(t);
+            vec![s * a.clone(), t * a]
+        });
+
+        let mut bytes = vec![];
+        write_cs::<G1Affine, _>(&cs, &mut bytes).unwrap();
+        let read = read_cs::<G1Affine, _>(&mut &bytes[..]).unwrap();
+
+        assert_eq!(format!("{:?}", read), format!("{:?}", cs));
+        assert_eq!(read.degree(), 5);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use ff::Field;
+    use group::GroupEncoding;
+    use pairing::bn256::{Bn256, Fr, G1Affine};
+    use proptest::{collection::vec, prelude::*, sample::Index};
+
+    use super::{read_cs, read_scalar, write_cs, CurveRead, Serializable};
+    use crate::{
+        plonk::{Assigned, ConstraintSystem, Expression, VerifyingKey},
+        poly::{
+            commitment::{Params, ParamsVerifier},
+            LagrangeCoeff, Polynomial, Rotation,
+        },
+    };
+
+    fn cs_bytes() -> Vec<u8> {
+        let mut cs = ConstraintSystem::<Fr>::default();
+        let a = cs.named_advice_column("a".to_owned());
+        let f = cs.fixed_column();
+        let i = cs.instance_column();
+        cs.enable_equality(a);
+        cs.create_gate("square", |cells| {
+            let a = cells.query_advice(a, Rotation::cur());
+            let next = cells.query_advice(a, Rotation::next());
+            let f = cells.query_fixed(f, Rotation::cur());
+            vec![f * (a.clone() * a - next)]
+        });
+        cs.lookup_any("instance", |cells| {
+            vec![(
+                cells.query_advice(a, Rotation::cur()),
+                cells.query_instance(i, Rotation::cur()),
+            )]
+        });
+
+        let mut bytes = vec![];
+        write_cs::<G1Affine, _>(&cs, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// Returns a verifying key as written by [`Serializable::store`], with
+    /// the generator as every commitment.
+    fn vk_bytes() -> Vec<u8> {
+        let cs_bytes = cs_bytes();
+        let cs = read_cs::<G1Affine, _>(&mut &cs_bytes[..]).unwrap();
+        let mut bytes = b"H2VK".to_vec();
+        1u32.store(&mut bytes).unwrap();
+        (cs.degree() as u32).store(&mut bytes).unwrap();
+        4u32.store(&mut bytes).unwrap();
+        bytes.extend_from_slice(&cs_bytes);
+        for _ in 0..cs.num_fixed_columns + cs.permutation.columns.len() {
+            bytes.extend_from_slice(G1Affine::generator().to_bytes().as_ref());
+        }
+        bytes
+    }
+
+    fn params_bytes() -> Vec<u8> {
+        let params = Params::<G1Affine>::unsafe_setup::<Bn256>(2);
+        let mut bytes = vec![];
+        params.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn flip(bytes: &mut [u8], index: Index, bit: u8) {
+        let index = index.index(bytes.len());
+        bytes[index] ^= 1 << (bit % 8);
+    }
+
+    #[test]
+    fn valid_inputs_are_read() {
+        assert!(VerifyingKey::<G1Affine>::fetch(&mut &vk_bytes()[..]).is_ok());
+        assert!(Params::<G1Affine>::read(&params_bytes()[..]).is_ok());
+
+        let poly = Polynomial::<Fr, LagrangeCoeff>::new(vec![Fr::zero(), Fr::one(), -Fr::one()]);
+        let mut bytes = vec![];
+        poly.store(&mut bytes).unwrap();
+        let read = Polynomial::<Fr, LagrangeCoeff>::fetch(&mut &bytes[..]).unwrap();
+        assert_eq!(&read[..], &poly[..]);
+    }
+
+    #[test]
+    fn non_canonical_encodings_are_rejected() {
+        // The modulus of the scalar field is below 2^254.
+        assert!(read_scalar::<Fr, _>(&mut &[0xff; 32][..]).is_err());
+        let trivial = [&1u32.to_le_bytes()[..], &[0xff; 32]].concat();
+        assert!(Assigned::<Fr>::fetch(&mut &trivial[..]).is_err());
+        assert!(Polynomial::<Fr, LagrangeCoeff>::fetch(&mut &trivial[..]).is_err());
+        assert!(G1Affine::read(&mut &[0xff; 32][..]).is_err());
+    }
+
+    #[test]
+    fn missing_columns_are_rejected() {
+        // The constraint system starts with its number of advice columns.
+        let mut cs = cs_bytes();
+        cs[..4].copy_from_slice(&0u32.to_le_bytes());
+        assert!(read_cs::<G1Affine, _>(&mut &cs[..]).is_err());
+    }
+
+    #[test]
+    fn deep_expressions_are_rejected() {
+        // An expression negated a million times, which would overflow the
+        // stack if it were read recursively without a limit.
+        let negated = 4u32.to_le_bytes().repeat(1 << 20);
+        assert!(Expression::<Fr>::fetch(&mut &negated[..]).is_err());
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        #[test]
+        fn random_inputs_do_not_panic(bytes in vec(any::<u8>(), 0..512)) {
+            let _ = read_cs::<G1Affine, _>(&mut &bytes[..]);
+            let _ = VerifyingKey::<G1Affine>::fetch(&mut &bytes[..]);
+            let _ = Expression::<Fr>::fetch(&mut &bytes[..]);
+            let _ = Vec::<Assigned<Fr>>::fetch(&mut &bytes[..]);
+            let _ = Polynomial::<Fr, LagrangeCoeff>::fetch(&mut &bytes[..]);
+            let _ = String::fetch(&mut &bytes[..]);
+            let _ = Params::<G1Affine>::read(&bytes[..]);
+            let _ = ParamsVerifier::<Bn256>::read(&bytes[..]);
+        }
+
+        #[test]
+        fn truncated_inputs_are_rejected(cut in any::<Index>()) {
+            let cs = cs_bytes();
+            prop_assert!(read_cs::<G1Affine, _>(&mut &cs[..cut.index(cs.len())]).is_err());
+            let vk = vk_bytes();
+            prop_assert!(VerifyingKey::<G1Affine>::fetch(&mut &vk[..cut.index(vk.len())]).is_err());
+        }
+
+        #[test]
+        fn corrupted_inputs_do_not_panic(index in any::<Index>(), bit in any::<u8>()) {
+            let mut vk = vk_bytes();
+            flip(&mut vk, index, bit);
+            if let Ok(vk) = VerifyingKey::<G1Affine>::fetch(&mut &vk[..]) {
+                let _ = vk.digest();
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn corrupted_params_do_not_panic(
+            index in any::<Index>(),
+            bit in any::<u8>(),
+            cut in any::<Index>(),
+        ) {
+            let valid = params_bytes();
+            prop_assert!(Params::<G1Affine>::read(&valid[..cut.index(valid.len())]).is_err());
+            let mut params = valid;
+            flip(&mut params, index, bit);
+            let _ = Params::<G1Affine>::read(&params[..]);
+        }
+    }
+}
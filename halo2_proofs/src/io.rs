@@ -0,0 +1,149 @@
+//! The I/O traits that proofs, transcripts, parameters and keys are read and
+//! written with.
+//!
+//! With the `std` feature these are the traits of [`std::io`]. Without it,
+//! this module provides the subset of them that the verifier uses, with
+//! [`Read`] implemented for byte slices and [`Write`] for `Vec<u8>`.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::no_std::*;
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    /// The kind of an [`Error`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ErrorKind {
+        /// The input is malformed.
+        InvalidData,
+        /// The input ended before all of it was read.
+        UnexpectedEof,
+        /// The output is full.
+        WriteZero,
+        /// Any other error.
+        Other,
+    }
+
+    /// An error of a reader or writer.
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: Option<String>,
+    }
+
+    impl Error {
+        /// Creates an error of the given kind with a message.
+        pub fn new<M: Into<String>>(kind: ErrorKind, message: M) -> Self {
+            Error {
+                kind,
+                message: Some(message.into()),
+            }
+        }
+
+        /// Returns the kind of this error.
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Error {
+                kind,
+                message: None,
+            }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.message {
+                Some(message) => write!(f, "{}", message),
+                None => write!(f, "{:?}", self.kind),
+            }
+        }
+    }
+
+    /// The result of a reader or writer.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// A source of bytes.
+    pub trait Read {
+        /// Reads some bytes into `buf`, returning how many were read.
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        /// Reads exactly enough bytes to fill `buf`.
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => return Err(ErrorKind::UnexpectedEof.into()),
+                    n => buf = &mut buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// A sink of bytes.
+    pub trait Write {
+        /// Writes some bytes of `buf`, returning how many were written.
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        /// Flushes buffered bytes.
+        fn flush(&mut self) -> Result<()>;
+
+        /// Writes all of `buf`.
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => return Err(ErrorKind::WriteZero.into()),
+                    n => buf = &buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl Read for &[u8] {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let len = core::cmp::min(buf.len(), self.len());
+            let (head, tail) = self.split_at(len);
+            buf[..len].copy_from_slice(head);
+            *self = tail;
+            Ok(len)
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+    }
+}
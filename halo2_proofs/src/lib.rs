@@ -1,5 +1,10 @@
 //! # halo2_proofs
+//!
+//! Without the default `std` feature, the crate builds under `no_std` with
+//! the `alloc` feature, and provides only what is needed to read verifying
+//! keys and verify proofs.
 #![feature(local_key_cell_methods)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 // Build without warnings on stable 1.51 and later.
@@ -22,17 +27,32 @@
 #![deny(missing_debug_implementations)]
 // Remove this once we update pasta_curves
 #![allow(unused_imports)]
+// The verifier-only build leaves some helpers of the prover unused.
+#![cfg_attr(not(feature = "std"), allow(dead_code))]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("halo2_proofs requires either the `std` or the `alloc` feature");
+
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
 
 pub mod arithmetic;
 pub mod circuit;
 pub use pairing;
+pub mod io;
 mod multicore;
 pub mod plonk;
 pub mod poly;
 pub mod transcript;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod dev;
+#[cfg(feature = "ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+pub mod ffi;
 pub mod helpers;
 
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate lazy_static;
@@ -1,5 +1,168 @@
 //! An interface for dealing with the kinds of parallel computations involved in
 //! `halo2`. It's currently just a (very!) thin wrapper around [`rayon`] but may
 //! be extended in the future to allow for various parallelism strategies.
+//!
+//! All of the parallel work of the prover is run on the rayon thread pool of
+//! the calling thread, so a proof created from within [`ProverPool::run`] only
+//! uses the threads of the pool it was given.
+//!
+//! Without the `std` feature, the work is run serially on the calling thread.
 
-pub use rayon::{current_num_threads, scope, Scope};
+#[cfg(feature = "std")]
+pub use rayon::{current_num_threads, join, scope, Scope};
+
+#[cfg(not(feature = "std"))]
+pub use self::serial::{current_num_threads, join, scope, Scope};
+
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex};
+
+#[cfg(feature = "std")]
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+#[cfg(not(feature = "std"))]
+mod serial {
+    use core::marker::PhantomData;
+
+    /// Returns the number of threads work is split across, which is one.
+    pub fn current_num_threads() -> usize {
+        1
+    }
+
+    /// Runs `a` and then `b`.
+    pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA,
+        B: FnOnce() -> RB,
+    {
+        (a(), b())
+    }
+
+    /// Runs `op` with a scope whose spawned work runs as soon as it is
+    /// spawned.
+    pub fn scope<'scope, OP, R>(op: OP) -> R
+    where
+        OP: FnOnce(&Scope<'scope>) -> R,
+    {
+        op(&Scope {
+            _marker: PhantomData,
+        })
+    }
+
+    /// A scope to spawn work in, with the interface of `rayon::Scope`.
+    #[derive(Debug)]
+    pub struct Scope<'scope> {
+        _marker: PhantomData<&'scope mut &'scope ()>,
+    }
+
+    impl<'scope> Scope<'scope> {
+        /// Runs `body` on the calling thread.
+        pub fn spawn<BODY>(&self, body: BODY)
+        where
+            BODY: FnOnce(&Scope<'scope>) + 'scope,
+        {
+            body(self)
+        }
+    }
+}
+
+/// A set of thread pools that runs concurrent proving jobs, each on a pool of
+/// its own so that the jobs do not oversubscribe the cores.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub struct ProverPool {
+    pools: Vec<ThreadPool>,
+    free: Mutex<Vec<usize>>,
+    released: Condvar,
+}
+
+#[cfg(feature = "std")]
+impl ProverPool {
+    /// Creates a pool that runs up to `num_jobs` jobs at once, each on
+    /// `threads_per_job` threads.
+    ///
+    /// If `pin_threads` is set, the threads of each job are pinned to their
+    /// own cores, in order, wrapping around if there are more threads than
+    /// cores.
+    pub fn new(
+        num_jobs: usize,
+        threads_per_job: usize,
+        pin_threads: bool,
+    ) -> Result<Self, ThreadPoolBuildError> {
+        let core_ids = if pin_threads {
+            core_affinity::get_core_ids().unwrap_or_default()
+        } else {
+            vec![]
+        };
+
+        let pools = (0..num_jobs)
+            .map(|job| {
+                let core_ids = core_ids.clone();
+                ThreadPoolBuilder::new()
+                    .num_threads(threads_per_job)
+                    .thread_name(move |thread| format!("halo2-prover-{}-{}", job, thread))
+                    .start_handler(move |thread| {
+                        if !core_ids.is_empty() {
+                            let core = (job * threads_per_job + thread) % core_ids.len();
+                            core_affinity::set_for_current(core_ids[core]);
+                        }
+                    })
+                    .build()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ProverPool {
+            free: Mutex::new((0..num_jobs).rev().collect()),
+            released: Condvar::new(),
+            pools,
+        })
+    }
+
+    /// Returns the number of jobs this pool runs at once.
+    pub fn num_jobs(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Runs `job` on the first thread pool that is free, blocking until one
+    /// is. All parallel work done by `job`, such as [`create_proof`], uses
+    /// only the threads of that pool.
+    ///
+    /// [`create_proof`]: crate::plonk::create_proof
+    pub fn run<R: Send, F: FnOnce() -> R + Send>(&self, job: F) -> R {
+        let pool = {
+            let mut free = self.free.lock().unwrap();
+            loop {
+                match free.pop() {
+                    Some(pool) => break pool,
+                    None => free = self.released.wait(free).unwrap(),
+                }
+            }
+        };
+
+        // Return the pool to the free list even if `job` panics.
+        struct Release<'a>(&'a ProverPool, usize);
+        impl<'a> Drop for Release<'a> {
+            fn drop(&mut self) {
+                self.0.free.lock().unwrap().push(self.1);
+                self.0.released.notify_one();
+            }
+        }
+        let _release = Release(self, pool);
+
+        self.pools[pool].install(job)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_prover_pool() {
+    let pool = ProverPool::new(2, 3, false).unwrap();
+    assert_eq!(pool.num_jobs(), 2);
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| assert_eq!(pool.run(current_num_threads), 3));
+        }
+    });
+}
@@ -8,7 +8,7 @@
 use blake2b_simd::Params as Blake2bParams;
 
 use crate::arithmetic::{BaseExt, CurveAffine, FieldExt};
-use crate::helpers::CurveRead;
+use crate::helpers::{invalid_data, Serializable};
 use crate::poly::{
     commitment::Params, Coeff, EvaluationDomain, ExtendedLagrangeCoeff, LagrangeCoeff,
     PinnedEvaluationDomain, Polynomial,
@@ -16,27 +16,59 @@ use crate::poly::{
 use crate::transcript::{ChallengeScalar, EncodedChallenge, Transcript};
 
 mod assigned;
+#[cfg(feature = "std")]
+mod checkpoint;
 pub(crate) mod circuit;
+#[cfg(feature = "std")]
+mod control;
+mod envelope;
 mod error;
+#[cfg(feature = "std")]
 mod evaluation;
+#[cfg(feature = "std")]
 mod evaluation_gpu;
+#[cfg(feature = "std")]
 mod keygen;
 pub(crate) mod lookup;
+#[cfg(feature = "std")]
+mod memory;
 pub(crate) mod permutation;
+mod proof_view;
 mod vanishing;
 
+#[cfg(feature = "std")]
 mod prover;
 mod verifier;
 
+#[cfg(feature = "std")]
+pub use crate::helpers::WitnessEncoding;
+#[cfg(feature = "std")]
+pub use crate::multicore::ProverPool;
 pub use assigned::*;
+#[cfg(feature = "std")]
+pub use checkpoint::Checkpoint;
 pub use circuit::*;
+#[cfg(feature = "std")]
+pub use control::{CancelFlag, NoControl, ProverControl, ProverPhase, QuotientEvaluation};
+pub use envelope::{
+    verify_proof_envelope, verify_proof_envelope_with_exposed_advice,
+    verify_proof_envelope_with_instance_commitments, EnvelopeMismatch, MultiopenScheme,
+    ProofEnvelope, TranscriptKind,
+};
 pub use error::*;
+#[cfg(feature = "std")]
 pub use keygen::*;
+#[cfg(feature = "std")]
+pub use memory::MemoryBudget;
+pub use proof_view::{EvaluationView, InstanceView, LookupView, PermutationProductView, ProofView};
+#[cfg(feature = "std")]
 pub use prover::*;
 pub use verifier::*;
 
-use std::io;
+use crate::io;
+use alloc::vec::Vec;
 
+#[cfg(feature = "std")]
 use self::evaluation::Evaluator;
 
 /// This is a verifying key which allows for the verification of proofs for a
@@ -50,8 +82,17 @@ pub struct VerifyingKey<C: CurveAffine> {
 }
 
 impl<C: CurveAffine> VerifyingKey<C> {
-    /// Writes a verifying key to a buffer.
+    /// Writes a verifying key to a buffer, in the self-describing format of
+    /// [`Serializable::store`].
+    ///
+    /// [`Serializable::store`]: crate::helpers::Serializable::store
     pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.store(writer)
+    }
+
+    /// Writes the fixed and permutation commitments of a verifying key, which
+    /// follow its constraint system in the self-describing format.
+    pub(crate) fn write_commitments<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
         for commitment in &self.fixed_commitments {
             writer.write_all(commitment.to_bytes().as_ref())?;
         }
@@ -60,25 +101,35 @@ impl<C: CurveAffine> VerifyingKey<C> {
         Ok(())
     }
 
-    /// Reads a verification key from a buffer.
+    /// Reads a verification key from a buffer, as written by
+    /// [`VerifyingKey::write`], and checks that it is a key of the circuit
+    /// `ConcreteCircuit` for `params`.
+    ///
+    /// To read the key of a circuit whose implementation is not at hand, use
+    /// [`Serializable::fetch`], which does not check the circuit.
+    ///
+    /// [`Serializable::fetch`]: crate::helpers::Serializable::fetch
     pub fn read<R: io::Read, ConcreteCircuit: Circuit<C::Scalar>>(
         reader: &mut R,
         params: &Params<C>,
     ) -> io::Result<Self> {
-        let (domain, cs, _) = keygen::create_domain::<C, ConcreteCircuit>(params);
-
-        let fixed_commitments: Vec<_> = (0..cs.num_fixed_columns)
-            .map(|_| C::read(reader))
-            .collect::<Result<_, _>>()?;
+        let vk = Self::fetch(reader)?;
 
-        let permutation = permutation::VerifyingKey::read(reader, &cs.permutation)?;
+        // Selectors are compressed into fixed columns by keygen, so only the
+        // columns and arguments that compression leaves alone are compared.
+        let mut cs = ConstraintSystem::default();
+        ConcreteCircuit::configure(&mut cs);
+        if vk.domain.k() != params.k
+            || vk.cs.num_advice_columns != cs.num_advice_columns
+            || vk.cs.num_instance_columns != cs.num_instance_columns
+            || vk.cs.gates.len() != cs.gates.len()
+            || vk.cs.lookups.len() != cs.lookups.len()
+            || vk.cs.permutation.columns != cs.permutation.columns
+        {
+            return Err(invalid_data("verifying key is not a key of the circuit"));
+        }
 
-        Ok(VerifyingKey {
-            domain,
-            fixed_commitments,
-            permutation,
-            cs,
-        })
+        Ok(vk)
     }
 
     /// Hashes a verification key into a transcript.
@@ -102,6 +153,23 @@ impl<C: CurveAffine> VerifyingKey<C> {
         Ok(())
     }
 
+    /// Returns a digest of the pinned representation of this verification
+    /// key, which identifies the circuit it was generated for.
+    pub fn digest(&self) -> [u8; 32] {
+        let s = format!("{:?}", self.pinned());
+        let hash = Blake2bParams::new()
+            .hash_length(32)
+            .personal(b"Halo2-VK-Digest!")
+            .to_state()
+            .update(&(s.len() as u64).to_le_bytes())
+            .update(s.as_bytes())
+            .finalize();
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(hash.as_bytes());
+        digest
+    }
+
     /// Obtains a pinned representation of this verification key that contains
     /// the minimal information necessary to reconstruct the verification key.
     pub fn pinned(&self) -> PinnedVerificationKey<'_, C> {
@@ -130,6 +198,8 @@ pub struct PinnedVerificationKey<'a, C: CurveAffine> {
 }
 /// This is a proving key which allows for the creation of proofs for a
 /// particular circuit.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 #[derive(Debug)]
 pub struct ProvingKey<C: CurveAffine> {
     vk: VerifyingKey<C>,
@@ -155,11 +225,24 @@ pub struct ProvingKey<C: CurveAffine> {
     ev: Evaluator<C>,
 }
 
+#[cfg(feature = "std")]
 impl<C: CurveAffine> ProvingKey<C> {
     /// Get the underlying [`VerifyingKey`].
     pub fn get_vk(&self) -> &VerifyingKey<C> {
         &self.vk
     }
+
+    /// Returns the values of the fixed columns, including the columns that
+    /// selectors were compressed into.
+    pub(crate) fn fixed_values(&self) -> &[Polynomial<C::Scalar, LagrangeCoeff>] {
+        &self.fixed_values
+    }
+
+    /// Returns the mapping of the copy constraints between the columns of the
+    /// permutation.
+    pub(crate) fn permutation_mapping(&self) -> Vec<Vec<(u32, u32)>> {
+        self.permutation.mapping(&self.vk.domain)
+    }
 }
 
 impl<C: CurveAffine> VerifyingKey<C> {
@@ -0,0 +1,337 @@
+//! Snapshots of the prover state taken after each transcript round, so that
+//! an interrupted proof can be resumed with [`resume_proof`].
+//!
+//! [`resume_proof`]: crate::plonk::resume_proof
+
+use std::convert::TryInto;
+use std::io;
+
+use blake2b_simd::Params as Blake2bParams;
+use ff::PrimeField;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::{lookup, Error, ProvingKey};
+use crate::arithmetic::CurveAffine;
+use crate::helpers::{CurveRead, Serializable};
+use crate::poly::{Coeff, LagrangeCoeff, Polynomial};
+
+const CHECKPOINT_MAGIC: [u8; 4] = *b"H2CP";
+const CHECKPOINT_VERSION: u32 = 3;
+
+/// The transcript round that commits to the advice columns.
+pub(crate) const ROUND_ADVICE: usize = 0;
+/// The transcript round that commits to the permuted lookup columns.
+pub(crate) const ROUND_LOOKUP_PERMUTED: usize = 1;
+/// The transcript round that commits to the permutation and lookup products,
+/// and to the random polynomial of the vanishing argument.
+pub(crate) const ROUND_PRODUCTS: usize = 2;
+
+/// Returns the generator used for the `index`-th random choice of `round`.
+///
+/// All blinding randomness of a proof is derived from the seed stored in its
+/// [`Checkpoint`], so that a resumed proof is identical to the one the
+/// interrupted run would have produced.
+pub(crate) fn round_rng(seed: &[u8; 32], round: usize, index: usize) -> StdRng {
+    let hash = Blake2bParams::new()
+        .hash_length(32)
+        .personal(b"Halo2-ProverRng!")
+        .to_state()
+        .update(seed)
+        .update(&(round as u64).to_le_bytes())
+        .update(&(index as u64).to_le_bytes())
+        .finalize();
+    StdRng::from_seed(hash.as_bytes().try_into().unwrap())
+}
+
+/// Returns a digest of the instances of a proof, which binds a checkpoint to
+/// the instances it was taken for.
+fn instances_digest<F: PrimeField>(instances: &[&[&[F]]]) -> [u8; 32] {
+    let mut state = Blake2bParams::new()
+        .hash_length(32)
+        .personal(b"Halo2-CkptInsts!")
+        .to_state();
+    state.update(&(instances.len() as u64).to_le_bytes());
+    for columns in instances.iter() {
+        state.update(&(columns.len() as u64).to_le_bytes());
+        for column in columns.iter() {
+            state.update(&(column.len() as u64).to_le_bytes());
+            for value in column.iter() {
+                state.update(value.to_repr().as_ref());
+            }
+        }
+    }
+    state.finalize().as_bytes().try_into().unwrap()
+}
+
+#[derive(Debug)]
+pub(in crate::plonk) enum LookupState<C: CurveAffine> {
+    /// The lookups have not been started.
+    None,
+    /// The permuted columns have been committed.
+    Permuted(Vec<Vec<lookup::prover::Permuted<C>>>),
+    /// The product columns have been committed.
+    Committed(Vec<Vec<lookup::prover::Committed<C>>>),
+}
+
+/// The state of a proof after one or more transcript rounds.
+///
+/// A checkpoint holds the seed that all blinding factors are derived from,
+/// a digest of the instances of the proof, the points written to the
+/// transcript in each completed round, the advice columns, and the lookup and
+/// permutation columns computed so far. The transcript itself is rebuilt on
+/// resumption by replaying those points.
+#[derive(Debug)]
+pub struct Checkpoint<C: CurveAffine> {
+    pub(in crate::plonk) seed: [u8; 32],
+    pub(in crate::plonk) instances: [u8; 32],
+    pub(in crate::plonk) commitments: Vec<Vec<C>>,
+    pub(in crate::plonk) advice: Vec<Vec<Polynomial<C::Scalar, LagrangeCoeff>>>,
+    pub(in crate::plonk) lookups: LookupState<C>,
+    pub(in crate::plonk) permutations: Vec<Vec<Polynomial<C::Scalar, Coeff>>>,
+    pub(in crate::plonk) vanishing: Option<Polynomial<C::Scalar, Coeff>>,
+}
+
+impl<C: CurveAffine> Checkpoint<C> {
+    pub(in crate::plonk) fn new(
+        seed: [u8; 32],
+        instances: &[&[&[C::Scalar]]],
+        advice: Vec<Vec<Polynomial<C::Scalar, LagrangeCoeff>>>,
+        advice_commitments: Vec<C>,
+    ) -> Self {
+        Checkpoint {
+            seed,
+            instances: instances_digest(instances),
+            commitments: vec![advice_commitments],
+            advice,
+            lookups: LookupState::None,
+            permutations: vec![],
+            vanishing: None,
+        }
+    }
+
+    /// Returns the number of transcript rounds completed by this checkpoint.
+    pub fn rounds(&self) -> usize {
+        self.commitments.len()
+    }
+
+    /// Checks that this checkpoint was taken for the circuit described by
+    /// `pk` and for `instances`, and that every polynomial it holds has the
+    /// size of the domain.
+    pub(in crate::plonk) fn validate(
+        &self,
+        pk: &ProvingKey<C>,
+        instances: &[&[&[C::Scalar]]],
+    ) -> Result<(), Error> {
+        let cs = &pk.vk.cs;
+        let n = 1usize << pk.vk.domain.k();
+        let num_proofs = instances.len();
+        let rounds = self.rounds();
+        let lookups_ok = match &self.lookups {
+            LookupState::None => rounds <= ROUND_LOOKUP_PERMUTED,
+            LookupState::Permuted(lookups) => {
+                rounds == ROUND_PRODUCTS
+                    && lookups.len() == num_proofs
+                    && lookups.iter().all(|l| l.len() == cs.lookups.len())
+                    && lookups.iter().flatten().all(|l| {
+                        l.compressed_input_expression.len() == n
+                            && l.permuted_input_expression.len() == n
+                            && l.compressed_table_expression.len() == n
+                            && l.permuted_table_expression.len() == n
+                    })
+            }
+            LookupState::Committed(lookups) => {
+                rounds == ROUND_PRODUCTS + 1
+                    && lookups.len() == num_proofs
+                    && lookups.iter().all(|l| l.len() == cs.lookups.len())
+                    && lookups.iter().flatten().all(|l| {
+                        l.permuted_input_poly.len() == n
+                            && l.permuted_table_poly.len() == n
+                            && l.product_poly.len() == n
+                    })
+            }
+        };
+        // The permutation products are committed in chunks of the columns of
+        // the permutation argument.
+        let chunk_len = cs.degree() - 2;
+        let num_products = (cs.permutation.columns.len() + chunk_len - 1) / chunk_len;
+        let products_ok = if rounds > ROUND_PRODUCTS {
+            self.permutations.len() == num_proofs
+                && self.permutations.iter().all(|products| {
+                    products.len() == num_products && products.iter().all(|p| p.len() == n)
+                })
+                && self.vanishing.as_ref().map_or(false, |p| p.len() == n)
+        } else {
+            self.permutations.is_empty() && self.vanishing.is_none()
+        };
+        // The number of points written to the transcript in each round; the
+        // products round ends with the commitment to the vanishing argument's
+        // random polynomial.
+        let num_commitments = [
+            num_proofs * cs.num_advice_columns,
+            2 * num_proofs * cs.lookups.len(),
+            num_proofs * (num_products + cs.lookups.len()) + 1,
+        ];
+        let commitments_ok = self
+            .commitments
+            .iter()
+            .zip(num_commitments.iter())
+            .all(|(commitments, &len)| commitments.len() == len);
+
+        if self.instances != instances_digest(instances)
+            || rounds == 0
+            || rounds > ROUND_PRODUCTS + 1
+            || !commitments_ok
+            || !lookups_ok
+            || !products_ok
+            || self.advice.len() != num_proofs
+            || self.advice.iter().any(|advice| {
+                advice.len() != cs.num_advice_columns || advice.iter().any(|p| p.len() != n)
+            })
+        {
+            return Err(Error::InvalidCheckpoint);
+        }
+        Ok(())
+    }
+
+    /// Writes this checkpoint to a buffer.
+    pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&CHECKPOINT_MAGIC)?;
+        CHECKPOINT_VERSION.store(writer)?;
+        writer.write_all(&self.seed)?;
+        writer.write_all(&self.instances)?;
+
+        (self.commitments.len() as u32).store(writer)?;
+        for commitments in self.commitments.iter() {
+            (commitments.len() as u32).store(writer)?;
+            for commitment in commitments.iter() {
+                writer.write_all(commitment.to_bytes().as_ref())?;
+            }
+        }
+
+        self.advice.store(writer)?;
+        match &self.lookups {
+            LookupState::None => 0u32.store(writer)?,
+            LookupState::Permuted(lookups) => {
+                1u32.store(writer)?;
+                (lookups.len() as u32).store(writer)?;
+                for lookups in lookups.iter() {
+                    (lookups.len() as u32).store(writer)?;
+                    for lookup in lookups.iter() {
+                        lookup.compressed_input_expression.store(writer)?;
+                        lookup.permuted_input_expression.store(writer)?;
+                        lookup.compressed_table_expression.store(writer)?;
+                        lookup.permuted_table_expression.store(writer)?;
+                    }
+                }
+            }
+            LookupState::Committed(lookups) => {
+                2u32.store(writer)?;
+                (lookups.len() as u32).store(writer)?;
+                for lookups in lookups.iter() {
+                    (lookups.len() as u32).store(writer)?;
+                    for lookup in lookups.iter() {
+                        lookup.permuted_input_poly.store(writer)?;
+                        lookup.permuted_table_poly.store(writer)?;
+                        lookup.product_poly.store(writer)?;
+                    }
+                }
+            }
+        }
+        self.permutations.store(writer)?;
+        match &self.vanishing {
+            None => 0u32.store(writer)?,
+            Some(random_poly) => {
+                1u32.store(writer)?;
+                random_poly.store(writer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a checkpoint from a buffer. The checkpoint is checked against
+    /// the proving key when it is passed to [`resume_proof`].
+    ///
+    /// [`resume_proof`]: crate::plonk::resume_proof
+    pub fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != CHECKPOINT_MAGIC {
+            return Err(invalid("not a prover checkpoint"));
+        }
+        if u32::fetch(reader)? != CHECKPOINT_VERSION {
+            return Err(invalid("unsupported prover checkpoint version"));
+        }
+        let mut seed = [0u8; 32];
+        reader.read_exact(&mut seed)?;
+        let mut instances = [0u8; 32];
+        reader.read_exact(&mut instances)?;
+
+        let rounds = u32::fetch(reader)?;
+        if rounds as usize > ROUND_PRODUCTS + 1 {
+            return Err(invalid("too many rounds in prover checkpoint"));
+        }
+        let commitments = (0..rounds)
+            .map(|_| -> io::Result<Vec<C>> {
+                let len = u32::fetch(reader)?;
+                (0..len).map(|_| C::read(reader)).collect()
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let advice = Vec::fetch(reader)?;
+        let lookups = match u32::fetch(reader)? {
+            0 => LookupState::None,
+            1 => LookupState::Permuted(
+                (0..u32::fetch(reader)?)
+                    .map(|_| -> io::Result<Vec<_>> {
+                        (0..u32::fetch(reader)?)
+                            .map(|_| -> io::Result<_> {
+                                Ok(lookup::prover::Permuted {
+                                    compressed_input_expression: Polynomial::fetch(reader)?,
+                                    permuted_input_expression: Polynomial::fetch(reader)?,
+                                    compressed_table_expression: Polynomial::fetch(reader)?,
+                                    permuted_table_expression: Polynomial::fetch(reader)?,
+                                })
+                            })
+                            .collect()
+                    })
+                    .collect::<io::Result<_>>()?,
+            ),
+            2 => LookupState::Committed(
+                (0..u32::fetch(reader)?)
+                    .map(|_| -> io::Result<Vec<_>> {
+                        (0..u32::fetch(reader)?)
+                            .map(|_| -> io::Result<_> {
+                                Ok(lookup::prover::Committed {
+                                    permuted_input_poly: Polynomial::fetch(reader)?,
+                                    permuted_table_poly: Polynomial::fetch(reader)?,
+                                    product_poly: Polynomial::fetch(reader)?,
+                                })
+                            })
+                            .collect()
+                    })
+                    .collect::<io::Result<_>>()?,
+            ),
+            _ => return Err(invalid("invalid lookup state in prover checkpoint")),
+        };
+        let permutations = Vec::fetch(reader)?;
+        let vanishing = match u32::fetch(reader)? {
+            0 => None,
+            1 => Some(Polynomial::fetch(reader)?),
+            _ => return Err(invalid("invalid vanishing state in prover checkpoint")),
+        };
+
+        Ok(Checkpoint {
+            seed,
+            instances,
+            commitments,
+            advice,
+            lookups,
+            permutations,
+            vanishing,
+        })
+    }
+}
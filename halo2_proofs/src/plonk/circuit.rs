@@ -1,10 +1,16 @@
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::cmp::max;
 use core::ops::{Add, Mul};
-use ff::Field;
-use std::{
+use core::{
     convert::TryFrom,
     ops::{Neg, Sub},
 };
+use ff::Field;
 
 use super::{lookup, permutation, Assigned, Error};
 use crate::circuit::Layouter;
@@ -14,7 +20,7 @@ mod compress_selectors;
 
 /// A column type
 pub trait ColumnType:
-    'static + Sized + Copy + std::fmt::Debug + PartialEq + Eq + Into<Any>
+    'static + Sized + Copy + core::fmt::Debug + PartialEq + Eq + Into<Any>
 {
 }
 
@@ -42,19 +48,19 @@ impl<C: ColumnType> Column<C> {
 }
 
 impl<C: ColumnType> Ord for Column<C> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         // This ordering is consensus-critical! The layouters rely on deterministic column
         // orderings.
         match self.column_type.into().cmp(&other.column_type.into()) {
             // Indices are assigned within column types.
-            std::cmp::Ordering::Equal => self.index.cmp(&other.index),
+            core::cmp::Ordering::Equal => self.index.cmp(&other.index),
             order => order,
         }
     }
 }
 
 impl<C: ColumnType> PartialOrd for Column<C> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
@@ -83,26 +89,26 @@ pub enum Any {
 }
 
 impl Ord for Any {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         // This ordering is consensus-critical! The layouters rely on deterministic column
         // orderings.
         match (self, other) {
             (Any::Instance, Any::Instance)
             | (Any::Advice, Any::Advice)
-            | (Any::Fixed, Any::Fixed) => std::cmp::Ordering::Equal,
+            | (Any::Fixed, Any::Fixed) => core::cmp::Ordering::Equal,
             // Across column types, sort Instance < Advice < Fixed.
             (Any::Instance, Any::Advice)
             | (Any::Advice, Any::Fixed)
-            | (Any::Instance, Any::Fixed) => std::cmp::Ordering::Less,
+            | (Any::Instance, Any::Fixed) => core::cmp::Ordering::Less,
             (Any::Fixed, Any::Instance)
             | (Any::Fixed, Any::Advice)
-            | (Any::Advice, Any::Instance) => std::cmp::Ordering::Greater,
+            | (Any::Advice, Any::Instance) => core::cmp::Ordering::Greater,
         }
     }
 }
 
 impl PartialOrd for Any {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
@@ -248,7 +254,7 @@ impl TryFrom<Column<Any>> for Column<Instance> {
 /// }
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct Selector(pub(crate) usize, bool);
+pub struct Selector(pub(crate) usize, pub(crate) bool);
 
 impl Selector {
     /// Enable this selector at the given offset within the given region.
@@ -331,6 +337,24 @@ pub trait Assignment<F: Field> {
     /// Returns the cell's value, if known.
     fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Option<F>, Error>;
 
+    /// Queries the value assigned to a cell of an advice column at a particular
+    /// absolute row.
+    ///
+    /// Returns the cell's value, if known. The default implementation returns `None`,
+    /// for backends that do not keep the assigned values.
+    fn query_advice(&self, _: Column<Advice>, _: usize) -> Result<Option<F>, Error> {
+        Ok(None)
+    }
+
+    /// Queries the value assigned to a cell of a fixed column at a particular
+    /// absolute row.
+    ///
+    /// Returns the cell's value, if known. The default implementation returns `None`,
+    /// for backends that do not keep the assigned values.
+    fn query_fixed(&self, _: Column<Fixed>, _: usize) -> Result<Option<F>, Error> {
+        Ok(None)
+    }
+
     /// Assign an advice column value (witness)
     fn assign_advice<V, VR, A, AR>(
         &mut self,
@@ -359,6 +383,36 @@ pub trait Assignment<F: Field> {
         A: FnOnce() -> AR,
         AR: Into<String>;
 
+    /// Assigns `values` to the rows of an advice `column` starting at `row`.
+    ///
+    /// The default implementation assigns the cells one by one.
+    fn assign_advice_slice(
+        &mut self,
+        column: Column<Advice>,
+        row: usize,
+        values: &[F],
+    ) -> Result<(), Error> {
+        for (i, value) in values.iter().enumerate() {
+            self.assign_advice(|| "", column, row + i, || Ok(*value))?;
+        }
+        Ok(())
+    }
+
+    /// Assigns `values` to the rows of a fixed `column` starting at `row`.
+    ///
+    /// The default implementation assigns the cells one by one.
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        values: &[F],
+    ) -> Result<(), Error> {
+        for (i, value) in values.iter().enumerate() {
+            self.assign_fixed(|| "", column, row + i, || Ok(*value))?;
+        }
+        Ok(())
+    }
+
     /// Assign two cells to have the same value
     fn copy(
         &mut self,
@@ -1005,32 +1059,44 @@ impl<Col: Into<Column<Any>>> From<(Col, Rotation)> for VirtualCell {
 /// These are returned by the closures passed to `ConstraintSystem::create_gate`.
 #[derive(Debug)]
 pub struct Constraint<F: Field> {
-    name: &'static str,
+    name: String,
     poly: Expression<F>,
 }
 
 impl<F: Field> From<Expression<F>> for Constraint<F> {
     fn from(poly: Expression<F>) -> Self {
-        Constraint { name: "", poly }
+        Constraint {
+            name: String::new(),
+            poly,
+        }
+    }
+}
+
+impl<F: Field> From<(&str, Expression<F>)> for Constraint<F> {
+    fn from((name, poly): (&str, Expression<F>)) -> Self {
+        Constraint {
+            name: name.to_string(),
+            poly,
+        }
     }
 }
 
-impl<F: Field> From<(&'static str, Expression<F>)> for Constraint<F> {
-    fn from((name, poly): (&'static str, Expression<F>)) -> Self {
+impl<F: Field> From<(String, Expression<F>)> for Constraint<F> {
+    fn from((name, poly): (String, Expression<F>)) -> Self {
         Constraint { name, poly }
     }
 }
 
 impl<F: Field> From<Expression<F>> for Vec<Constraint<F>> {
     fn from(poly: Expression<F>) -> Self {
-        vec![Constraint { name: "", poly }]
+        vec![poly.into()]
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Gate<F: Field> {
-    name: &'static str,
-    constraint_names: Vec<&'static str>,
+    name: String,
+    constraint_names: Vec<String>,
     pub polys: Vec<Expression<F>>,
     /// We track queried selectors separately from other cells, so that we can use them to
     /// trigger debug checks on gates.
@@ -1041,22 +1107,38 @@ pub struct Gate<F: Field> {
 impl<F: Field> Gate<F> {
     pub(crate) fn new_with_polys_and_queries(
         polys: Vec<Expression<F>>,
+        queried_selectors: Vec<Selector>,
         queried_cells: Vec<VirtualCell>,
     ) -> Self {
         Gate {
-            name: "",
+            name: String::new(),
             constraint_names: vec![],
             polys,
             queried_cells,
-            queried_selectors: vec![],
+            queried_selectors,
         }
     }
-    pub(crate) fn name(&self) -> &'static str {
-        self.name
+
+    pub(crate) fn with_names(mut self, name: String, constraint_names: Vec<String>) -> Self {
+        self.name = name;
+        self.constraint_names = constraint_names;
+        self
     }
 
-    pub(crate) fn constraint_name(&self, constraint_index: usize) -> &'static str {
-        self.constraint_names[constraint_index]
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the name of the given constraint, or the empty string if it is unnamed.
+    pub(crate) fn constraint_name(&self, constraint_index: usize) -> &str {
+        self.constraint_names
+            .get(constraint_index)
+            .map(|name| name.as_str())
+            .unwrap_or("")
+    }
+
+    pub(crate) fn constraint_names(&self) -> &[String] {
+        &self.constraint_names
     }
 
     pub(crate) fn polynomials(&self) -> &[Expression<F>] {
@@ -1084,6 +1166,8 @@ pub struct ConstraintSystem<F: Field> {
     pub gates: Vec<Gate<F>>,
     pub advice_queries: Vec<(Column<Advice>, Rotation)>,
     pub named_advices: Vec<(String, u32)>,
+    // The names given to columns of any kind, for debugging output.
+    pub(crate) column_names: BTreeMap<Column<Any>, String>,
     // Contains an integer for each advice column
     // identifying how many distinct queries it has
     // so far; should be same length as num_advice_columns.
@@ -1103,11 +1187,31 @@ pub struct ConstraintSystem<F: Field> {
     pub(crate) constants: Vec<Column<Fixed>>,
 
     pub(crate) minimum_degree: Option<usize>,
+
+    pub(crate) instance_evaluation: InstanceEvaluation,
+}
+
+/// How the verifier obtains the evaluations of the instance columns at `x`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstanceEvaluation {
+    /// The instance columns are committed to and opened at `x` like the other
+    /// columns. The verifier parameters must include the Lagrange bases for the
+    /// public inputs.
+    Committed,
+    /// The instance values are hashed into the transcript, and the verifier
+    /// evaluates the instance columns at `x` itself by barycentric Lagrange
+    /// evaluation over the domain. The verifier parameters only need `g1`, `g2`
+    /// and `s_g2`, whatever the number of public inputs.
+    Direct,
+}
+
+impl Default for InstanceEvaluation {
+    fn default() -> Self {
+        InstanceEvaluation::Committed
+    }
 }
 
 /// Represents the minimal parameters that determine a `ConstraintSystem`.
-#[allow(dead_code)]
-#[derive(Debug)]
 pub struct PinnedConstraintSystem<'a, F: Field> {
     num_fixed_columns: &'a usize,
     num_advice_columns: &'a usize,
@@ -1122,12 +1226,39 @@ pub struct PinnedConstraintSystem<'a, F: Field> {
     lookups: PinnedLookups<'a, F>,
     constants: &'a Vec<Column<Fixed>>,
     minimum_degree: &'a Option<usize>,
+    instance_evaluation: &'a InstanceEvaluation,
+}
+
+impl<'a, F: Field> core::fmt::Debug for PinnedConstraintSystem<'a, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        let mut debug_struct = f.debug_struct("PinnedConstraintSystem");
+        debug_struct
+            .field("num_fixed_columns", self.num_fixed_columns)
+            .field("num_advice_columns", self.num_advice_columns)
+            .field("num_instance_columns", self.num_instance_columns)
+            .field("num_selectors", self.num_selectors)
+            .field("selector_map", &self.selector_map)
+            .field("gates", &self.gates)
+            .field("advice_queries", self.advice_queries)
+            .field("instance_queries", self.instance_queries)
+            .field("fixed_queries", self.fixed_queries)
+            .field("permutation", self.permutation)
+            .field("lookups", &self.lookups)
+            .field("constants", self.constants)
+            .field("minimum_degree", self.minimum_degree);
+        // The instance evaluation is only pinned when it is not the default, so
+        // that the verifying keys of existing circuits are unchanged.
+        if *self.instance_evaluation != InstanceEvaluation::Committed {
+            debug_struct.field("instance_evaluation", self.instance_evaluation);
+        }
+        debug_struct.finish()
+    }
 }
 
 struct PinnedLookups<'a, F: Field>(&'a Vec<lookup::Argument<F>>);
 
-impl<'a, F: Field> std::fmt::Debug for PinnedLookups<'a, F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+impl<'a, F: Field> core::fmt::Debug for PinnedLookups<'a, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
         f.debug_list()
             .entries(self.0.iter().enumerate().map(|(i, arg)| {
                 (
@@ -1142,8 +1273,8 @@ impl<'a, F: Field> std::fmt::Debug for PinnedLookups<'a, F> {
 
 struct PinnedGates<'a, F: Field>(&'a Vec<Gate<F>>);
 
-impl<'a, F: Field> std::fmt::Debug for PinnedGates<'a, F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+impl<'a, F: Field> core::fmt::Debug for PinnedGates<'a, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
         f.debug_list()
             .entries(self.0.iter().flat_map(|gate| gate.polynomials().iter()))
             .finish()
@@ -1162,12 +1293,14 @@ impl<F: Field> Default for ConstraintSystem<F> {
             fixed_queries: Vec::new(),
             advice_queries: Vec::new(),
             named_advices: Vec::new(),
+            column_names: BTreeMap::new(),
             num_advice_queries: Vec::new(),
             instance_queries: Vec::new(),
             permutation: permutation::Argument::new(),
             lookups: Vec::new(),
             constants: vec![],
             minimum_degree: None,
+            instance_evaluation: InstanceEvaluation::Committed,
         }
     }
 }
@@ -1191,6 +1324,7 @@ impl<F: Field> ConstraintSystem<F> {
             lookups: PinnedLookups(&self.lookups),
             constants: &self.constants,
             minimum_degree: &self.minimum_degree,
+            instance_evaluation: &self.instance_evaluation,
         }
     }
 
@@ -1219,7 +1353,7 @@ impl<F: Field> ConstraintSystem<F> {
     /// they need to match.
     pub fn lookup(
         &mut self,
-        name: &'static str,
+        name: impl Into<String>,
         table_map: impl FnOnce(&mut VirtualCells<'_, F>) -> Vec<(Expression<F>, TableColumn)>,
     ) -> usize {
         let mut cells = VirtualCells::new(self);
@@ -1251,7 +1385,7 @@ impl<F: Field> ConstraintSystem<F> {
     /// This API allows any column type to be used as table columns.
     pub fn lookup_any(
         &mut self,
-        name: &'static str,
+        name: impl Into<String>,
         table_map: impl FnOnce(&mut VirtualCells<'_, F>) -> Vec<(Expression<F>, Expression<F>)>,
     ) -> usize {
         let mut cells = VirtualCells::new(self);
@@ -1371,6 +1505,18 @@ impl<F: Field> ConstraintSystem<F> {
         self.minimum_degree = Some(degree);
     }
 
+    /// Sets how the verifier obtains the evaluations of the instance columns.
+    ///
+    /// The default is [`InstanceEvaluation::Committed`].
+    pub fn set_instance_evaluation(&mut self, instance_evaluation: InstanceEvaluation) {
+        self.instance_evaluation = instance_evaluation;
+    }
+
+    /// Returns how the verifier obtains the evaluations of the instance columns.
+    pub fn instance_evaluation(&self) -> InstanceEvaluation {
+        self.instance_evaluation
+    }
+
     /// Creates a new gate.
     ///
     /// # Panics
@@ -1379,7 +1525,7 @@ impl<F: Field> ConstraintSystem<F> {
     /// `constraints` returns an empty iterator.
     pub fn create_gate<C: Into<Constraint<F>>, Iter: IntoIterator<Item = C>>(
         &mut self,
-        name: &'static str,
+        name: impl Into<String>,
         constraints: impl FnOnce(&mut VirtualCells<'_, F>) -> Iter,
     ) {
         let mut cells = VirtualCells::new(self);
@@ -1399,7 +1545,7 @@ impl<F: Field> ConstraintSystem<F> {
         );
 
         self.gates.push(Gate {
-            name,
+            name: name.into(),
             constraint_names,
             polys,
             queried_selectors,
@@ -1580,19 +1726,82 @@ impl<F: Field> ConstraintSystem<F> {
         tmp
     }
 
-    /// Allocate a new advice column
+    /// Allocate a new exposed advice column with the given name.
+    ///
+    /// This is the same as [`ConstraintSystem::exposed_advice_column`].
     pub fn named_advice_column(&mut self, name: String) -> Column<Advice> {
         let res = Column {
             index: self.num_advice_columns,
             column_type: Advice,
         };
         self.named_advices
-            .push((name, self.num_advice_columns as u32));
+            .push((name.clone(), self.num_advice_columns as u32));
+        self.column_names.insert(res.into(), name);
         self.num_advice_columns += 1;
         self.num_advice_queries.push(0);
         res
     }
 
+    /// Allocate a new advice column whose commitment is a public output of the
+    /// circuit, under the given name.
+    ///
+    /// Exposed advice columns are not blinded by the prover, so that two proofs
+    /// commit to the same contents of an exposed column with the same commitment.
+    /// The contents of exposed columns are therefore not hidden by the proof.
+    /// [`verify_proof_with_exposed_advice`] returns the commitments to the exposed
+    /// columns of a proof, and [`verify_commitment_link`] checks that two proofs
+    /// committed to the same contents.
+    ///
+    /// [`verify_proof_with_exposed_advice`]: crate::plonk::verify_proof_with_exposed_advice
+    /// [`verify_commitment_link`]: crate::plonk::verify_commitment_link
+    pub fn exposed_advice_column(&mut self, name: impl Into<String>) -> Column<Advice> {
+        self.named_advice_column(name.into())
+    }
+
+    /// Returns the names and columns of the exposed advice columns, in the order
+    /// in which they were allocated.
+    pub fn exposed_advice_columns(&self) -> impl Iterator<Item = (&str, Column<Advice>)> {
+        self.named_advices
+            .iter()
+            .map(|(name, index)| (name.as_str(), Column::new(*index as usize, Advice)))
+    }
+
+    /// Returns `true` if the advice column with the given index is exposed.
+    pub(crate) fn is_exposed_advice(&self, column_index: usize) -> bool {
+        self.named_advices
+            .iter()
+            .any(|(_, index)| *index as usize == column_index)
+    }
+
+    /// Allocate a new fixed column with the given name.
+    pub fn named_fixed_column(&mut self, name: impl Into<String>) -> Column<Fixed> {
+        let column = self.fixed_column();
+        self.annotate_column(column, name);
+        column
+    }
+
+    /// Allocate a new instance column with the given name.
+    pub fn named_instance_column(&mut self, name: impl Into<String>) -> Column<Instance> {
+        let column = self.instance_column();
+        self.annotate_column(column, name);
+        column
+    }
+
+    /// Gives a name to `column`, replacing any name it already had.
+    ///
+    /// Unlike [`ConstraintSystem::named_advice_column`], this does not change how an
+    /// advice column is blinded; the name is only used in debugging output.
+    pub fn annotate_column<C: Into<Column<Any>>>(&mut self, column: C, name: impl Into<String>) {
+        self.column_names.insert(column.into(), name.into());
+    }
+
+    /// Returns the name of `column`, if it has one.
+    pub fn column_name<C: Into<Column<Any>>>(&self, column: C) -> Option<&str> {
+        self.column_names
+            .get(&column.into())
+            .map(|name| name.as_str())
+    }
+
     /// Allocate a new instance column
     pub fn instance_column(&mut self) -> Column<Instance> {
         let tmp = Column {
@@ -1612,7 +1821,7 @@ impl<F: Field> ConstraintSystem<F> {
 
         // The lookup argument also serves alongside the gates and must be accounted
         // for.
-        degree = std::cmp::max(
+        degree = core::cmp::max(
             degree,
             self.lookups
                 .iter()
@@ -1623,7 +1832,7 @@ impl<F: Field> ConstraintSystem<F> {
 
         // Account for each gate to ensure our quotient polynomial is the
         // correct degree and that our extended domain is the right size.
-        degree = std::cmp::max(
+        degree = core::cmp::max(
             degree,
             self.gates
                 .iter()
@@ -1632,7 +1841,7 @@ impl<F: Field> ConstraintSystem<F> {
                 .unwrap_or(0),
         );
 
-        std::cmp::max(degree, self.minimum_degree.unwrap_or(1))
+        core::cmp::max(degree, self.minimum_degree.unwrap_or(1))
     }
 
     /// Compute the number of blinding factors necessary to perfectly blind
@@ -1645,7 +1854,7 @@ impl<F: Field> ConstraintSystem<F> {
         // - The permutation argument witness polynomials are evaluated at most 3 times.
         // - Each lookup argument has independent witness polynomials, and they are
         //   evaluated at most 2 times.
-        let factors = std::cmp::max(3, factors);
+        let factors = core::cmp::max(3, factors);
 
         // Each polynomial is evaluated at most an additional time during
         // multiopen (at x_3 to produce q_evals):
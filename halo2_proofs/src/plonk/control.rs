@@ -0,0 +1,220 @@
+//! Hooks for observing and cancelling a running proof.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::{Error, MemoryBudget};
+
+/// The phases of [`create_proof`](crate::plonk::create_proof), in the order
+/// in which they are run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverPhase {
+    /// Committing to the instance columns.
+    Instance,
+    /// Synthesizing and committing to the advice columns.
+    Advice,
+    /// Computing and committing to the permuted lookup columns.
+    LookupPermuted,
+    /// Committing to the permutation and lookup product columns.
+    Products,
+    /// Evaluating the quotient polynomial `h(X)`.
+    Quotient,
+    /// Committing to the pieces of `h(X)`.
+    Vanishing,
+    /// Evaluating all polynomials at `x`.
+    Evaluation,
+    /// Creating the multi-opening proof.
+    Opening,
+}
+
+impl ProverPhase {
+    /// The range of overall progress covered by this phase. The weights are
+    /// a rough estimate of the relative cost of each phase on a CPU prover.
+    fn span(&self) -> (f64, f64) {
+        match self {
+            ProverPhase::Instance => (0.00, 0.02),
+            ProverPhase::Advice => (0.02, 0.20),
+            ProverPhase::LookupPermuted => (0.20, 0.30),
+            ProverPhase::Products => (0.30, 0.45),
+            ProverPhase::Quotient => (0.45, 0.80),
+            ProverPhase::Vanishing => (0.80, 0.85),
+            ProverPhase::Evaluation => (0.85, 0.90),
+            ProverPhase::Opening => (0.90, 1.00),
+        }
+    }
+}
+
+/// How the CPU prover evaluates the quotient polynomial `h(X)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotientEvaluation {
+    /// Computes the cosets of the advice, fixed and instance columns over the
+    /// whole extended domain before evaluating the gates.
+    Extended,
+    /// Evaluates the extended domain one coset of the original domain at a
+    /// time, so that only `n` evaluations of each column are held at once.
+    ByCoset,
+}
+
+/// A hook that is polled by the prover between phases and inside the long
+/// running MSM, FFT and quotient evaluation loops.
+///
+/// Polling happens from the prover's worker threads, and never while a GPU
+/// index is held, so returning `true` from [`ProverControl::is_cancelled`]
+/// makes the prover drop all of its intermediate state and return
+/// [`Error::Cancelled`].
+pub trait ProverControl: Sync {
+    /// Called with the phase being run and the overall progress of the proof,
+    /// in `[0, 1]`.
+    fn progress(&self, _phase: ProverPhase, _fraction: f64) {}
+
+    /// Returns `true` if the proof should be aborted.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    /// Returns the memory budget the prover should stay within, if any.
+    fn memory_budget(&self) -> Option<&MemoryBudget> {
+        None
+    }
+
+    /// Returns how the quotient polynomial should be evaluated. If `None`,
+    /// the prover evaluates it by coset when the extended cosets do not fit in
+    /// the [`ProverControl::memory_budget`].
+    fn quotient_evaluation(&self) -> Option<QuotientEvaluation> {
+        None
+    }
+}
+
+/// A [`ProverControl`] that never cancels and ignores progress.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoControl;
+
+impl ProverControl for NoControl {}
+
+/// A [`ProverControl`] backed by a shared cancellation flag, with optional
+/// progress callback, memory budget and quotient evaluation mode.
+#[derive(Clone, Default)]
+pub struct CancelFlag {
+    cancelled: Arc<AtomicBool>,
+    on_progress: Option<Arc<dyn Fn(ProverPhase, f64) + Send + Sync>>,
+    memory_budget: Option<MemoryBudget>,
+    quotient_evaluation: Option<QuotientEvaluation>,
+}
+
+impl fmt::Debug for CancelFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelFlag")
+            .field("cancelled", &self.is_cancelled())
+            .field("memory_budget", &self.memory_budget)
+            .field("quotient_evaluation", &self.quotient_evaluation)
+            .finish()
+    }
+}
+
+impl CancelFlag {
+    /// Creates a new flag that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a callback that receives every progress report.
+    pub fn with_progress<P: Fn(ProverPhase, f64) + Send + Sync + 'static>(
+        mut self,
+        on_progress: P,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    /// Sets the memory budget of every proof using this flag.
+    pub fn with_memory_budget(mut self, memory_budget: MemoryBudget) -> Self {
+        self.memory_budget = Some(memory_budget);
+        self
+    }
+
+    /// Sets how every proof using this flag evaluates the quotient
+    /// polynomial.
+    pub fn with_quotient_evaluation(mut self, quotient_evaluation: QuotientEvaluation) -> Self {
+        self.quotient_evaluation = Some(quotient_evaluation);
+        self
+    }
+
+    /// Requests cancellation of every proof using a clone of this flag.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+impl ProverControl for CancelFlag {
+    fn progress(&self, phase: ProverPhase, fraction: f64) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(phase, fraction);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn memory_budget(&self) -> Option<&MemoryBudget> {
+        self.memory_budget.as_ref()
+    }
+
+    fn quotient_evaluation(&self) -> Option<QuotientEvaluation> {
+        self.quotient_evaluation
+    }
+}
+
+/// Tracks the progress of one phase that is split into `total` steps, which
+/// may be completed from several threads.
+pub(crate) struct PhaseTracker<'a> {
+    control: &'a dyn ProverControl,
+    phase: ProverPhase,
+    total: usize,
+    done: AtomicUsize,
+}
+
+impl<'a> PhaseTracker<'a> {
+    /// Enters `phase`, returning `Error::Cancelled` if the proof was aborted.
+    pub(crate) fn start(
+        control: &'a dyn ProverControl,
+        phase: ProverPhase,
+        total: usize,
+    ) -> Result<Self, Error> {
+        let tracker = PhaseTracker {
+            control,
+            phase,
+            total: total.max(1),
+            done: AtomicUsize::new(0),
+        };
+        tracker.poll()?;
+        control.progress(phase, phase.span().0);
+        Ok(tracker)
+    }
+
+    /// Returns `Error::Cancelled` if the proof was aborted.
+    pub(crate) fn poll(&self) -> Result<(), Error> {
+        if self.control.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Marks one step of the phase as done and reports progress.
+    pub(crate) fn step(&self) -> Result<(), Error> {
+        self.poll()?;
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        let (start, end) = self.phase.span();
+        let fraction = (done.min(self.total) as f64) / (self.total as f64);
+        self.control
+            .progress(self.phase, start + (end - start) * fraction);
+        Ok(())
+    }
+
+    /// Returns `true` if the proof was aborted, for loops that cannot return
+    /// an error directly.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.control.is_cancelled()
+    }
+}
@@ -0,0 +1,518 @@
+//! An optional envelope around a serialized proof, recording what the proof was
+//! created for so that a mismatch is reported as such rather than as an invalid
+//! proof.
+
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+
+use super::{
+    verify_proof, verify_proof_with_exposed_advice, verify_proof_with_instance_commitments, Error,
+    ExposedAdvice, VerificationStrategy, VerifyingKey,
+};
+use crate::arithmetic::{CurveAffine, MultiMillerLoop};
+use crate::poly::commitment::ParamsVerifier;
+use crate::transcript::{Blake2bRead, Challenge255};
+
+const ENVELOPE_MAGIC: [u8; 4] = *b"H2PE";
+const ENVELOPE_VERSION: u32 = 1;
+
+/// The transcript with which a proof was created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptKind {
+    /// [`Blake2bWrite`] and [`Blake2bRead`] with [`Challenge255`] challenges.
+    ///
+    /// [`Blake2bWrite`]: crate::transcript::Blake2bWrite
+    Blake2b,
+}
+
+impl TranscriptKind {
+    fn code(self) -> u8 {
+        match self {
+            TranscriptKind::Blake2b => 0,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(TranscriptKind::Blake2b),
+            _ => None,
+        }
+    }
+}
+
+/// The multi-opening scheme with which a proof was created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiopenScheme {
+    /// The scheme of Gabizon, Williamson and Ciobotaru, used by default.
+    Gwc,
+    /// The scheme of Boneh, Drake, Fisch and Gabizon, used with the `shplonk`
+    /// feature.
+    Shplonk,
+}
+
+impl MultiopenScheme {
+    /// Returns the scheme that this build of the crate creates and verifies
+    /// proofs with.
+    pub fn current() -> Self {
+        if cfg!(feature = "shplonk") {
+            MultiopenScheme::Shplonk
+        } else {
+            MultiopenScheme::Gwc
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            MultiopenScheme::Gwc => 0,
+            MultiopenScheme::Shplonk => 1,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(MultiopenScheme::Gwc),
+            1 => Some(MultiopenScheme::Shplonk),
+            _ => None,
+        }
+    }
+}
+
+/// The part of a [`ProofEnvelope`] that does not match what the proof is
+/// verified with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EnvelopeMismatch {
+    /// The proof was created with another multi-opening scheme.
+    Scheme {
+        /// The scheme of the verifier.
+        expected: MultiopenScheme,
+        /// The scheme recorded in the envelope.
+        found: MultiopenScheme,
+    },
+    /// The proof was created with another transcript.
+    Transcript {
+        /// The transcript of the verifier.
+        expected: TranscriptKind,
+        /// The transcript recorded in the envelope.
+        found: TranscriptKind,
+    },
+    /// The proof was created for a circuit of another size.
+    K {
+        /// The `k` of the verifying key.
+        expected: u32,
+        /// The `k` recorded in the envelope.
+        found: u32,
+    },
+    /// The proof was created for another verifying key.
+    VerifyingKey,
+    /// The proof was created for another number of circuit instances, or for
+    /// instance columns of other lengths.
+    Instances,
+}
+
+impl fmt::Display for EnvelopeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeMismatch::Scheme { expected, found } => write!(
+                f,
+                "proof uses the {:?} multi-opening scheme, expected {:?}",
+                found, expected
+            ),
+            EnvelopeMismatch::Transcript { expected, found } => write!(
+                f,
+                "proof uses the {:?} transcript, expected {:?}",
+                found, expected
+            ),
+            EnvelopeMismatch::K { expected, found } => {
+                write!(f, "proof is for k = {}, expected k = {}", found, expected)
+            }
+            EnvelopeMismatch::VerifyingKey => {
+                write!(f, "proof was created for another verifying key")
+            }
+            EnvelopeMismatch::Instances => {
+                write!(f, "proof was created for other instance lengths")
+            }
+        }
+    }
+}
+
+/// The metadata of a proof: what it was created for and how.
+///
+/// An envelope is sealed around a serialized proof with [`ProofEnvelope::seal`],
+/// and checked by [`verify_proof_envelope`] before the proof is verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofEnvelope {
+    vk_digest: [u8; 32],
+    k: u32,
+    transcript: TranscriptKind,
+    scheme: MultiopenScheme,
+    instance_lengths: Vec<Vec<usize>>,
+}
+
+impl ProofEnvelope {
+    /// Creates the envelope of a proof created with this build of the crate,
+    /// for the circuit with verifying key `vk` and the given `instances`.
+    pub fn new<C: CurveAffine>(
+        vk: &VerifyingKey<C>,
+        transcript: TranscriptKind,
+        instances: &[&[&[C::Scalar]]],
+    ) -> Self {
+        ProofEnvelope {
+            vk_digest: vk.digest(),
+            k: vk.domain.k(),
+            transcript,
+            scheme: MultiopenScheme::current(),
+            instance_lengths: instance_lengths(instances),
+        }
+    }
+
+    /// Returns the digest of the verifying key the proof was created for.
+    pub fn vk_digest(&self) -> &[u8; 32] {
+        &self.vk_digest
+    }
+
+    /// Returns the `k` of the circuit the proof was created for.
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    /// Returns the transcript the proof was created with.
+    pub fn transcript(&self) -> TranscriptKind {
+        self.transcript
+    }
+
+    /// Returns the multi-opening scheme the proof was created with.
+    pub fn scheme(&self) -> MultiopenScheme {
+        self.scheme
+    }
+
+    /// Returns the number of circuit instances the proof was created for.
+    pub fn num_instances(&self) -> usize {
+        self.instance_lengths.len()
+    }
+
+    /// Returns the length of each instance column of each circuit instance.
+    pub fn instance_lengths(&self) -> &[Vec<usize>] {
+        &self.instance_lengths
+    }
+
+    /// Wraps `proof` in this envelope.
+    ///
+    /// The envelope starts with a header, in which all integers are
+    /// little-endian:
+    ///
+    /// | bytes      | content                                                   |
+    /// |------------|-----------------------------------------------------------|
+    /// | 4          | magic `H2PE`                                              |
+    /// | 4          | format version, currently 1                               |
+    /// | 32         | digest of the verifying key, see [`VerifyingKey::digest`] |
+    /// | 4          | `k`                                                       |
+    /// | 1          | transcript, 0 = BLAKE2b                                   |
+    /// | 1          | multi-opening scheme, 0 = GWC, 1 = SHPLONK                |
+    /// | 4          | number of circuit instances `m`                           |
+    /// |            | for each circuit instance: the number of instance columns |
+    /// |            | `p` (4), and the number of values in each of them (4 * p) |
+    ///
+    /// The header is followed by the proof, up to the end of the envelope.
+    pub fn seal(&self, proof: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&ENVELOPE_MAGIC);
+        bytes.extend_from_slice(&ENVELOPE_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.vk_digest);
+        bytes.extend_from_slice(&self.k.to_le_bytes());
+        bytes.push(self.transcript.code());
+        bytes.push(self.scheme.code());
+        bytes.extend_from_slice(&(self.instance_lengths.len() as u32).to_le_bytes());
+        for lengths in self.instance_lengths.iter() {
+            bytes.extend_from_slice(&(lengths.len() as u32).to_le_bytes());
+            for len in lengths.iter() {
+                bytes.extend_from_slice(&(*len as u32).to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(proof);
+        bytes
+    }
+
+    /// Reads the envelope at the start of `bytes`, and returns it together with
+    /// the proof it wraps.
+    pub fn open(bytes: &[u8]) -> Result<(Self, &[u8]), Error> {
+        let mut reader = bytes;
+
+        if read_bytes(&mut reader, 4)? != ENVELOPE_MAGIC {
+            return Err(Error::InvalidEnvelope("not a proof envelope".to_string()));
+        }
+        let version = read_u32(&mut reader)?;
+        if version != ENVELOPE_VERSION {
+            return Err(Error::InvalidEnvelope(format!(
+                "unsupported version {}, expected {}",
+                version, ENVELOPE_VERSION
+            )));
+        }
+
+        let mut vk_digest = [0u8; 32];
+        vk_digest.copy_from_slice(read_bytes(&mut reader, 32)?);
+        let k = read_u32(&mut reader)?;
+        let transcript = TranscriptKind::from_code(read_bytes(&mut reader, 1)?[0])
+            .ok_or_else(|| Error::InvalidEnvelope("unknown transcript".to_string()))?;
+        let scheme = MultiopenScheme::from_code(read_bytes(&mut reader, 1)?[0])
+            .ok_or_else(|| Error::InvalidEnvelope("unknown multi-opening scheme".to_string()))?;
+
+        // The lengths are not preallocated, so that a corrupted count cannot
+        // make us allocate more than the envelope itself.
+        let num_instances = read_u32(&mut reader)?;
+        let mut instance_lengths = vec![];
+        for _ in 0..num_instances {
+            let num_columns = read_u32(&mut reader)?;
+            let mut lengths = vec![];
+            for _ in 0..num_columns {
+                lengths.push(read_u32(&mut reader)? as usize);
+            }
+            instance_lengths.push(lengths);
+        }
+
+        let envelope = ProofEnvelope {
+            vk_digest,
+            k,
+            transcript,
+            scheme,
+            instance_lengths,
+        };
+        Ok((envelope, reader))
+    }
+
+    /// Checks that the proof in this envelope was created with this build of
+    /// the crate and `transcript`, for the circuit with verifying key `vk` and
+    /// the given `instances`.
+    pub fn check<C: CurveAffine>(
+        &self,
+        vk: &VerifyingKey<C>,
+        transcript: TranscriptKind,
+        instances: &[&[&[C::Scalar]]],
+    ) -> Result<(), Error> {
+        self.check_proof(vk, transcript)?;
+        if self.instance_lengths != instance_lengths(instances) {
+            return Err(Error::EnvelopeMismatch(EnvelopeMismatch::Instances));
+        }
+        Ok(())
+    }
+
+    /// Checks that the proof in this envelope was created with this build of
+    /// the crate and `transcript`, for the circuit with verifying key `vk` and
+    /// instance columns with the given commitments.
+    ///
+    /// Only the number of circuit instances and of their instance columns can
+    /// be checked against the commitments, not the lengths of the columns.
+    pub fn check_instance_commitments<C: CurveAffine>(
+        &self,
+        vk: &VerifyingKey<C>,
+        transcript: TranscriptKind,
+        instance_commitments: &[&[C]],
+    ) -> Result<(), Error> {
+        self.check_proof(vk, transcript)?;
+        if self.instance_lengths.len() != instance_commitments.len()
+            || self
+                .instance_lengths
+                .iter()
+                .zip(instance_commitments.iter())
+                .any(|(lengths, commitments)| lengths.len() != commitments.len())
+        {
+            return Err(Error::EnvelopeMismatch(EnvelopeMismatch::Instances));
+        }
+        Ok(())
+    }
+
+    /// Checks everything in this envelope but the instances.
+    fn check_proof<C: CurveAffine>(
+        &self,
+        vk: &VerifyingKey<C>,
+        transcript: TranscriptKind,
+    ) -> Result<(), Error> {
+        let scheme = MultiopenScheme::current();
+        if self.scheme != scheme {
+            return Err(Error::EnvelopeMismatch(EnvelopeMismatch::Scheme {
+                expected: scheme,
+                found: self.scheme,
+            }));
+        }
+        if self.transcript != transcript {
+            return Err(Error::EnvelopeMismatch(EnvelopeMismatch::Transcript {
+                expected: transcript,
+                found: self.transcript,
+            }));
+        }
+        if self.k != vk.domain.k() {
+            return Err(Error::EnvelopeMismatch(EnvelopeMismatch::K {
+                expected: vk.domain.k(),
+                found: self.k,
+            }));
+        }
+        if self.vk_digest != vk.digest() {
+            return Err(Error::EnvelopeMismatch(EnvelopeMismatch::VerifyingKey));
+        }
+        Ok(())
+    }
+}
+
+fn read_bytes<'a>(reader: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if reader.len() < len {
+        return Err(Error::InvalidEnvelope("truncated header".to_string()));
+    }
+    let (read, rest) = reader.split_at(len);
+    *reader = rest;
+    Ok(read)
+}
+
+fn read_u32(reader: &mut &[u8]) -> Result<u32, Error> {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(read_bytes(reader, 4)?);
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn instance_lengths<F>(instances: &[&[&[F]]]) -> Vec<Vec<usize>> {
+    instances
+        .iter()
+        .map(|instance| instance.iter().map(|values| values.len()).collect())
+        .collect()
+}
+
+/// Checks the envelope of a proof, and then the proof like [`verify_proof`].
+///
+/// The proof is read with the transcript recorded in the envelope. Returns
+/// [`Error::InvalidEnvelope`] if the envelope is malformed, and
+/// [`Error::EnvelopeMismatch`] if it does not match `vk` or `instances`, or
+/// was created with a multi-opening scheme this build does not verify.
+pub fn verify_proof_envelope<'params, C: MultiMillerLoop, V: VerificationStrategy<C::G1Affine>>(
+    params: &'params ParamsVerifier<C>,
+    vk: &VerifyingKey<C::G1Affine>,
+    strategy: V,
+    instances: &[&[&[C::Scalar]]],
+    envelope: &[u8],
+) -> Result<V::Output, Error> {
+    let (envelope, proof) = ProofEnvelope::open(envelope)?;
+    match envelope.transcript() {
+        TranscriptKind::Blake2b => {
+            envelope.check(vk, TranscriptKind::Blake2b, instances)?;
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+            verify_proof(params, vk, strategy, instances, &mut transcript)
+        }
+    }
+}
+
+/// Checks the envelope of a proof like [`verify_proof_envelope`], and then the
+/// proof like [`verify_proof_with_exposed_advice`].
+pub fn verify_proof_envelope_with_exposed_advice<
+    'params,
+    C: MultiMillerLoop,
+    V: VerificationStrategy<C::G1Affine>,
+>(
+    params: &'params ParamsVerifier<C>,
+    vk: &VerifyingKey<C::G1Affine>,
+    strategy: V,
+    instances: &[&[&[C::Scalar]]],
+    envelope: &[u8],
+) -> Result<(V::Output, ExposedAdvice<C::G1Affine>), Error> {
+    let (envelope, proof) = ProofEnvelope::open(envelope)?;
+    match envelope.transcript() {
+        TranscriptKind::Blake2b => {
+            envelope.check(vk, TranscriptKind::Blake2b, instances)?;
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+            verify_proof_with_exposed_advice(params, vk, strategy, instances, &mut transcript)
+        }
+    }
+}
+
+/// Checks the envelope of a proof like [`verify_proof_envelope`], and then the
+/// proof like [`verify_proof_with_instance_commitments`].
+///
+/// The lengths of the instance columns recorded in the envelope are not
+/// checked, as the commitments do not reveal them.
+pub fn verify_proof_envelope_with_instance_commitments<
+    'params,
+    C: MultiMillerLoop,
+    V: VerificationStrategy<C::G1Affine>,
+>(
+    params: &'params ParamsVerifier<C>,
+    vk: &VerifyingKey<C::G1Affine>,
+    strategy: V,
+    instance_commitments: &[&[C::G1Affine]],
+    envelope: &[u8],
+) -> Result<V::Output, Error> {
+    let (envelope, proof) = ProofEnvelope::open(envelope)?;
+    match envelope.transcript() {
+        TranscriptKind::Blake2b => {
+            envelope.check_instance_commitments(
+                vk,
+                TranscriptKind::Blake2b,
+                instance_commitments,
+            )?;
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+            verify_proof_with_instance_commitments(
+                params,
+                vk,
+                strategy,
+                instance_commitments,
+                &mut transcript,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::{MultiopenScheme, ProofEnvelope, TranscriptKind};
+    use crate::plonk::Error;
+
+    fn envelope() -> ProofEnvelope {
+        ProofEnvelope {
+            vk_digest: [7; 32],
+            k: 9,
+            transcript: TranscriptKind::Blake2b,
+            scheme: MultiopenScheme::current(),
+            instance_lengths: vec![vec![3, 0], vec![1, 2]],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let sealed = envelope().seal(&[1, 2, 3]);
+        let (opened, proof) = ProofEnvelope::open(&sealed).unwrap();
+        assert_eq!(opened, envelope());
+        assert_eq!(proof, &[1, 2, 3]);
+        assert_eq!(opened.num_instances(), 2);
+    }
+
+    #[test]
+    fn malformed() {
+        let sealed = envelope().seal(&[]);
+
+        let mut bad_magic = sealed.clone();
+        bad_magic[0] ^= 1;
+        assert_matches!(
+            ProofEnvelope::open(&bad_magic),
+            Err(Error::InvalidEnvelope(reason)) if reason == "not a proof envelope"
+        );
+
+        let mut bad_version = sealed.clone();
+        bad_version[4] = 2;
+        assert_matches!(
+            ProofEnvelope::open(&bad_version),
+            Err(Error::InvalidEnvelope(reason)) if reason.starts_with("unsupported version 2")
+        );
+
+        // The scheme tag follows the magic, version, digest, k and transcript.
+        let mut bad_scheme = sealed.clone();
+        bad_scheme[4 + 4 + 32 + 4 + 1] = 9;
+        assert_matches!(
+            ProofEnvelope::open(&bad_scheme),
+            Err(Error::InvalidEnvelope(reason)) if reason == "unknown multi-opening scheme"
+        );
+
+        for len in 0..sealed.len() {
+            assert_matches!(
+                ProofEnvelope::open(&sealed[..len]),
+                Err(Error::InvalidEnvelope(_))
+            );
+        }
+    }
+}
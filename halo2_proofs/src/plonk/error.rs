@@ -1,9 +1,12 @@
-use std::cmp;
+use alloc::string::String;
+use core::cmp;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::error;
-use std::fmt;
-use std::io;
 
-use super::{Any, Column};
+use crate::io;
+
+use super::{Any, Column, EnvelopeMismatch};
 
 /// This is an error that could occur during proving or circuit synthesis.
 // TODO: these errors need to be cleaned up
@@ -14,6 +17,8 @@ pub enum Error {
     Synthesis,
     /// The provided instances do not match the circuit parameters.
     InvalidInstances,
+    /// The provided advice columns do not match the circuit parameters.
+    InvalidAdvice,
     /// The constraint system is not satisfied.
     ConstraintSystemFailure,
     /// Out of bounds index passed to a backend
@@ -37,6 +42,46 @@ pub enum Error {
     /// The instance sets up a copy constraint involving a column that has not been
     /// included in the permutation.
     ColumnNotInPermutation(Column<Any>),
+    /// The proof was aborted through its [`ProverControl`].
+    ///
+    /// [`ProverControl`]: crate::plonk::ProverControl
+    Cancelled,
+    /// The checkpoint passed to [`resume_proof`] does not match the proving
+    /// key or the number of circuit instances.
+    ///
+    /// [`resume_proof`]: crate::plonk::resume_proof
+    InvalidCheckpoint,
+    /// A scratch file used to stay within a [`MemoryBudget`] could not be
+    /// written or read.
+    ///
+    /// [`MemoryBudget`]: crate::plonk::MemoryBudget
+    ScratchFile(io::Error),
+    /// A witness file is malformed or was not created for the proving key
+    /// and parameters it is loaded with.
+    InvalidWitness(String),
+    /// The named advice column is not an exposed advice column of the circuit.
+    ColumnNotExposed(String),
+    /// Two proofs did not commit to the same contents of the exposed advice
+    /// columns that were linked.
+    CommitmentLinkFailure,
+    /// The verifier parameters of a proof added to a [`MultiCircuitBatchVerifier`]
+    /// do not share the same `g2` and `s_g2` as those of the other proofs.
+    ///
+    /// [`MultiCircuitBatchVerifier`]: crate::plonk::MultiCircuitBatchVerifier
+    IncompatibleParams,
+    /// A proof envelope is malformed.
+    InvalidEnvelope(String),
+    /// A proof envelope records another verifying key, instance lengths,
+    /// transcript or multi-opening scheme than the proof is verified with.
+    EnvelopeMismatch(EnvelopeMismatch),
+    /// The fixed columns, selectors or copy constraints passed to
+    /// [`keygen_vk_from_parts`] or [`keygen_pk_from_parts`] do not match the
+    /// constraint system, or the constraint system does not match the
+    /// verifying key.
+    ///
+    /// [`keygen_vk_from_parts`]: crate::plonk::keygen_vk_from_parts
+    /// [`keygen_pk_from_parts`]: crate::plonk::keygen_pk_from_parts
+    InvalidCircuitParts(String),
 }
 
 impl From<io::Error> for Error {
@@ -58,6 +103,7 @@ impl fmt::Display for Error {
         match self {
             Error::Synthesis => write!(f, "General synthesis error"),
             Error::InvalidInstances => write!(f, "Provided instances do not match the circuit"),
+            Error::InvalidAdvice => write!(f, "Provided advice columns do not match the circuit"),
             Error::ConstraintSystemFailure => write!(f, "The constraint system is not satisfied"),
             Error::BoundsFailure => write!(f, "An out-of-bounds index was passed to the backend"),
             Error::Opening => write!(f, "Multi-opening proof was invalid"),
@@ -79,14 +125,34 @@ impl fmt::Display for Error {
                 "Column {:?} must be included in the permutation. Help: try applying `meta.enable_equalty` on the column",
                 column
             ),
+            Error::Cancelled => write!(f, "Proof creation was cancelled"),
+            Error::InvalidCheckpoint => {
+                write!(f, "Checkpoint does not match the proving key or instances")
+            }
+            Error::ScratchFile(e) => write!(f, "Scratch file error: {}", e),
+            Error::InvalidWitness(reason) => write!(f, "Invalid witness file: {}", reason),
+            Error::ColumnNotExposed(name) => {
+                write!(f, "Advice column '{}' is not exposed by the circuit", name)
+            }
+            Error::CommitmentLinkFailure => {
+                write!(f, "Linked advice columns do not have the same contents")
+            }
+            Error::IncompatibleParams => {
+                write!(f, "Verifier parameters cannot be checked in the same batch")
+            }
+            Error::InvalidEnvelope(reason) => write!(f, "Invalid proof envelope: {}", reason),
+            Error::EnvelopeMismatch(mismatch) => write!(f, "Proof envelope mismatch: {}", mismatch),
+            Error::InvalidCircuitParts(reason) => write!(f, "Invalid circuit parts: {}", reason),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             Error::Transcript(e) => Some(e),
+            Error::ScratchFile(e) => Some(e),
             _ => None,
         }
     }
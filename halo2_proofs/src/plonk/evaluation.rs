@@ -1,4 +1,6 @@
-use super::{evaluation_gpu, ConstraintSystem, Expression};
+#[cfg(not(feature = "cuda"))]
+use super::memory::Values;
+use super::{control::PhaseTracker, evaluation_gpu, ConstraintSystem, Error, Expression};
 use crate::multicore;
 use crate::plonk::evaluation_gpu::{LookupProveExpression, ProveExpression};
 use crate::plonk::lookup::prover::Committed;
@@ -20,6 +22,7 @@ use group::{
     Curve,
 };
 use num_bigint::BigUint;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use std::any::TypeId;
 use std::collections::BTreeSet;
 use std::convert::TryInto;
@@ -30,7 +33,7 @@ use std::{cmp, slice};
 use std::{
     collections::BTreeMap,
     iter,
-    ops::{Index, Mul, MulAssign},
+    ops::{Deref, Index, Mul, MulAssign},
 };
 
 #[cfg(not(feature = "cuda"))]
@@ -57,14 +60,14 @@ pub enum ValueSource {
 #[cfg(not(feature = "cuda"))]
 impl ValueSource {
     /// Get the value for this source
-    pub fn get<F: Field, B: Basis>(
+    pub fn get<F: Field, V: Deref<Target = [F]>>(
         &self,
         rotations: &[usize],
         constants: &[F],
         intermediates: &[F],
-        fixed_values: &[Polynomial<F, B>],
-        advice_values: &[Polynomial<F, B>],
-        instance_values: &[Polynomial<F, B>],
+        fixed_values: &[V],
+        advice_values: &[V],
+        instance_values: &[V],
     ) -> F {
         match self {
             ValueSource::Constant(idx) => constants[*idx],
@@ -106,14 +109,14 @@ pub enum Calculation {
 #[cfg(not(feature = "cuda"))]
 impl Calculation {
     /// Get the resulting value of this calculation
-    pub fn evaluate<F: Field, B: Basis>(
+    pub fn evaluate<F: Field, V: Deref<Target = [F]>>(
         &self,
         rotations: &[usize],
         constants: &[F],
         intermediates: &[F],
-        fixed_values: &[Polynomial<F, B>],
-        advice_values: &[Polynomial<F, B>],
-        instance_values: &[Polynomial<F, B>],
+        fixed_values: &[V],
+        advice_values: &[V],
+        instance_values: &[V],
         beta: &F,
         gamma: &F,
         theta: &F,
@@ -244,6 +247,30 @@ impl Calculation {
     }
 }
 
+/// The polynomials of one circuit instance that the quotient polynomial is
+/// evaluated over.
+#[cfg(not(feature = "cuda"))]
+pub(in crate::plonk) struct CircuitColumns<'a, F> {
+    /// The advice columns, as extended cosets for [`Evaluator::evaluate_h`]
+    /// and in coefficient form for [`Evaluator::evaluate_h_by_coset`].
+    pub(in crate::plonk) advice: Vec<Values<'a, F>>,
+    /// The instance columns, in the same form as the advice columns.
+    pub(in crate::plonk) instance: Vec<Values<'a, F>>,
+    /// The permutation product polynomials, in the same form as the advice
+    /// columns.
+    pub(in crate::plonk) permutation_products: Vec<Values<'a, F>>,
+    /// The lookup polynomials, always in coefficient form.
+    pub(in crate::plonk) lookups: Vec<LookupColumns<'a, F>>,
+}
+
+/// The polynomials committed to by a lookup argument, in coefficient form.
+#[cfg(not(feature = "cuda"))]
+pub(in crate::plonk) struct LookupColumns<'a, F> {
+    pub(in crate::plonk) permuted_input: Values<'a, F>,
+    pub(in crate::plonk) permuted_table: Values<'a, F>,
+    pub(in crate::plonk) product: Values<'a, F>,
+}
+
 /// EvaluationData
 #[derive(Default, Debug)]
 pub struct Evaluator<C: CurveAffine> {
@@ -519,23 +546,31 @@ impl<C: CurveAffine> Evaluator<C> {
     }
 
     /// Evaluate h poly
+    ///
+    /// `columns` returns the extended cosets of the given circuit instance,
+    /// and is called once for each of the `num_circuits` instances, so that
+    /// the cosets can be computed on demand instead of being cached.
     #[cfg(not(feature = "cuda"))]
-    pub(in crate::plonk) fn evaluate_h(
+    pub(in crate::plonk) fn evaluate_h<'a>(
         &self,
         pk: &ProvingKey<C>,
-        advice: Vec<&Vec<Polynomial<C::ScalarExt, ExtendedLagrangeCoeff>>>,
-        instance: Vec<&Vec<Polynomial<C::ScalarExt, ExtendedLagrangeCoeff>>>,
+        num_circuits: usize,
+        columns: impl Fn(usize) -> Result<CircuitColumns<'a, C::ScalarExt>, Error>,
         y: C::ScalarExt,
         beta: C::ScalarExt,
         gamma: C::ScalarExt,
         theta: C::ScalarExt,
-        lookups: &[Vec<lookup::prover::Committed<C>>],
-        permutations: &[permutation::prover::Committed<C>],
-    ) -> Polynomial<C::ScalarExt, ExtendedLagrangeCoeff> {
+        tracker: &PhaseTracker,
+    ) -> Result<Polynomial<C::ScalarExt, ExtendedLagrangeCoeff>, Error> {
         let domain = &pk.vk.domain;
         let size = domain.extended_len();
         let rot_scale = 1 << (domain.extended_k() - domain.k());
-        let fixed = &pk.fixed_cosets[..];
+        let fixed: Vec<_> = pk
+            .fixed_cosets
+            .iter()
+            .map(|coset| Values::Borrowed(&coset[..]))
+            .collect();
+        let fixed = &fixed[..];
         let extended_omega = domain.get_extended_omega();
         let num_lookups = pk.vk.cs.lookups.len();
         let isize = size as i32;
@@ -552,12 +587,10 @@ impl<C: CurveAffine> Evaluator<C> {
         let num_threads = multicore::current_num_threads();
         let mut table_values_box = ThreadBox::wrap(&mut lookup_values);
 
-        for (((advice, instance), lookups), permutation) in advice
-            .iter()
-            .zip(instance.iter())
-            .zip(lookups.iter())
-            .zip(permutations.iter())
-        {
+        for circuit_idx in 0..num_circuits {
+            let columns = columns(circuit_idx)?;
+            let (advice, instance) = (&columns.advice[..], &columns.instance[..]);
+
             let timer = ark_std::start_timer!(|| "expressions");
             multicore::scope(|scope| {
                 let chunk_size = (size + num_threads - 1) / num_threads;
@@ -569,6 +602,9 @@ impl<C: CurveAffine> Evaluator<C> {
                         let mut intermediates: Vec<C::ScalarExt> =
                             vec![C::ScalarExt::zero(); self.calculations.len()];
                         for (i, value) in values.iter_mut().enumerate() {
+                            if i & 0xfff == 0 && tracker.is_cancelled() {
+                                return;
+                            }
                             let idx = start + i;
 
                             // All rotation index values
@@ -623,10 +659,11 @@ impl<C: CurveAffine> Evaluator<C> {
                 }
             });
             end_timer!(timer);
+            tracker.step()?;
 
             let timer = ark_std::start_timer!(|| "permutations");
             // Permutations
-            let sets = &permutation.sets;
+            let sets = &columns.permutation_products;
             if !sets.is_empty() {
                 let blinding_factors = pk.vk.cs.blinding_factors();
                 let last_rotation = Rotation(-((blinding_factors + 1) as i32));
@@ -640,30 +677,26 @@ impl<C: CurveAffine> Evaluator<C> {
                 parallelize(&mut values, |values, start| {
                     let mut beta_term = extended_omega.pow_vartime(&[start as u64, 0, 0, 0]);
                     for (i, value) in values.iter_mut().enumerate() {
+                        if i & 0xfff == 0 && tracker.is_cancelled() {
+                            return;
+                        }
                         let idx = start + i;
                         let r_next = get_rotation_idx(idx, 1, rot_scale, isize);
                         let r_last = get_rotation_idx(idx, last_rotation.0, rot_scale, isize);
 
                         // Enforce only for the first set.
                         // l_0(X) * (1 - z_0(X)) = 0
-                        *value = *value * y
-                            + ((one - first_set.permutation_product_coset[idx]) * l0[idx]);
+                        *value = *value * y + ((one - first_set[idx]) * l0[idx]);
                         // Enforce only for the last set.
                         // l_last(X) * (z_l(X)^2 - z_l(X)) = 0
                         *value = *value * y
-                            + ((last_set.permutation_product_coset[idx]
-                                * last_set.permutation_product_coset[idx]
-                                - last_set.permutation_product_coset[idx])
-                                * l_last[idx]);
+                            + ((last_set[idx] * last_set[idx] - last_set[idx]) * l_last[idx]);
                         // Except for the first set, enforce.
                         // l_0(X) * (z_i(X) - z_{i-1}(\omega^(last) X)) = 0
                         for (set_idx, set) in sets.iter().enumerate() {
                             if set_idx != 0 {
-                                *value = *value * y
-                                    + ((set.permutation_product_coset[idx]
-                                        - permutation.sets[set_idx - 1].permutation_product_coset
-                                            [r_last])
-                                        * l0[idx]);
+                                *value =
+                                    *value * y + ((set[idx] - sets[set_idx - 1][r_last]) * l0[idx]);
                             }
                         }
                         // And for all the sets we enforce:
@@ -677,7 +710,7 @@ impl<C: CurveAffine> Evaluator<C> {
                             .zip(p.columns.chunks(chunk_len))
                             .zip(pk.permutation.cosets.chunks(chunk_len))
                         {
-                            let mut left = set.permutation_product_coset[r_next];
+                            let mut left = set[r_next];
                             for (values, permutation) in columns
                                 .iter()
                                 .map(|&column| match column.column_type() {
@@ -690,7 +723,7 @@ impl<C: CurveAffine> Evaluator<C> {
                                 left *= values[idx] + beta * permutation[idx] + gamma;
                             }
 
-                            let mut right = set.permutation_product_coset[idx];
+                            let mut right = set[idx];
                             for values in columns.iter().map(|&column| match column.column_type() {
                                 Any::Advice => &advice[column.index()],
                                 Any::Fixed => &fixed[column.index()],
@@ -707,27 +740,28 @@ impl<C: CurveAffine> Evaluator<C> {
                 });
             }
             end_timer!(timer);
+            tracker.step()?;
 
             let timer = ark_std::start_timer!(|| "eval_h_lookups");
 
-            for (lookup_idx, lookup) in lookups.iter().enumerate() {
+            for (lookup_idx, lookup) in columns.lookups.iter().enumerate() {
                 // Lookup constraints
                 let table = &lookup_values[lookup_idx * size..(lookup_idx + 1) * size];
                 // Polynomials required for this lookup.
                 // Calculated here so these only have to be kept in memory for the short time
                 // they are actually needed.
-                let product_coset = pk.vk.domain.coeff_to_extended(lookup.product_poly.clone());
-                let permuted_input_coset = pk
-                    .vk
-                    .domain
-                    .coeff_to_extended(lookup.permuted_input_poly.clone());
-                let permuted_table_coset = pk
-                    .vk
-                    .domain
-                    .coeff_to_extended(lookup.permuted_table_poly.clone());
+                let to_extended = |values: &[C::ScalarExt]| {
+                    domain.coeff_to_extended(domain.coeff_from_vec(values.to_vec()))
+                };
+                let product_coset = to_extended(&lookup.product);
+                let permuted_input_coset = to_extended(&lookup.permuted_input);
+                let permuted_table_coset = to_extended(&lookup.permuted_table);
 
                 parallelize(&mut values, |values, start| {
                     for (i, value) in values.iter_mut().enumerate() {
+                        if i & 0xfff == 0 && tracker.is_cancelled() {
+                            return;
+                        }
                         let idx = start + i;
 
                         let r_next = get_rotation_idx(idx, 1, rot_scale, isize);
@@ -768,12 +802,294 @@ impl<C: CurveAffine> Evaluator<C> {
                                 * l_active_row[idx]);
                     }
                 });
+                tracker.step()?;
             }
 
             end_timer!(timer);
         }
 
-        values
+        Ok(values)
+    }
+
+    /// Evaluate h poly one coset of the original domain at a time.
+    ///
+    /// This computes the same polynomial as [`Self::evaluate_h`], but only
+    /// ever holds the evaluations of the columns over one of the
+    /// `extended_len() / n` cosets that make up the extended domain, so that
+    /// its memory use scales with `n` instead of `extended_len()`. The
+    /// cosets are computed from the coefficient forms of the columns, and
+    /// `columns` returns those of the given circuit instance, out of
+    /// `num_circuits`. The tracker is stepped once per coset.
+    #[cfg(not(feature = "cuda"))]
+    pub(in crate::plonk) fn evaluate_h_by_coset<'a>(
+        &self,
+        pk: &ProvingKey<C>,
+        num_circuits: usize,
+        columns: impl Fn(usize) -> Result<CircuitColumns<'a, C::ScalarExt>, Error>,
+        y: C::ScalarExt,
+        beta: C::ScalarExt,
+        gamma: C::ScalarExt,
+        theta: C::ScalarExt,
+        tracker: &PhaseTracker,
+    ) -> Result<Polynomial<C::ScalarExt, ExtendedLagrangeCoeff>, Error> {
+        let domain = &pk.vk.domain;
+        let n = 1 << domain.k();
+        let num_parts = domain.extended_len() >> domain.k();
+        let omega = domain.get_omega();
+        let extended_omega = domain.get_extended_omega();
+        let num_lookups = pk.vk.cs.lookups.len();
+        let isize = n as i32;
+        let one = C::ScalarExt::one();
+        let l0 = &pk.l0;
+        let l_last = &pk.l_last;
+        let l_active_row = &pk.l_active_row;
+        let p = &pk.vk.cs.permutation;
+
+        let mut h = domain.empty_extended();
+        let mut lookup_values = vec![C::Scalar::zero(); n * num_lookups];
+
+        let num_threads = multicore::current_num_threads();
+        let mut table_values_box = ThreadBox::wrap(&mut lookup_values);
+
+        for part in 0..num_parts {
+            // The index in the extended domain of the `i`-th value of this coset.
+            let extended_idx = move |i: usize| part + i * num_parts;
+            let to_part = |values: &[C::ScalarExt]| {
+                domain.coeff_to_extended_part(domain.coeff_from_vec(values.to_vec()), part)
+            };
+
+            let timer = ark_std::start_timer!(|| format!("coset {}", part));
+            let fixed: Vec<_> = pk
+                .fixed_polys
+                .par_iter()
+                .map(|poly| to_part(poly))
+                .collect();
+            let mut values = vec![C::ScalarExt::zero(); n];
+
+            for circuit_idx in 0..num_circuits {
+                let columns = columns(circuit_idx)?;
+                let advice: Vec<_> = columns.advice.par_iter().map(|v| to_part(v)).collect();
+                let instance: Vec<_> = columns.instance.par_iter().map(|v| to_part(v)).collect();
+                let (fixed, advice, instance) = (&fixed[..], &advice[..], &instance[..]);
+
+                // Core expression evaluations
+                multicore::scope(|scope| {
+                    let chunk_size = (n + num_threads - 1) / num_threads;
+                    for (thread_idx, values) in values.chunks_mut(chunk_size).enumerate() {
+                        let start = thread_idx * chunk_size;
+                        scope.spawn(move |_| {
+                            let table_values = table_values_box.unwrap();
+                            let mut rotations = vec![0usize; self.rotations.len()];
+                            let mut intermediates: Vec<C::ScalarExt> =
+                                vec![C::ScalarExt::zero(); self.calculations.len()];
+                            for (i, value) in values.iter_mut().enumerate() {
+                                if i & 0xfff == 0 && tracker.is_cancelled() {
+                                    return;
+                                }
+                                let idx = start + i;
+
+                                // All rotation index values
+                                for (rot_idx, rot) in self.rotations.iter().enumerate() {
+                                    rotations[rot_idx] = get_rotation_idx(idx, *rot, 1, isize);
+                                }
+
+                                // All calculations, with cached intermediate results
+                                for (i_idx, calc) in self.calculations.iter().enumerate() {
+                                    intermediates[i_idx] = calc.calculation.evaluate(
+                                        &rotations,
+                                        &self.constants,
+                                        &intermediates,
+                                        fixed,
+                                        advice,
+                                        instance,
+                                        &beta,
+                                        &gamma,
+                                        &theta,
+                                    );
+                                }
+
+                                // Accumulate value parts
+                                for value_part in self.value_parts.iter() {
+                                    *value = *value * y
+                                        + value_part.get(
+                                            &rotations,
+                                            &self.constants,
+                                            &intermediates,
+                                            fixed,
+                                            advice,
+                                            instance,
+                                        );
+                                }
+
+                                // Values required for the lookups
+                                for (t, table_result) in self.lookup_results.iter().enumerate() {
+                                    table_values[t * n + idx] = table_result.evaluate(
+                                        &rotations,
+                                        &self.constants,
+                                        &intermediates,
+                                        fixed,
+                                        advice,
+                                        instance,
+                                        &beta,
+                                        &gamma,
+                                        &theta,
+                                    );
+                                }
+                            }
+                        });
+                    }
+                });
+                tracker.poll()?;
+
+                // Permutations
+                let sets: Vec<_> = columns
+                    .permutation_products
+                    .par_iter()
+                    .map(|set| to_part(set))
+                    .collect();
+                if !sets.is_empty() {
+                    let blinding_factors = pk.vk.cs.blinding_factors();
+                    let last_rotation = Rotation(-((blinding_factors + 1) as i32));
+                    let chunk_len = pk.vk.cs.degree() - 2;
+                    let delta_start = beta * &C::Scalar::ZETA;
+
+                    let first_set = sets.first().unwrap();
+                    let last_set = sets.last().unwrap();
+
+                    // Permutation constraints
+                    parallelize(&mut values, |values, start| {
+                        let mut beta_term =
+                            extended_omega.pow_vartime(&[extended_idx(start) as u64, 0, 0, 0]);
+                        for (i, value) in values.iter_mut().enumerate() {
+                            if i & 0xfff == 0 && tracker.is_cancelled() {
+                                return;
+                            }
+                            let idx = start + i;
+                            let extended = extended_idx(idx);
+                            let r_next = get_rotation_idx(idx, 1, 1, isize);
+                            let r_last = get_rotation_idx(idx, last_rotation.0, 1, isize);
+
+                            // Enforce only for the first set.
+                            // l_0(X) * (1 - z_0(X)) = 0
+                            *value = *value * y + ((one - first_set[idx]) * l0[extended]);
+                            // Enforce only for the last set.
+                            // l_last(X) * (z_l(X)^2 - z_l(X)) = 0
+                            *value = *value * y
+                                + ((last_set[idx] * last_set[idx] - last_set[idx])
+                                    * l_last[extended]);
+                            // Except for the first set, enforce.
+                            // l_0(X) * (z_i(X) - z_{i-1}(\omega^(last) X)) = 0
+                            for (set_idx, set) in sets.iter().enumerate() {
+                                if set_idx != 0 {
+                                    *value = *value * y
+                                        + ((set[idx] - sets[set_idx - 1][r_last]) * l0[extended]);
+                                }
+                            }
+                            // And for all the sets we enforce:
+                            // (1 - (l_last(X) + l_blind(X))) * (
+                            //   z_i(\omega X) \prod_j (p(X) + \beta s_j(X) + \gamma)
+                            // - z_i(X) \prod_j (p(X) + \delta^j \beta X + \gamma)
+                            // )
+                            let mut current_delta = delta_start * beta_term;
+                            for ((set, columns), cosets) in sets
+                                .iter()
+                                .zip(p.columns.chunks(chunk_len))
+                                .zip(pk.permutation.cosets.chunks(chunk_len))
+                            {
+                                let mut left = set[r_next];
+                                for (values, permutation) in columns
+                                    .iter()
+                                    .map(|&column| match column.column_type() {
+                                        Any::Advice => &advice[column.index()],
+                                        Any::Fixed => &fixed[column.index()],
+                                        Any::Instance => &instance[column.index()],
+                                    })
+                                    .zip(cosets.iter())
+                                {
+                                    left *= values[idx] + beta * permutation[extended] + gamma;
+                                }
+
+                                let mut right = set[idx];
+                                for values in
+                                    columns.iter().map(|&column| match column.column_type() {
+                                        Any::Advice => &advice[column.index()],
+                                        Any::Fixed => &fixed[column.index()],
+                                        Any::Instance => &instance[column.index()],
+                                    })
+                                {
+                                    right *= values[idx] + current_delta + gamma;
+                                    current_delta *= &C::Scalar::DELTA;
+                                }
+
+                                *value = *value * y + ((left - right) * l_active_row[extended]);
+                            }
+                            beta_term *= &omega;
+                        }
+                    });
+                }
+                drop(sets);
+                tracker.poll()?;
+
+                for (lookup_idx, lookup) in columns.lookups.iter().enumerate() {
+                    // Lookup constraints
+                    let table = &lookup_values[lookup_idx * n..(lookup_idx + 1) * n];
+                    let product_coset = to_part(&lookup.product);
+                    let permuted_input_coset = to_part(&lookup.permuted_input);
+                    let permuted_table_coset = to_part(&lookup.permuted_table);
+
+                    parallelize(&mut values, |values, start| {
+                        for (i, value) in values.iter_mut().enumerate() {
+                            if i & 0xfff == 0 && tracker.is_cancelled() {
+                                return;
+                            }
+                            let idx = start + i;
+                            let extended = extended_idx(idx);
+
+                            let r_next = get_rotation_idx(idx, 1, 1, isize);
+                            let r_prev = get_rotation_idx(idx, -1, 1, isize);
+
+                            let a_minus_s = permuted_input_coset[idx] - permuted_table_coset[idx];
+                            // l_0(X) * (1 - z(X)) = 0
+                            *value = *value * y + ((one - product_coset[idx]) * l0[extended]);
+                            // l_last(X) * (z(X)^2 - z(X)) = 0
+                            *value = *value * y
+                                + ((product_coset[idx] * product_coset[idx] - product_coset[idx])
+                                    * l_last[extended]);
+                            // (1 - (l_last(X) + l_blind(X))) * (
+                            //   z(\omega X) (a'(X) + \beta) (s'(X) + \gamma)
+                            //   - z(X) (\theta^{m-1} a_0(X) + ... + a_{m-1}(X) + \beta)
+                            //          (\theta^{m-1} s_0(X) + ... + s_{m-1}(X) + \gamma)
+                            // ) = 0
+                            *value = *value * y
+                                + ((product_coset[r_next]
+                                    * (permuted_input_coset[idx] + beta)
+                                    * (permuted_table_coset[idx] + gamma)
+                                    - product_coset[idx] * table[idx])
+                                    * l_active_row[extended]);
+
+                            // l_0(X) * (a'(X) - s'(X)) = 0
+                            *value = *value * y + (a_minus_s * l0[extended]);
+
+                            // (1 - (l_last + l_blind)) * (a′(X) − s′(X))⋅(a′(X) − a′(\omega^{-1} X)) = 0
+                            *value = *value * y
+                                + (a_minus_s
+                                    * (permuted_input_coset[idx] - permuted_input_coset[r_prev])
+                                    * l_active_row[extended]);
+                        }
+                    });
+                    tracker.poll()?;
+                }
+            }
+
+            // Move the values of this coset to their place in the extended domain.
+            for (i, value) in values.into_iter().enumerate() {
+                h[extended_idx(i)] = value;
+            }
+            end_timer!(timer);
+            tracker.step()?;
+        }
+
+        Ok(h)
     }
 
     #[cfg(feature = "cuda")]
@@ -788,7 +1104,8 @@ impl<C: CurveAffine> Evaluator<C> {
         theta: C::ScalarExt,
         lookups: &[Vec<lookup::prover::Committed<C>>],
         permutations: &[permutation::prover::Committed<C>],
-    ) -> Polynomial<C::ScalarExt, ExtendedLagrangeCoeff> {
+        tracker: &PhaseTracker,
+    ) -> Result<Polynomial<C::ScalarExt, ExtendedLagrangeCoeff>, Error> {
         use crate::arithmetic::acquire_gpu;
         use crate::arithmetic::release_gpu;
         use ec_gpu_gen::{fft::FftKernel, rust_gpu_tools::Device, rust_gpu_tools::LocalBuffer};
@@ -813,18 +1130,22 @@ impl<C: CurveAffine> Evaluator<C> {
             .ev
             .gpu_gates_expr
             .par_iter()
-            .map(|x| {
+            .map(|x| -> Result<_, Error> {
+                // Only poll outside of the acquire/release pair so that a
+                // cancelled proof never keeps a GPU index.
+                tracker.poll()?;
                 let gpu_idx = acquire_gpu();
                 let r = x.eval_gpu(gpu_idx, pk, &advice_poly[0], &instance_poly[0], y);
                 release_gpu(gpu_idx);
-                r
+                Ok(r)
             })
-            .collect::<Vec<_>>()
+            .collect::<Result<Vec<_>, _>>()?
             .into_iter()
             .reduce(|acc, x| acc + &x)
             .unwrap();
 
         end_timer!(timer);
+        tracker.step()?;
 
         let domain = &pk.vk.domain;
         let size = domain.extended_len();
@@ -1041,6 +1362,7 @@ impl<C: CurveAffine> Evaluator<C> {
                 }
             );
 
+            tracker.poll()?;
             let devices = Device::all();
             let gpu_idx = acquire_gpu();
 
@@ -1056,6 +1378,7 @@ impl<C: CurveAffine> Evaluator<C> {
             release_gpu(gpu_idx);
         }
         end_timer!(timer);
+        tracker.step()?;
 
         let timer = ark_std::start_timer!(|| "eval_h_lookups");
         let lookups = &lookups[0];
@@ -1067,9 +1390,10 @@ impl<C: CurveAffine> Evaluator<C> {
             values = lookups
                 .par_chunks(group_expr_len)
                 .enumerate()
-                .map(|(group_idx, lookups)| {
+                .map(|(group_idx, lookups)| -> Result<_, Error> {
                     // combine fft with eval_h_lookups:
                     // fft code: from ec-gpu lib.
+                    tracker.poll()?;
                     let mut buffer = vec![];
                     buffer.resize(domain.extended_len(), C::Scalar::zero());
 
@@ -1200,9 +1524,12 @@ impl<C: CurveAffine> Evaluator<C> {
                         })
                         .unwrap();
                     release_gpu(gpu_idx);
-                    (tmp_value, lookups.len())
+                    for _ in lookups {
+                        tracker.step()?;
+                    }
+                    Ok((tmp_value, lookups.len()))
                 })
-                .collect::<Vec<_>>()
+                .collect::<Result<Vec<_>, _>>()?
                 .iter()
                 .fold(values, |acc, (x, len)| {
                     acc * y.pow_vartime([*len as u64 * 5, 0, 0, 0]) + x
@@ -1211,7 +1538,7 @@ impl<C: CurveAffine> Evaluator<C> {
 
         end_timer!(timer);
 
-        values
+        Ok(values)
     }
 }
 
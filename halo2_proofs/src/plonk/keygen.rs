@@ -95,6 +95,18 @@ impl<F: Field> Assignment<F> for Assembly<F> {
         Ok(None)
     }
 
+    fn query_fixed(&self, column: Column<Fixed>, row: usize) -> Result<Option<F>, Error> {
+        if !self.usable_rows.contains(&row) {
+            return Err(Error::not_enough_rows_available(self.k));
+        }
+
+        self.fixed
+            .get(column.index())
+            .and_then(|column| column.get(row))
+            .map(|v| Some(v.evaluate()))
+            .ok_or(Error::BoundsFailure)
+    }
+
     fn assign_advice<V, VR, A, AR>(
         &mut self,
         _: A,
@@ -138,6 +150,37 @@ impl<F: Field> Assignment<F> for Assembly<F> {
         Ok(())
     }
 
+    fn assign_advice_slice(&mut self, _: Column<Advice>, _: usize, _: &[F]) -> Result<(), Error> {
+        // We only care about fixed columns here
+        Ok(())
+    }
+
+    fn assign_fixed_slice(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        values: &[F],
+    ) -> Result<(), Error> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        if !self.usable_rows.contains(&row) || !self.usable_rows.contains(&(row + values.len() - 1))
+        {
+            return Err(Error::not_enough_rows_available(self.k));
+        }
+
+        let cells = self
+            .fixed
+            .get_mut(column.index())
+            .and_then(|v| v[..].get_mut(row..row + values.len()))
+            .ok_or(Error::BoundsFailure)?;
+        for (cell, value) in cells.iter_mut().zip(values.iter()) {
+            *cell = Assigned::Trivial(*value);
+        }
+
+        Ok(())
+    }
+
     fn copy(
         &mut self,
         left_column: Column<Any>,
@@ -392,26 +435,23 @@ where
     })
 }
 
-/// Generate a `ProvingKey` from a `VerifyingKey` and an instance of `Circuit`.
+/// Generate a `ProvingKey` from a `VerifyingKey`, the values of all of its
+/// fixed columns (including the columns that selectors were compressed into)
+/// and its permutation assembly.
 pub(crate) fn keygen_pk_from_info<C>(
     params: &Params<C>,
-    vk: &VerifyingKey<C>,
+    vk: VerifyingKey<C>,
     fixed: Vec<Polynomial<C::Scalar, LagrangeCoeff>>,
     permutation: permutation::keygen::Assembly,
 ) -> Result<ProvingKey<C>, Error>
 where
     C: CurveAffine,
 {
+    // The constraint system of the verifying key already has its selectors
+    // compressed.
     let cs = vk.cs.clone();
-    assert!(cs.num_selectors == 0);
-    //We do not support the case when selectors exists
-    //let selectors = vec![vec![false; params.n as usize]; cs.num_selectors];
-    let selectors = vec![];
     use ark_std::{start_timer, end_timer};
 
-    let timer = start_timer!(|| "compress selectors ...");
-    let (cs, _) = cs.compress_selectors(selectors);
-    end_timer!(timer);
     let timer = start_timer!(|| "fixed polys ...");
 
     let fixed_polys: Vec<_> = fixed
@@ -476,7 +516,7 @@ where
     let l_last = l_last_extended;
 
     Ok(ProvingKey {
-        vk: vk.clone(),
+        vk,
         l0,
         l_last,
         l_active_row,
@@ -490,6 +530,137 @@ where
     })
 }
 
+/// The parts of a circuit that [`keygen_vk_from_parts`] and
+/// [`keygen_pk_from_parts`] generate keys from, in place of a [`Circuit`].
+#[derive(Clone, Debug)]
+pub struct CircuitParts<F: Field> {
+    /// The constraint system of the circuit, before its selectors are
+    /// compressed, such as built by [`Circuit::configure`] or read by
+    /// [`read_cs`](crate::helpers::read_cs).
+    pub cs: ConstraintSystem<F>,
+    /// The values of each fixed column, from the first row. Rows that are not
+    /// given are zero.
+    pub fixed: Vec<Vec<F>>,
+    /// The rows at which each selector is enabled, from the first row.
+    pub selectors: Vec<Vec<bool>>,
+    /// The copy constraints of the circuit, each between the cell of the
+    /// first column and row and the cell of the second column and row.
+    pub copies: Vec<(Column<Any>, usize, Column<Any>, usize)>,
+}
+
+/// Assigns the parts of a circuit, and returns its constraint system with
+/// compressed selectors, the values of its fixed columns including the
+/// selector columns, and its permutation assembly.
+fn assemble_parts<C: CurveAffine>(
+    params: &Params<C>,
+    domain: &EvaluationDomain<C::Scalar>,
+    parts: &CircuitParts<C::Scalar>,
+) -> Result<
+    (
+        ConstraintSystem<C::Scalar>,
+        Vec<Polynomial<C::Scalar, LagrangeCoeff>>,
+        permutation::keygen::Assembly,
+    ),
+    Error,
+> {
+    let cs = &parts.cs;
+
+    if (params.n as usize) < cs.minimum_rows() {
+        return Err(Error::not_enough_rows_available(params.k));
+    }
+    if !cs.selector_map.is_empty() {
+        return Err(Error::InvalidCircuitParts(
+            "the selectors of the constraint system are already compressed".to_string(),
+        ));
+    }
+    if parts.fixed.len() != cs.num_fixed_columns {
+        return Err(Error::InvalidCircuitParts(format!(
+            "expected {} fixed columns, found {}",
+            cs.num_fixed_columns,
+            parts.fixed.len()
+        )));
+    }
+    if parts.selectors.len() != cs.num_selectors {
+        return Err(Error::InvalidCircuitParts(format!(
+            "expected {} selectors, found {}",
+            cs.num_selectors,
+            parts.selectors.len()
+        )));
+    }
+
+    let mut assembly: Assembly<C::Scalar> = Assembly {
+        k: params.k,
+        fixed: vec![domain.empty_lagrange_assigned(); cs.num_fixed_columns],
+        permutation: permutation::keygen::Assembly::new(params.n as usize, &cs.permutation),
+        selectors: vec![vec![false; params.n as usize]; cs.num_selectors],
+        usable_rows: 0..params.n as usize - (cs.blinding_factors() + 1),
+        _marker: std::marker::PhantomData,
+    };
+
+    for (index, values) in parts.fixed.iter().enumerate() {
+        assembly.assign_fixed_slice(Column::new(index, Fixed), 0, values)?;
+    }
+    for (index, rows) in parts.selectors.iter().enumerate() {
+        for (row, _) in rows.iter().enumerate().filter(|(_, enabled)| **enabled) {
+            // The assembly only uses the index of the selector.
+            assembly.enable_selector(|| "", &Selector(index, false), row)?;
+        }
+    }
+    for (left_column, left_row, right_column, right_row) in parts.copies.iter() {
+        assembly.copy(*left_column, *left_row, *right_column, *right_row)?;
+    }
+
+    let mut fixed = batch_invert_assigned(assembly.fixed);
+    let (cs, selector_polys) = cs.clone().compress_selectors(assembly.selectors);
+    fixed.extend(
+        selector_polys
+            .into_iter()
+            .map(|poly| domain.lagrange_from_vec(poly)),
+    );
+
+    Ok((cs, fixed, assembly.permutation))
+}
+
+/// Generate a `VerifyingKey` from the parts of a circuit, without a `Circuit`.
+pub fn keygen_vk_from_parts<C: CurveAffine>(
+    params: &Params<C>,
+    parts: &CircuitParts<C::Scalar>,
+) -> Result<VerifyingKey<C>, Error> {
+    let domain = EvaluationDomain::new(parts.cs.degree() as u32, params.k);
+    let (cs, fixed, permutation) = assemble_parts(params, &domain, parts)?;
+
+    let permutation_vk = permutation.build_vk(params, &domain, &cs.permutation);
+
+    let fixed_commitments = fixed
+        .iter()
+        .map(|poly| params.commit_lagrange(poly).to_affine())
+        .collect();
+
+    Ok(VerifyingKey {
+        domain,
+        fixed_commitments,
+        permutation: permutation_vk,
+        cs,
+    })
+}
+
+/// Generate a `ProvingKey` from a `VerifyingKey` and the parts of the circuit
+/// it was generated from, without a `Circuit`.
+pub fn keygen_pk_from_parts<C: CurveAffine>(
+    params: &Params<C>,
+    vk: VerifyingKey<C>,
+    parts: &CircuitParts<C::Scalar>,
+) -> Result<ProvingKey<C>, Error> {
+    let (cs, fixed, permutation) = assemble_parts(params, &vk.domain, parts)?;
+    if format!("{:?}", cs.pinned()) != format!("{:?}", vk.cs.pinned()) {
+        return Err(Error::InvalidCircuitParts(
+            "the constraint system does not match the verifying key".to_string(),
+        ));
+    }
+
+    keygen_pk_from_info(params, vk, fixed, permutation)
+}
+
 /// Generate a `ProvingKey` from a `VerifyingKey` and an instance of `Circuit`.
 pub(crate) fn generate_pk_info<C, ConcreteCircuit>(
     params: &Params<C>,
@@ -1,12 +1,14 @@
 use super::circuit::Expression;
+use alloc::{string::String, vec::Vec};
 use ff::Field;
 
+#[cfg(feature = "std")]
 pub(crate) mod prover;
 pub(crate) mod verifier;
 
 #[derive(Clone, Debug)]
 pub struct Argument<F: Field> {
-    pub name: &'static str,
+    pub name: String,
     pub input_expressions: Vec<Expression<F>>,
     pub table_expressions: Vec<Expression<F>>,
 }
@@ -15,10 +17,10 @@ impl<F: Field> Argument<F> {
     /// Constructs a new lookup argument.
     ///
     /// `table_map` is a sequence of `(input, table)` tuples.
-    pub fn new(name: &'static str, table_map: Vec<(Expression<F>, Expression<F>)>) -> Self {
+    pub fn new(name: impl Into<String>, table_map: Vec<(Expression<F>, Expression<F>)>) -> Self {
         let (input_expressions, table_expressions) = table_map.into_iter().unzip();
         Argument {
-            name,
+            name: name.into(),
             input_expressions,
             table_expressions,
         }
@@ -53,18 +55,18 @@ impl<F: Field> Argument<F> {
         // (1 - (l_last(X) + l_blind(X))) * (a′(X) − s′(X))⋅(a′(X) − a′(\omega^{-1} X)) = 0
         let mut input_degree = 1;
         for expr in self.input_expressions.iter() {
-            input_degree = std::cmp::max(input_degree, expr.degree());
+            input_degree = core::cmp::max(input_degree, expr.degree());
         }
         let mut table_degree = 1;
         for expr in self.table_expressions.iter() {
-            table_degree = std::cmp::max(table_degree, expr.degree());
+            table_degree = core::cmp::max(table_degree, expr.degree());
         }
 
         // In practice because input_degree and table_degree are initialized to
         // one, the latter half of this max() invocation is at least 4 always,
         // rendering this call pointless except to be explicit in case we change
         // the initialization of input_degree/table_degree in the future.
-        std::cmp::max(
+        core::cmp::max(
             // (1 - (l_last + l_blind)) z(\omega X) (a'(X) + \beta) (s'(X) + \gamma)
             4,
             // (1 - (l_last + l_blind)) z(X) (\theta^{m-1} a_0(X) + ... + a_{m-1}(X) + \beta) (\theta^{m-1} s_0(X) + ... + s_{m-1}(X) + \gamma)
@@ -38,9 +38,9 @@ use std::{
 
 #[derive(Debug)]
 pub(in crate::plonk) struct Permuted<C: CurveAffine> {
-    compressed_input_expression: Polynomial<C::Scalar, LagrangeCoeff>,
+    pub(in crate::plonk) compressed_input_expression: Polynomial<C::Scalar, LagrangeCoeff>,
     pub(in crate::plonk) permuted_input_expression: Polynomial<C::Scalar, LagrangeCoeff>,
-    compressed_table_expression: Polynomial<C::Scalar, LagrangeCoeff>,
+    pub(in crate::plonk) compressed_table_expression: Polynomial<C::Scalar, LagrangeCoeff>,
     pub(in crate::plonk) permuted_table_expression: Polynomial<C::Scalar, LagrangeCoeff>,
 }
 
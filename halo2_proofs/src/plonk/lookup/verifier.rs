@@ -1,4 +1,5 @@
-use std::iter;
+use alloc::vec::Vec;
+use core::iter;
 
 use super::super::{
     circuit::Expression, ChallengeBeta, ChallengeGamma, ChallengeTheta, ChallengeX,
@@ -140,7 +141,7 @@ impl<C: CurveAffine> Evaluated<C> {
             (left - &right) * &active_rows
         };
 
-        std::iter::empty()
+        core::iter::empty()
             .chain(
                 // l_0(X) * (1 - z'(X)) = 0
                 Some(l_0 * &(C::Scalar::one() - &self.product_eval)),
@@ -0,0 +1,183 @@
+//! Memory budgeting for the prover, which spills polynomials that are not in
+//! use to memory-mapped scratch files.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use memmap::{Mmap, MmapOptions};
+
+use super::Error;
+use crate::poly::Polynomial;
+
+/// A limit on the memory used by the CPU prover for its polynomials.
+///
+/// The extended cosets of the advice, instance and permutation product
+/// polynomials are only cached while the quotient polynomial is evaluated if
+/// they fit in the budget, together with the proving key, the coefficient
+/// forms of all the polynomials and the buffers of the evaluation. Otherwise
+/// the coefficient forms of the advice, instance, lookup and permutation
+/// product polynomials are spilled to scratch files until the evaluation
+/// phase, and the cosets are recomputed one column at a time, each being
+/// spilled as soon as it is computed and read back through a memory map.
+///
+/// The budget is a target rather than a hard limit: the proving key, which
+/// holds the fixed polynomials and their cosets, is borrowed by the prover
+/// and always counts against it, and the polynomials needed by the phase
+/// being run are kept in memory. The GPU prover does not keep cosets and
+/// ignores the budget.
+#[derive(Clone, Debug)]
+pub struct MemoryBudget {
+    limit: usize,
+    scratch_dir: PathBuf,
+}
+
+impl MemoryBudget {
+    /// Creates a budget of `limit` bytes that spills to the system's
+    /// temporary directory.
+    pub fn new(limit: usize) -> Self {
+        MemoryBudget {
+            limit,
+            scratch_dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Sets the directory in which scratch files are created.
+    pub fn with_scratch_dir<P: Into<PathBuf>>(mut self, scratch_dir: P) -> Self {
+        self.scratch_dir = scratch_dir.into();
+        self
+    }
+
+    /// Returns the limit of this budget, in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns `true` if `bytes` fit in this budget.
+    pub(crate) fn fits(&self, bytes: usize) -> bool {
+        bytes <= self.limit
+    }
+
+    /// Moves `poly` to a new scratch file.
+    pub(crate) fn spill<F: Copy, B>(&self, poly: Polynomial<F, B>) -> Result<Spilled<F, B>, Error> {
+        Spilled::new(&self.scratch_dir, poly).map_err(Error::ScratchFile)
+    }
+}
+
+/// A polynomial that has been moved to a scratch file. The file is removed
+/// when this is dropped.
+#[derive(Debug)]
+pub(crate) struct Spilled<F, B> {
+    file: File,
+    path: PathBuf,
+    len: usize,
+    _marker: PhantomData<(F, B)>,
+}
+
+impl<F: Copy, B> Spilled<F, B> {
+    fn new(scratch_dir: &Path, poly: Polynomial<F, B>) -> io::Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let path = scratch_dir.join(format!(
+            "halo2-spill-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let spilled = Spilled {
+            file,
+            path,
+            len: poly.len(),
+            _marker: PhantomData,
+        };
+
+        let bytes = spilled.len * mem::size_of::<F>();
+        if bytes > 0 {
+            spilled.file.set_len(bytes as u64)?;
+            let mut mmap = unsafe { MmapOptions::new().len(bytes).map_mut(&spilled.file)? };
+            let s: &[u8] = unsafe { std::slice::from_raw_parts(poly.as_ptr() as *const u8, bytes) };
+            mmap.copy_from_slice(s);
+            mmap.flush()?;
+        }
+
+        Ok(spilled)
+    }
+
+    /// Reads the polynomial back from its scratch file.
+    pub(crate) fn load(&self) -> Result<Polynomial<F, B>, Error> {
+        Ok(Polynomial::new(self.map()?.to_vec()))
+    }
+
+    /// Maps the values of the polynomial from its scratch file, without
+    /// reading them into memory. The map stays valid after this is dropped.
+    pub(crate) fn map(&self) -> Result<Mapped<F>, Error> {
+        let mmap = if self.len == 0 {
+            None
+        } else {
+            let bytes = self.len * mem::size_of::<F>();
+            let mmap = unsafe { MmapOptions::new().len(bytes).map(&self.file) }
+                .map_err(Error::ScratchFile)?;
+            Some(mmap)
+        };
+
+        Ok(Mapped {
+            mmap,
+            len: self.len,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<F, B> Drop for Spilled<F, B> {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// The values of a spilled polynomial, mapped from its scratch file.
+#[derive(Debug)]
+pub(crate) struct Mapped<F> {
+    mmap: Option<Mmap>,
+    len: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Copy> Deref for Mapped<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        match &self.mmap {
+            Some(mmap) => unsafe {
+                std::slice::from_raw_parts(mmap.as_ptr() as *const F, self.len)
+            },
+            None => &[],
+        }
+    }
+}
+
+/// The values of a polynomial, which are either in memory or mapped from the
+/// scratch file it was spilled to.
+#[derive(Debug)]
+pub(crate) enum Values<'a, F> {
+    Borrowed(&'a [F]),
+    Mapped(Mapped<F>),
+}
+
+impl<'a, F: Copy> Deref for Values<'a, F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        match self {
+            Values::Borrowed(values) => values,
+            Values::Mapped(values) => values,
+        }
+    }
+}
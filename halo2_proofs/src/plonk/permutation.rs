@@ -5,11 +5,14 @@ use crate::{
     poly::{Coeff, ExtendedLagrangeCoeff, LagrangeCoeff, Polynomial},
 };
 
+#[cfg(feature = "std")]
 pub(crate) mod keygen;
+#[cfg(feature = "std")]
 pub(crate) mod prover;
 pub(crate) mod verifier;
 
-use std::io;
+use crate::io;
+use alloc::vec::Vec;
 
 /// A permutation argument.
 #[derive(Debug, Clone)]
@@ -96,6 +99,7 @@ impl<C: CurveAffine> VerifyingKey<C> {
 }
 
 /// The proving key for a single permutation argument.
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub(crate) struct ProvingKey<C: CurveAffine> {
     permutations: Vec<Polynomial<C::Scalar, LagrangeCoeff>>,
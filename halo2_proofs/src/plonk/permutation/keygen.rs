@@ -1,5 +1,6 @@
 use ark_std::{end_timer, start_timer};
-use ff::Field;
+use ff::{Field, PrimeField};
+use std::collections::HashMap;
 use group::Curve;
 use rayon::prelude::*;
 
@@ -214,3 +215,41 @@ impl Assembly {
         }
     }
 }
+
+impl<C: CurveAffine> ProvingKey<C> {
+    /// Recovers the mapping of the permutation, as in [`Assembly`], from the
+    /// permutation polynomials.
+    pub(crate) fn mapping(&self, domain: &EvaluationDomain<C::Scalar>) -> Vec<Vec<(u32, u32)>> {
+        let n = 1usize << domain.k();
+        let omega = domain.get_omega();
+
+        // Index the cells by their value delta^i omega^j.
+        let mut cells = HashMap::with_capacity(n * self.permutations.len());
+        let mut delta = C::Scalar::one();
+        for i in 0..self.permutations.len() {
+            let mut x = delta;
+            for j in 0..n {
+                cells.insert(x.to_repr().as_ref().to_vec(), (i as u32, j as u32));
+                x *= omega;
+            }
+            delta *= C::Scalar::DELTA;
+        }
+
+        self.permutations
+            .par_iter()
+            .enumerate()
+            .map(|(i, permutation)| {
+                permutation
+                    .iter()
+                    .enumerate()
+                    .map(|(j, value)| {
+                        cells
+                            .get(value.to_repr().as_ref())
+                            .copied()
+                            .unwrap_or((i as u32, j as u32))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -16,16 +16,13 @@ use crate::{
     },
     plonk::{self, Error},
     poly::{
-        commitment::Params, multiopen::ProverQuery, Coeff, ExtendedLagrangeCoeff, LagrangeCoeff,
-        Polynomial, Rotation,
+        commitment::Params, multiopen::ProverQuery, Coeff, LagrangeCoeff, Polynomial, Rotation,
     },
     transcript::{EncodedChallenge, TranscriptWrite},
 };
 
 pub(crate) struct CommittedSet<C: CurveAffine> {
     pub(crate) permutation_product_poly: Polynomial<C::Scalar, Coeff>,
-    #[cfg(not(feature = "cuda"))]
-    pub(crate) permutation_product_coset: Polynomial<C::Scalar, ExtendedLagrangeCoeff>,
 }
 
 pub(crate) struct Committed<C: CurveAffine> {
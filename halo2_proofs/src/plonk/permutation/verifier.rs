@@ -1,5 +1,6 @@
+use alloc::vec::Vec;
+use core::iter;
 use ff::Field;
-use std::iter;
 
 use super::super::{circuit::Any, ChallengeBeta, ChallengeGamma, ChallengeX};
 use super::{Argument, VerifyingKey};
@@ -0,0 +1,501 @@
+//! A structured view of the contents of a proof, for debugging and for building
+//! external verifiers.
+
+use alloc::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use crate::io;
+
+use ff::PrimeField;
+use group::GroupEncoding;
+
+use super::{Any, Column, ColumnType, Error, InstanceEvaluation, VerifyingKey};
+use crate::arithmetic::CurveAffine;
+use crate::poly::Rotation;
+use crate::transcript::{read_n_points, read_n_scalars, Blake2bRead, Challenge255, TranscriptRead};
+
+/// The contents of a proof, labelled with the parts of the circuit they belong to.
+///
+/// The view is obtained by reading a proof in the same order as [`verify_proof`]
+/// does, without checking it. Commitments are only checked to be valid curve
+/// points, and evaluations to be canonical field elements.
+///
+/// [`verify_proof`]: crate::plonk::verify_proof
+#[derive(Clone, Debug)]
+pub struct ProofView<C: CurveAffine> {
+    /// The parts of the proof for each circuit instance.
+    pub instances: Vec<InstanceView<C>>,
+    /// The commitment to the random polynomial of the vanishing argument.
+    pub vanishing_random_commitment: C,
+    /// The commitments to the pieces of the quotient polynomial `h(X)`.
+    pub vanishing_h_commitments: Vec<C>,
+    /// The evaluation of the random polynomial of the vanishing argument at `x`.
+    pub vanishing_random_eval: C::Scalar,
+    /// The evaluations of the fixed column queries, shared by all circuit
+    /// instances.
+    pub fixed_evals: Vec<EvaluationView<C::Scalar>>,
+    /// The evaluations of the permutation polynomials at `x`, in the order of the
+    /// columns of the permutation argument.
+    pub permutation_common_evals: Vec<C::Scalar>,
+    /// The commitments of the multi-opening proof.
+    pub multiopen: Vec<C>,
+}
+
+/// The parts of a proof that belong to one circuit instance.
+#[derive(Clone, Debug)]
+pub struct InstanceView<C: CurveAffine> {
+    /// The commitment to each advice column, labelled with the column.
+    pub advice_commitments: Vec<(String, C)>,
+    /// The commitments and evaluations of each lookup argument.
+    pub lookups: Vec<LookupView<C>>,
+    /// The commitments and evaluations of each chunk of the permutation argument.
+    pub permutation_products: Vec<PermutationProductView<C>>,
+    /// The evaluations of the instance column queries. These are empty if the
+    /// circuit uses [`InstanceEvaluation::Direct`].
+    pub instance_evals: Vec<EvaluationView<C::Scalar>>,
+    /// The evaluations of the advice column queries.
+    pub advice_evals: Vec<EvaluationView<C::Scalar>>,
+}
+
+/// The evaluation of a column at `x` rotated by `rotation`.
+#[derive(Clone, Debug)]
+pub struct EvaluationView<F> {
+    /// The label of the column.
+    pub column: String,
+    /// The rotation of the query.
+    pub rotation: i32,
+    /// The value of the evaluation.
+    pub value: F,
+}
+
+/// The parts of a proof that belong to one lookup argument.
+#[derive(Clone, Debug)]
+pub struct LookupView<C: CurveAffine> {
+    /// The name of the lookup argument.
+    pub name: String,
+    /// The commitment to the permuted input expression.
+    pub permuted_input_commitment: C,
+    /// The commitment to the permuted table expression.
+    pub permuted_table_commitment: C,
+    /// The commitment to the grand product.
+    pub product_commitment: C,
+    /// The evaluation of the grand product at `x`.
+    pub product_eval: C::Scalar,
+    /// The evaluation of the grand product at `\omega x`.
+    pub product_next_eval: C::Scalar,
+    /// The evaluation of the permuted input expression at `x`.
+    pub permuted_input_eval: C::Scalar,
+    /// The evaluation of the permuted input expression at `\omega^{-1} x`.
+    pub permuted_input_inv_eval: C::Scalar,
+    /// The evaluation of the permuted table expression at `x`.
+    pub permuted_table_eval: C::Scalar,
+}
+
+/// The parts of a proof that belong to one chunk of the permutation argument.
+#[derive(Clone, Debug)]
+pub struct PermutationProductView<C: CurveAffine> {
+    /// The commitment to the grand product of the chunk.
+    pub commitment: C,
+    /// The evaluation of the grand product at `x`.
+    pub eval: C::Scalar,
+    /// The evaluation of the grand product at `\omega x`.
+    pub next_eval: C::Scalar,
+    /// The evaluation of the grand product at the last usable row, which is
+    /// absent for the last chunk.
+    pub last_eval: Option<C::Scalar>,
+}
+
+impl<C: CurveAffine> ProofView<C> {
+    /// Reads a proof of `num_instances` circuit instances of the circuit with
+    /// verifying key `vk`.
+    ///
+    /// Returns [`Error::Transcript`] if the proof is too short, too long or
+    /// contains an invalid encoding.
+    pub fn parse(vk: &VerifyingKey<C>, bytes: &[u8], num_instances: usize) -> Result<Self, Error> {
+        let cs = &vk.cs;
+        let num_chunks = if cs.permutation.columns.is_empty() {
+            0
+        } else {
+            let chunk_len = cs.degree() - 2;
+            (cs.permutation.columns.len() + chunk_len - 1) / chunk_len
+        };
+        // Every chunk of the permutation argument but the last is also evaluated
+        // at the last usable row.
+        let num_permutation_evals = if num_chunks == 0 {
+            0
+        } else {
+            3 * num_chunks - 1
+        };
+        let instance_queries = match cs.instance_evaluation {
+            InstanceEvaluation::Committed => &cs.instance_queries[..],
+            InstanceEvaluation::Direct => &[],
+        };
+
+        let mut reader = bytes;
+        let mut transcript = Blake2bRead::<_, C, Challenge255<C>>::init(&mut reader);
+
+        // The parts of the proof are read in the order in which `verify_proof`
+        // reads them.
+        let advice_commitments =
+            read_points_per_instance(&mut transcript, num_instances, cs.num_advice_columns)?;
+        let lookups_permuted =
+            read_points_per_instance(&mut transcript, num_instances, 2 * cs.lookups.len())?;
+        let permutation_commitments =
+            read_points_per_instance(&mut transcript, num_instances, num_chunks)?;
+        let lookup_products =
+            read_points_per_instance(&mut transcript, num_instances, cs.lookups.len())?;
+
+        let vanishing_random_commitment = transcript.read_point()?;
+        let vanishing_h_commitments =
+            read_n_points(&mut transcript, vk.domain.get_quotient_poly_degree())?;
+
+        let instance_evals =
+            read_scalars_per_instance(&mut transcript, num_instances, instance_queries.len())?;
+        let advice_evals =
+            read_scalars_per_instance(&mut transcript, num_instances, cs.advice_queries.len())?;
+        let fixed_evals = read_n_scalars(&mut transcript, cs.fixed_queries.len())?;
+        let vanishing_random_eval = transcript.read_scalar()?;
+        let permutation_common_evals =
+            read_n_scalars(&mut transcript, vk.permutation.commitments.len())?;
+        let permutation_evals =
+            read_scalars_per_instance(&mut transcript, num_instances, num_permutation_evals)?;
+        let lookup_evals =
+            read_scalars_per_instance(&mut transcript, num_instances, 5 * cs.lookups.len())?;
+
+        let multiopen = read_n_points(
+            &mut transcript,
+            num_multiopen_commitments(vk, num_instances, num_chunks),
+        )?;
+
+        drop(transcript);
+        if !reader.is_empty() {
+            return Err(Error::Transcript(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "trailing bytes after the proof",
+            )));
+        }
+
+        let mut advice_commitments = advice_commitments.into_iter();
+        let mut lookups_permuted = lookups_permuted.into_iter();
+        let mut permutation_commitments = permutation_commitments.into_iter();
+        let mut lookup_products = lookup_products.into_iter();
+        let mut instance_evals = instance_evals.into_iter();
+        let mut advice_evals = advice_evals.into_iter();
+        let mut permutation_evals = permutation_evals.into_iter();
+        let mut lookup_evals = lookup_evals.into_iter();
+        let instances = (0..num_instances)
+            .map(|_| {
+                let advice_commitments = advice_commitments
+                    .next()
+                    .unwrap()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, commitment)| {
+                        (
+                            column_label(vk, Column::new(index, Any::Advice)),
+                            commitment,
+                        )
+                    })
+                    .collect();
+
+                let lookups = cs
+                    .lookups
+                    .iter()
+                    .zip(lookups_permuted.next().unwrap().chunks(2))
+                    .zip(lookup_products.next().unwrap())
+                    .zip(lookup_evals.next().unwrap().chunks(5))
+                    .map(|(((argument, permuted), product), evals)| LookupView {
+                        name: argument.name.clone(),
+                        permuted_input_commitment: permuted[0],
+                        permuted_table_commitment: permuted[1],
+                        product_commitment: product,
+                        product_eval: evals[0],
+                        product_next_eval: evals[1],
+                        permuted_input_eval: evals[2],
+                        permuted_input_inv_eval: evals[3],
+                        permuted_table_eval: evals[4],
+                    })
+                    .collect();
+
+                let mut evals = permutation_evals.next().unwrap().into_iter();
+                let permutation_products = permutation_commitments
+                    .next()
+                    .unwrap()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(chunk, commitment)| PermutationProductView {
+                        commitment,
+                        eval: evals.next().unwrap(),
+                        next_eval: evals.next().unwrap(),
+                        last_eval: if chunk + 1 < num_chunks {
+                            evals.next()
+                        } else {
+                            None
+                        },
+                    })
+                    .collect();
+
+                InstanceView {
+                    advice_commitments,
+                    lookups,
+                    permutation_products,
+                    instance_evals: evaluations(
+                        vk,
+                        instance_queries,
+                        instance_evals.next().unwrap(),
+                    ),
+                    advice_evals: evaluations(vk, &cs.advice_queries, advice_evals.next().unwrap()),
+                }
+            })
+            .collect();
+
+        Ok(ProofView {
+            instances,
+            vanishing_random_commitment,
+            vanishing_h_commitments,
+            vanishing_random_eval,
+            fixed_evals: evaluations(vk, &cs.fixed_queries, fixed_evals),
+            permutation_common_evals,
+            multiopen,
+        })
+    }
+
+    /// Serializes the view to JSON.
+    ///
+    /// Commitments are written as the hex encoding of their compressed form, and
+    /// evaluations as the hex encoding of their canonical representation, both as
+    /// they appear in the proof.
+    pub fn to_json(&self) -> String {
+        json_object(&[
+            (
+                "instances",
+                json_array(self.instances.iter().map(instance_json)),
+            ),
+            (
+                "vanishing_random_commitment",
+                json_point(&self.vanishing_random_commitment),
+            ),
+            (
+                "vanishing_h_commitments",
+                json_array(self.vanishing_h_commitments.iter().map(json_point)),
+            ),
+            (
+                "vanishing_random_eval",
+                json_scalar(&self.vanishing_random_eval),
+            ),
+            ("fixed_evals", json_evaluations(&self.fixed_evals)),
+            (
+                "permutation_common_evals",
+                json_array(self.permutation_common_evals.iter().map(json_scalar)),
+            ),
+            (
+                "multiopen",
+                json_array(self.multiopen.iter().map(json_point)),
+            ),
+        ])
+    }
+}
+
+fn instance_json<C: CurveAffine>(instance: &InstanceView<C>) -> String {
+    let advice_commitments = instance
+        .advice_commitments
+        .iter()
+        .map(|(column, commitment)| {
+            json_object(&[
+                ("column", json_string(column)),
+                ("commitment", json_point(commitment)),
+            ])
+        });
+    let lookups = instance.lookups.iter().map(|lookup| {
+        json_object(&[
+            ("name", json_string(&lookup.name)),
+            (
+                "permuted_input_commitment",
+                json_point(&lookup.permuted_input_commitment),
+            ),
+            (
+                "permuted_table_commitment",
+                json_point(&lookup.permuted_table_commitment),
+            ),
+            ("product_commitment", json_point(&lookup.product_commitment)),
+            ("product_eval", json_scalar(&lookup.product_eval)),
+            ("product_next_eval", json_scalar(&lookup.product_next_eval)),
+            (
+                "permuted_input_eval",
+                json_scalar(&lookup.permuted_input_eval),
+            ),
+            (
+                "permuted_input_inv_eval",
+                json_scalar(&lookup.permuted_input_inv_eval),
+            ),
+            (
+                "permuted_table_eval",
+                json_scalar(&lookup.permuted_table_eval),
+            ),
+        ])
+    });
+    let permutation_products = instance.permutation_products.iter().map(|product| {
+        json_object(&[
+            ("commitment", json_point(&product.commitment)),
+            ("eval", json_scalar(&product.eval)),
+            ("next_eval", json_scalar(&product.next_eval)),
+            (
+                "last_eval",
+                product
+                    .last_eval
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), json_scalar),
+            ),
+        ])
+    });
+
+    json_object(&[
+        ("advice_commitments", json_array(advice_commitments)),
+        ("lookups", json_array(lookups)),
+        ("permutation_products", json_array(permutation_products)),
+        ("instance_evals", json_evaluations(&instance.instance_evals)),
+        ("advice_evals", json_evaluations(&instance.advice_evals)),
+    ])
+}
+
+fn read_points_per_instance<C: CurveAffine, T: TranscriptRead<C, Challenge255<C>>>(
+    transcript: &mut T,
+    num_instances: usize,
+    n: usize,
+) -> io::Result<Vec<Vec<C>>> {
+    (0..num_instances)
+        .map(|_| read_n_points(transcript, n))
+        .collect()
+}
+
+fn read_scalars_per_instance<C: CurveAffine, T: TranscriptRead<C, Challenge255<C>>>(
+    transcript: &mut T,
+    num_instances: usize,
+    n: usize,
+) -> io::Result<Vec<Vec<C::Scalar>>> {
+    (0..num_instances)
+        .map(|_| read_n_scalars(transcript, n))
+        .collect()
+}
+
+/// Labels the evaluations of `queries` with their columns and rotations.
+fn evaluations<C: CurveAffine, T: ColumnType>(
+    vk: &VerifyingKey<C>,
+    queries: &[(Column<T>, Rotation)],
+    evals: Vec<C::Scalar>,
+) -> Vec<EvaluationView<C::Scalar>> {
+    queries
+        .iter()
+        .zip(evals.into_iter())
+        .map(|(&(column, at), value)| EvaluationView {
+            column: column_label(
+                vk,
+                Column::new(column.index(), (*column.column_type()).into()),
+            ),
+            rotation: at.0,
+            value,
+        })
+        .collect()
+}
+
+/// Returns the name of `column`, or its type and index if it is not named.
+fn column_label<C: CurveAffine>(vk: &VerifyingKey<C>, column: Column<Any>) -> String {
+    match vk.cs.column_name(column) {
+        Some(name) => name.to_string(),
+        None => format!("{:?}[{}]", column.column_type(), column.index()),
+    }
+}
+
+/// Returns the number of commitments in the multi-opening proof.
+fn num_multiopen_commitments<C: CurveAffine>(
+    vk: &VerifyingKey<C>,
+    num_instances: usize,
+    num_chunks: usize,
+) -> usize {
+    if cfg!(feature = "shplonk") {
+        return 2;
+    }
+
+    // The GWC multi-opening proof has one commitment per point at which the
+    // polynomials are opened.
+    let cs = &vk.cs;
+    let mut rotations = BTreeSet::new();
+    // The vanishing argument is opened at x.
+    rotations.insert(0);
+    if num_instances > 0 {
+        if cs.instance_evaluation == InstanceEvaluation::Committed {
+            rotations.extend(cs.instance_queries.iter().map(|(_, at)| at.0));
+        }
+        rotations.extend(cs.advice_queries.iter().map(|(_, at)| at.0));
+        if num_chunks > 0 {
+            rotations.insert(Rotation::next().0);
+        }
+        if num_chunks > 1 {
+            rotations.insert(-((cs.blinding_factors() + 1) as i32));
+        }
+        if !cs.lookups.is_empty() {
+            rotations.insert(Rotation::prev().0);
+            rotations.insert(Rotation::next().0);
+        }
+    }
+    rotations.extend(cs.fixed_queries.iter().map(|(_, at)| at.0));
+    rotations.len()
+}
+
+fn json_object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|(name, value)| format!("{}:{}", json_string(name), value))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn json_array(items: impl Iterator<Item = String>) -> String {
+    format!("[{}]", items.collect::<Vec<_>>().join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_hex(bytes: &[u8]) -> String {
+    let mut json = String::from("\"");
+    for byte in bytes {
+        write!(json, "{:02x}", byte).unwrap();
+    }
+    json.push('"');
+    json
+}
+
+fn json_point<C: CurveAffine>(point: &C) -> String {
+    json_hex(point.to_bytes().as_ref())
+}
+
+fn json_scalar<F: PrimeField>(scalar: &F) -> String {
+    json_hex(scalar.to_repr().as_ref())
+}
+
+fn json_evaluations<F: PrimeField>(evaluations: &[EvaluationView<F>]) -> String {
+    json_array(evaluations.iter().map(|evaluation| {
+        format!(
+            "{{\"column\":{},\"rotation\":{},\"value\":{}}}",
+            json_string(&evaluation.column),
+            evaluation.rotation,
+            json_scalar(&evaluation.value)
+        )
+    }))
+}
@@ -1,7 +1,6 @@
 use ark_std::UniformRand;
 use ark_std::{end_timer, start_timer};
 use ff::Field;
-use ff::PrimeField;
 use group::Curve;
 use rand_core::OsRng;
 use rand_core::RngCore;
@@ -14,22 +13,33 @@ use rayon::slice::ParallelSlice;
 use std::env::var;
 use std::iter::FromIterator;
 use std::fs::File;
+use std::io::{Seek, SeekFrom};
 use std::ops::RangeTo;
 use std::sync::atomic::AtomicUsize;
 use std::sync::{Condvar, Mutex};
 use std::time::Instant;
 use std::{iter, sync::atomic::Ordering};
-use crate::helpers::AssignWitnessCollection;
+use crate::dev::MockProver;
+use crate::helpers::{scalar_bits, AssignWitnessCollection, WitnessEncoding};
 
 use super::{
+    checkpoint::{round_rng, LookupState, ROUND_ADVICE, ROUND_LOOKUP_PERMUTED, ROUND_PRODUCTS},
     circuit::{
         Advice, Any, Assignment, Circuit, Column, ConstraintSystem, Fixed, FloorPlanner, Instance,
-        Selector,
+        InstanceEvaluation, Selector,
     },
+    control::PhaseTracker,
     lookup, permutation, vanishing, ChallengeBeta, ChallengeGamma, ChallengeTheta, ChallengeX,
-    ChallengeY, Error, ProvingKey,
+    ChallengeY, Checkpoint, Error, NoControl, ProverControl, ProverPhase, ProvingKey,
+    QuotientEvaluation, VerifyingKey,
+};
+#[cfg(not(feature = "cuda"))]
+use super::{
+    evaluation::{CircuitColumns, LookupColumns},
+    memory::{Spilled, Values},
 };
 use crate::arithmetic::eval_polynomial_st;
+use crate::multicore;
 use crate::plonk::lookup::prover::Permuted;
 use crate::{
     arithmetic::{eval_polynomial, BaseExt, CurveAffine, FieldExt},
@@ -41,7 +51,7 @@ use crate::{
         self,
         commitment::{Blind, Params},
         multiopen::{self, ProverQuery},
-        Coeff, ExtendedLagrangeCoeff, LagrangeCoeff, Polynomial, Rotation,
+        Coeff, LagrangeCoeff, Polynomial, Rotation,
     },
 };
 use crate::{
@@ -72,9 +82,48 @@ lazy_static! {
 struct InstanceSingle<C: CurveAffine> {
     pub instance_values: Vec<Polynomial<C::Scalar, LagrangeCoeff>>,
     pub instance_polys: Vec<Polynomial<C::Scalar, Coeff>>,
+}
 
-    #[cfg(not(feature = "cuda"))]
-    pub instance_cosets: Vec<Polynomial<C::Scalar, ExtendedLagrangeCoeff>>,
+/// Commits to the instance columns of each circuit instance, as
+/// [`verify_proof_with_instance_commitments`] expects them.
+///
+/// Returns [`Error::InvalidInstances`] if the circuit uses
+/// [`InstanceEvaluation::Direct`], as its instance columns are not committed to.
+///
+/// [`verify_proof_with_instance_commitments`]: crate::plonk::verify_proof_with_instance_commitments
+pub fn commit_instances<C: CurveAffine>(
+    params: &Params<C>,
+    vk: &VerifyingKey<C>,
+    instances: &[&[&[C::Scalar]]],
+) -> Result<Vec<Vec<C>>, Error> {
+    if vk.cs.instance_evaluation == InstanceEvaluation::Direct {
+        return Err(Error::InvalidInstances);
+    }
+
+    let usable_rows = params.n as usize - (vk.cs.blinding_factors() + 1);
+    instances
+        .iter()
+        .map(|instance| {
+            if instance.len() != vk.cs.num_instance_columns {
+                return Err(Error::InvalidInstances);
+            }
+
+            let commitments_projective = instance
+                .iter()
+                .map(|values| {
+                    if values.len() > usable_rows {
+                        return Err(Error::InstanceTooLarge);
+                    }
+                    let mut poly = vk.domain.empty_lagrange();
+                    poly[..][..values.len()].copy_from_slice(values);
+                    Ok(params.commit_lagrange(&poly))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut commitments = vec![C::identity(); commitments_projective.len()];
+            C::Curve::batch_normalize(&commitments_projective, &mut commitments);
+            Ok(commitments)
+        })
+        .collect()
 }
 
 fn create_single_instances<
@@ -122,18 +171,35 @@ fn create_single_instances<
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
-            let instance_commitments_projective: Vec<_> = instance_values
-                .iter()
-                .map(|poly| params.commit_lagrange(poly))
-                .collect();
-            let mut instance_commitments =
-                vec![C::identity(); instance_commitments_projective.len()];
-            C::Curve::batch_normalize(&instance_commitments_projective, &mut instance_commitments);
-            let instance_commitments = instance_commitments;
-            drop(instance_commitments_projective);
-
-            for commitment in &instance_commitments {
-                transcript.common_point(*commitment)?;
+            match meta.instance_evaluation {
+                InstanceEvaluation::Committed => {
+                    let instance_commitments_projective: Vec<_> = instance_values
+                        .iter()
+                        .map(|poly| params.commit_lagrange(poly))
+                        .collect();
+                    let mut instance_commitments =
+                        vec![C::identity(); instance_commitments_projective.len()];
+                    C::Curve::batch_normalize(
+                        &instance_commitments_projective,
+                        &mut instance_commitments,
+                    );
+                    let instance_commitments = instance_commitments;
+                    drop(instance_commitments_projective);
+
+                    for commitment in &instance_commitments {
+                        transcript.common_point(*commitment)?;
+                    }
+                }
+                InstanceEvaluation::Direct => {
+                    // The verifier evaluates the instance columns itself, so
+                    // the values are hashed in place of the commitments.
+                    for values in instance.iter() {
+                        transcript.common_scalar(C::Scalar::from(values.len() as u64))?;
+                        for value in values.iter() {
+                            transcript.common_scalar(*value)?;
+                        }
+                    }
+                }
             }
 
             let instance_polys: Vec<_> = instance_values
@@ -144,25 +210,35 @@ fn create_single_instances<
                 })
                 .collect();
 
-            #[cfg(not(feature = "cuda"))]
-            let instance_cosets: Vec<_> = instance_polys
-                .iter()
-                .map(|poly| domain.coeff_to_extended(poly.clone()))
-                .collect();
-
             Ok(InstanceSingle {
                 instance_values,
                 instance_polys,
-
-                #[cfg(not(feature = "cuda"))]
-                instance_cosets,
             })
         })
         .collect::<Result<Vec<_>, _>>()?;
     Ok(instance)
 }
 
+/// Returns the number of bits of the largest of `values`, which bounds the
+/// scalars of a commitment to them.
+fn find_max_scalar_bits<F: FieldExt>(values: &[F]) -> usize {
+    scalar_bits(&values.iter().fold(F::zero(), |acc, x| acc.max(*x)))
+}
+
+struct AdviceSingle<C: CurveAffine> {
+    pub advice_polys: Vec<Polynomial<C::Scalar, Coeff>>,
+}
 
+/// The coefficient forms of the polynomials of a circuit instance, spilled
+/// while the quotient polynomial is evaluated. Each lookup spills its
+/// permuted input, permuted table and product polynomials.
+#[cfg(not(feature = "cuda"))]
+struct SpilledSingle<C: CurveAffine> {
+    advice_polys: Vec<Spilled<C::Scalar, Coeff>>,
+    instance_polys: Vec<Spilled<C::Scalar, Coeff>>,
+    lookup_polys: Vec<[Spilled<C::Scalar, Coeff>; 3]>,
+    permutation_product_polys: Vec<Spilled<C::Scalar, Coeff>>,
+}
 
 /// This creates a proof for the provided `circuit` when given the public
 /// parameters `params` and the proving key [`ProvingKey`] that was
@@ -179,14 +255,77 @@ pub fn create_proof<
     pk: &ProvingKey<C>,
     circuits: &[ConcreteCircuit],
     instances: &[&[&[C::Scalar]]],
-    mut rng: R,
+    rng: R,
+    transcript: &mut T,
+) -> Result<(), Error> {
+    create_proof_with_control(params, pk, circuits, instances, rng, transcript, &NoControl)
+}
+
+/// Same as [`create_proof`], but reports progress to `control` and polls it
+/// for cancellation between phases and inside the long running loops.
+///
+/// Returns [`Error::Cancelled`] if `control` requested cancellation, in
+/// which case the transcript is left in an unspecified state.
+pub fn create_proof_with_control<
+    C: CurveAffine,
+    E: EncodedChallenge<C>,
+    R: RngCore,
+    T: TranscriptWrite<C, E>,
+    ConcreteCircuit: Circuit<C::Scalar>,
+>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    circuits: &[ConcreteCircuit],
+    instances: &[&[&[C::Scalar]]],
+    rng: R,
     transcript: &mut T,
+    control: &dyn ProverControl,
 ) -> Result<(), Error> {
+    create_proof_with_checkpoints(
+        params,
+        pk,
+        circuits,
+        instances,
+        rng,
+        transcript,
+        control,
+        &mut |_| Ok(()),
+    )
+}
 
+/// Same as [`create_proof_with_control`], but hands a [`Checkpoint`] to
+/// `on_checkpoint` after each of the transcript rounds that commit to the
+/// advice columns, the permuted lookup columns and the product columns.
+///
+/// A checkpoint can be persisted with [`Checkpoint::write`], and the proof
+/// later finished from it with [`resume_proof`]. All blinding factors are
+/// derived from a seed drawn from `rng` and stored in the checkpoint, so the
+/// resumed proof is identical to the one this call would have produced.
+pub fn create_proof_with_checkpoints<
+    C: CurveAffine,
+    E: EncodedChallenge<C>,
+    R: RngCore,
+    T: TranscriptWrite<C, E>,
+    ConcreteCircuit: Circuit<C::Scalar>,
+>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    circuits: &[ConcreteCircuit],
+    instances: &[&[&[C::Scalar]]],
+    mut rng: R,
+    transcript: &mut T,
+    control: &dyn ProverControl,
+    on_checkpoint: &mut dyn FnMut(&Checkpoint<C>) -> Result<(), Error>,
+) -> Result<(), Error> {
     let domain = &pk.vk.domain;
 
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+
     let timer = start_timer!(|| "instance");
+    let tracker = PhaseTracker::start(control, ProverPhase::Instance, 1)?;
     let instance = create_single_instances(params, pk, instances, transcript)?;
+    tracker.step()?;
     end_timer!(timer);
 
     let mut meta = ConstraintSystem::default();
@@ -195,39 +334,23 @@ pub fn create_proof<
     let meta = &pk.vk.cs;
 
     let timer = start_timer!(|| "advice");
-    struct AdviceSingle<C: CurveAffine> {
-        pub advice_polys: Vec<Polynomial<C::Scalar, Coeff>>,
-
-        #[cfg(not(feature = "cuda"))]
-        pub advice_cosets: Vec<Polynomial<C::Scalar, ExtendedLagrangeCoeff>>,
-    }
-
-    let get_scalar_bits = |x: C::Scalar| {
-        let repr = x.to_repr();
-        let max_scalar_repr_ref: &[u8] = repr.as_ref();
-        max_scalar_repr_ref
-            .iter()
-            .enumerate()
-            .fold(0, |acc, (idx, v)| {
-                if *v == 0 {
-                    acc
-                } else {
-                    idx * 8 + 8 - v.leading_zeros() as usize
-                }
-            })
-    };
-
-    let find_max_scalar_bits = |x: &Vec<C::Scalar>| {
-        get_scalar_bits(x.iter().fold(C::Scalar::zero(), |acc, x| acc.max(*x)))
-    };
+    let tracker = PhaseTracker::start(
+        control,
+        ProverPhase::Advice,
+        circuits.len() * meta.num_advice_columns,
+    )?;
 
-    let advice: Vec<Vec<Polynomial<C::Scalar, LagrangeCoeff>>> = circuits
+    let (advice, advice_commitments): (Vec<_>, Vec<_>) = circuits
         .iter()
         .zip(instances.iter())
-        .map(|(circuit, instances)| {
+        .enumerate()
+        .map(|(circuit_idx, (circuit, instances))| -> Result<_, Error> {
             struct WitnessCollection<'a, F: Field> {
                 k: u32,
                 pub advice: Vec<Polynomial<F, LagrangeCoeff>>,
+                // Whether each advice cell has been assigned, so that queries
+                // of unassigned cells return `None` as in `MockProver`.
+                assigned: Vec<Vec<bool>>,
                 instances: &'a [&'a [F]],
                 usable_rows: RangeTo<usize>,
                 _marker: std::marker::PhantomData<F>,
@@ -277,6 +400,27 @@ pub fn create_proof<
                         .ok_or(Error::BoundsFailure)
                 }
 
+                fn query_advice(
+                    &self,
+                    column: Column<Advice>,
+                    row: usize,
+                ) -> Result<Option<F>, Error> {
+                    if !self.usable_rows.contains(&row) {
+                        return Err(Error::not_enough_rows_available(self.k));
+                    }
+
+                    self.advice
+                        .get(column.index())
+                        .and_then(|column| column.get(row))
+                        .zip(
+                            self.assigned
+                                .get(column.index())
+                                .and_then(|column| column.get(row)),
+                        )
+                        .map(|(v, assigned)| if *assigned { Some(*v) } else { None })
+                        .ok_or(Error::BoundsFailure)
+                }
+
                 fn assign_advice<V, VR, A, AR>(
                     &mut self,
                     _: A,
@@ -306,6 +450,7 @@ pub fn create_proof<
                         .get_mut(column.index())
                         .and_then(|v| v.get_mut(row))
                         .ok_or(Error::BoundsFailure)? = v;
+                    self.assigned[column.index()][row] = true;
 
                     Ok(())
                 }
@@ -328,6 +473,39 @@ pub fn create_proof<
                     Ok(())
                 }
 
+                fn assign_advice_slice(
+                    &mut self,
+                    column: Column<Advice>,
+                    row: usize,
+                    values: &[F],
+                ) -> Result<(), Error> {
+                    if row + values.len() > self.usable_rows.end {
+                        return Err(Error::not_enough_rows_available(self.k));
+                    }
+
+                    self.advice
+                        .get_mut(column.index())
+                        .and_then(|v| v[..].get_mut(row..row + values.len()))
+                        .ok_or(Error::BoundsFailure)?
+                        .copy_from_slice(values);
+                    for assigned in &mut self.assigned[column.index()][row..row + values.len()] {
+                        *assigned = true;
+                    }
+
+                    Ok(())
+                }
+
+                fn assign_fixed_slice(
+                    &mut self,
+                    _: Column<Fixed>,
+                    _: usize,
+                    _: &[F],
+                ) -> Result<(), Error> {
+                    // We only care about advice columns here
+
+                    Ok(())
+                }
+
                 fn copy(
                     &mut self,
                     _: Column<Any>,
@@ -372,6 +550,7 @@ pub fn create_proof<
             let mut witness = WitnessCollection {
                 k: params.k,
                 advice,
+                assigned: vec![vec![false; params.n as usize]; meta.num_advice_columns],
                 instances,
                 // The prover will not be allowed to assign values to advice
                 // cells that exist within inactive rows, which include some
@@ -389,19 +568,22 @@ pub fn create_proof<
                 circuit,
                 config.clone(),
                 meta.constants.clone(),
-            )
-            .unwrap();
+            )?;
             end_timer!(timer);
+            tracker.poll()?;
 
             let mut advice = witness.advice;
 
-            let named = &pk.vk.cs.named_advices;
-
             let timer = start_timer!(|| "rng");
             advice.par_iter_mut().enumerate().for_each(|(i, advice)| {
-                if named.iter().find(|n| n.1 as usize == i).is_none() {
+                if !meta.is_exposed_advice(i) {
+                    let mut rng = round_rng(
+                        &seed,
+                        ROUND_ADVICE,
+                        circuit_idx * meta.num_advice_columns + i,
+                    );
                     for cell in &mut advice[unusable_rows_start..] {
-                        *cell = C::Scalar::from(u16::rand(&mut OsRng) as u64);
+                        *cell = C::Scalar::from(u16::rand(&mut rng) as u64);
                     }
                 }
             });
@@ -410,11 +592,13 @@ pub fn create_proof<
             let timer = start_timer!(|| "commit_lagrange");
             let advice_commitments_projective: Vec<_> = advice
                 .par_iter()
-                .map(|advice| {
+                .map(|advice| -> Result<_, Error> {
                     let max_bits = find_max_scalar_bits(&advice.values);
-                    params.commit_lagrange_with_bound(advice, max_bits)
+                    let commitment = params.commit_lagrange_with_bound(advice, max_bits);
+                    tracker.step()?;
+                    Ok(commitment)
                 })
-                .collect();
+                .collect::<Result<_, Error>>()?;
             end_timer!(timer);
 
             let timer = start_timer!(|| "advice_commitments_projective");
@@ -424,235 +608,441 @@ pub fn create_proof<
             drop(advice_commitments_projective);
             end_timer!(timer);
 
-            for commitment in &advice_commitments {
-                transcript.write_point(*commitment).unwrap();
-            }
-
-            advice
+            Ok((advice, advice_commitments))
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    end_timer!(timer);
+
+    let checkpoint = Checkpoint::new(seed, instances, advice, advice_commitments.concat());
+    on_checkpoint(&checkpoint)?;
+
+    create_proof_from_checkpoint(
+        params,
+        pk,
+        instance,
+        checkpoint,
+        transcript,
+        control,
+        on_checkpoint,
+    )
+}
+
+/// Finishes a proof from a [`Checkpoint`] taken by
+/// [`create_proof_with_checkpoints`] for the same `pk` and `instances`.
+///
+/// The transcript must be freshly initialized; the rounds completed by the
+/// checkpoint are replayed into it before the remaining rounds are run, and
+/// `on_checkpoint` is called for each newly completed round.
+pub fn resume_proof<C: CurveAffine, E: EncodedChallenge<C>, T: TranscriptWrite<C, E>>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    instances: &[&[&[C::Scalar]]],
+    checkpoint: Checkpoint<C>,
+    transcript: &mut T,
+    control: &dyn ProverControl,
+    on_checkpoint: &mut dyn FnMut(&Checkpoint<C>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    checkpoint.validate(pk, instances)?;
+
+    let timer = start_timer!(|| "instance");
+    let tracker = PhaseTracker::start(control, ProverPhase::Instance, 1)?;
+    let instance = create_single_instances(params, pk, instances, transcript)?;
+    tracker.step()?;
+    end_timer!(timer);
+
+    create_proof_from_checkpoint(
+        params,
+        pk,
+        instance,
+        checkpoint,
+        transcript,
+        control,
+        on_checkpoint,
+    )
+}
+
+/// Runs every round of the prover that follows the commitment of the advice
+/// columns of each circuit instance, starting from the last round completed
+/// by `state`.
+fn create_proof_from_checkpoint<
+    C: CurveAffine,
+    E: EncodedChallenge<C>,
+    T: TranscriptWrite<C, E>,
+>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    instance: Vec<InstanceSingle<C>>,
+    mut state: Checkpoint<C>,
+    transcript: &mut T,
+    control: &dyn ProverControl,
+    on_checkpoint: &mut dyn FnMut(&Checkpoint<C>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let meta = &pk.vk.cs;
+    let domain = &pk.vk.domain;
+    let seed = state.seed;
+    let num_proofs = instance.len();
+
+    // Rounds completed before this call have already been handed to
+    // `on_checkpoint`, and only need to be replayed into the transcript.
+    for commitment in state.commitments[ROUND_ADVICE].iter() {
+        transcript.write_point(*commitment)?;
+    }
 
     // Sample theta challenge for keeping lookup columns linearly independent
     let theta: ChallengeTheta<_> = transcript.squeeze_challenge_scalar();
 
-    end_timer!(timer);
-    let timer = start_timer!(|| format!("lookups {}", pk.vk.cs.lookups.len()));
-    let (lookups, lookups_commitments): (Vec<Vec<lookup::prover::Permuted<C>>>, Vec<Vec<[C; 2]>>) =
-        instance
+    if state.rounds() == ROUND_LOOKUP_PERMUTED {
+        let timer = start_timer!(|| format!("lookups {}", pk.vk.cs.lookups.len()));
+        let tracker = PhaseTracker::start(
+            control,
+            ProverPhase::LookupPermuted,
+            num_proofs * meta.lookups.len(),
+        )?;
+        let (lookups, lookups_commitments): (Vec<Vec<_>>, Vec<Vec<[C; 2]>>) = instance
             .iter()
-            .zip(advice.iter())
-            .map(|(instance, advice)| -> (Vec<_>, Vec<_>) {
-                pk.vk
-                    .cs
-                    .lookups
-                    .par_iter()
-                    .map(|lookup| {
-                        lookup
-                            .commit_permuted(
+            .zip(state.advice.iter())
+            .enumerate()
+            .map(
+                |(circuit_idx, (instance, advice))| -> Result<(Vec<_>, Vec<_>), Error> {
+                    let permuted = pk
+                        .vk
+                        .cs
+                        .lookups
+                        .par_iter()
+                        .enumerate()
+                        .map(|(lookup_idx, lookup)| -> Result<_, Error> {
+                            let permuted = lookup.commit_permuted(
                                 pk,
                                 params,
                                 domain,
                                 theta,
-                                &advice,
+                                advice,
                                 &pk.fixed_values,
                                 &instance.instance_values,
-                                &mut OsRng,
-                            )
-                            .unwrap()
-                    })
-                    .unzip()
-            })
+                                round_rng(
+                                    &seed,
+                                    ROUND_LOOKUP_PERMUTED,
+                                    circuit_idx * meta.lookups.len() + lookup_idx,
+                                ),
+                            )?;
+                            tracker.step()?;
+                            Ok(permuted)
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    Ok(permuted.into_iter().unzip())
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
             .unzip();
+        end_timer!(timer);
 
-    lookups_commitments.into_iter().for_each(|x| {
-        x.iter().for_each(|x| {
-            transcript.write_point(x[0]).unwrap();
-            transcript.write_point(x[1]).unwrap();
-        })
-    });
-    end_timer!(timer);
+        state.lookups = LookupState::Permuted(lookups);
+        state.commitments.push(
+            lookups_commitments
+                .iter()
+                .flatten()
+                .flat_map(|c| vec![c[0], c[1]])
+                .collect(),
+        );
+        on_checkpoint(&state)?;
+    }
+
+    for commitment in state.commitments[ROUND_LOOKUP_PERMUTED].iter() {
+        transcript.write_point(*commitment)?;
+    }
 
     // Sample beta challenge
     let beta: ChallengeBeta<_> = transcript.squeeze_challenge_scalar();
     // Sample gamma challenge
     let gamma: ChallengeGamma<_> = transcript.squeeze_challenge_scalar();
 
-    let (lookups, permutations) = std::thread::scope(|s| {
-        let permutations = s.spawn(|| {
-            // prepare permutation value.
-            instance
-                .iter()
-                .zip(advice.iter())
-                .map(|(instance, advice)| {
-                    pk.vk.cs.permutation.commit(
-                        params,
-                        pk,
-                        &pk.permutation,
-                        &advice,
-                        &pk.fixed_values,
-                        &instance.instance_values,
-                        beta,
-                        gamma.clone(),
-                        &mut OsRng,
-                    )
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .unwrap()
-        });
-
-        let timer = start_timer!(|| "lookups commit product");
-        let lookups: Vec<Vec<_>> = lookups
-            .into_iter()
-            .map(|lookups| {
-                lookups
-                    .into_par_iter()
-                    .map(|lookup| lookup.commit_product(pk, params, beta, gamma).unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        end_timer!(timer);
+    if state.rounds() == ROUND_PRODUCTS {
+        let lookups = match std::mem::replace(&mut state.lookups, LookupState::None) {
+            LookupState::Permuted(lookups) => lookups,
+            _ => return Err(Error::InvalidCheckpoint),
+        };
+        let advice = &state.advice;
+
+        let num_permutation_sets = meta.permutation.columns.chunks(meta.degree() - 2).len();
+        let tracker = PhaseTracker::start(
+            control,
+            ProverPhase::Products,
+            num_proofs * (2 * meta.lookups.len() + num_permutation_sets) + 1,
+        )?;
+        // The permutation products are computed alongside the lookup products,
+        // on the thread pool the proof is running on.
+        let (permutations, lookups) = multicore::join(
+            || {
+                // prepare permutation value.
+                instance
+                    .iter()
+                    .zip(advice.iter())
+                    .enumerate()
+                    .map(|(circuit_idx, (instance, advice))| {
+                        tracker.poll()?;
+                        pk.vk.cs.permutation.commit(
+                            params,
+                            pk,
+                            &pk.permutation,
+                            advice,
+                            &pk.fixed_values,
+                            &instance.instance_values,
+                            beta,
+                            gamma.clone(),
+                            round_rng(&seed, ROUND_PRODUCTS, circuit_idx),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+            || -> Result<_, Error> {
+                let timer = start_timer!(|| "lookups commit product");
+                let lookups: Vec<Vec<_>> = lookups
+                    .into_iter()
+                    .map(|lookups| {
+                        lookups
+                            .into_par_iter()
+                            .map(|lookup| -> Result<_, Error> {
+                                let product = lookup.commit_product(pk, params, beta, gamma)?;
+                                tracker.step()?;
+                                Ok(product)
+                            })
+                            .collect::<Result<Vec<_>, Error>>()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                end_timer!(timer);
 
-        let timer = start_timer!(|| "lookups add blinding value");
-        let lookups: Vec<Vec<_>> = lookups
-            .into_iter()
-            .map(|lookups| {
-                lookups
+                let timer = start_timer!(|| "lookups add blinding value");
+                let lookups: Vec<Vec<_>> = lookups
                     .into_iter()
-                    .map(|(l0, l1, mut z)| {
-                        for _ in 0..pk.vk.cs.blinding_factors() {
-                            z.push(C::Scalar::random(&mut rng))
-                        }
-                        (l0, l1, pk.vk.domain.lagrange_from_vec(z))
+                    .enumerate()
+                    .map(|(circuit_idx, lookups)| {
+                        let mut rng = round_rng(&seed, ROUND_PRODUCTS, num_proofs + circuit_idx);
+                        lookups
+                            .into_iter()
+                            .map(|(l0, l1, mut z)| {
+                                for _ in 0..pk.vk.cs.blinding_factors() {
+                                    z.push(C::Scalar::random(&mut rng))
+                                }
+                                (l0, l1, pk.vk.domain.lagrange_from_vec(z))
+                            })
+                            .collect::<Vec<_>>()
                     })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<Vec<_>>>();
-        end_timer!(timer);
+                    .collect::<Vec<Vec<_>>>();
+                end_timer!(timer);
 
-        let timer = start_timer!(|| "lookups msm and fft");
-        let (lookups_z_commitments, lookups): (Vec<Vec<_>>, Vec<Vec<_>>) = lookups
-            .into_iter()
-            .map(|lookups| {
-                lookups
-                    .into_par_iter()
-                    .map(|l| {
-                        let (product_poly, c) = params.commit_lagrange_and_ifft(
-                            l.2,
-                            &pk.vk.domain.get_omega_inv(),
-                            &pk.vk.domain.ifft_divisor,
-                        );
-                        let c = c.to_affine();
-                        (
-                            c,
-                            lookup::prover::Committed {
-                                permuted_input_poly: pk.vk.domain.lagrange_to_coeff_st(l.0),
-                                permuted_table_poly: pk.vk.domain.lagrange_to_coeff_st(l.1),
-                                product_poly,
-                            },
-                        )
+                let timer = start_timer!(|| "lookups msm and fft");
+                let (lookups_z_commitments, lookups): (Vec<Vec<_>>, Vec<Vec<_>>) = lookups
+                    .into_iter()
+                    .map(|lookups| -> Result<(Vec<_>, Vec<_>), Error> {
+                        Ok(lookups
+                            .into_par_iter()
+                            .map(|l| -> Result<_, Error> {
+                                let (product_poly, c) = params.commit_lagrange_and_ifft(
+                                    l.2,
+                                    &pk.vk.domain.get_omega_inv(),
+                                    &pk.vk.domain.ifft_divisor,
+                                );
+                                let c = c.to_affine();
+                                tracker.step()?;
+                                Ok((
+                                    c,
+                                    lookup::prover::Committed {
+                                        permuted_input_poly: pk
+                                            .vk
+                                            .domain
+                                            .lagrange_to_coeff_st(l.0),
+                                        permuted_table_poly: pk
+                                            .vk
+                                            .domain
+                                            .lagrange_to_coeff_st(l.1),
+                                        product_poly,
+                                    },
+                                ))
+                            })
+                            .collect::<Result<Vec<_>, Error>>()?
+                            .into_iter()
+                            .unzip())
                     })
-                    .collect::<Vec<_>>()
+                    .collect::<Result<Vec<_>, _>>()?
                     .into_iter()
-                    .unzip()
-            })
-            .unzip();
-        end_timer!(timer);
+                    .unzip();
+                end_timer!(timer);
+
+                Ok((lookups_z_commitments, lookups))
+            },
+        );
+        let (lookups_z_commitments, lookups) = lookups?;
 
         let timer = start_timer!(|| "permutation commit");
-        let permutations = permutations
-            .join()
-            .expect("permutations thread failed unexpectedly");
+        let permutations = permutations?;
 
-        let permutations: Vec<_> = permutations
-            .into_iter()
-            .map(|permutations| {
-                let (c, sets): (Vec<_>, _) = permutations
-                    .into_par_iter()
-                    .map(|z| {
-                        let (permutation_product_poly, permutation_product_commitment_projective) =
-                            params.commit_lagrange_and_ifft(
+        let (permutations_commitments, permutations): (Vec<Vec<_>>, Vec<Vec<_>>) =
+            permutations
+                .into_iter()
+                .map(|permutations| -> Result<(Vec<_>, Vec<_>), Error> {
+                    Ok(permutations
+                        .into_par_iter()
+                        .map(|z| -> Result<_, Error> {
+                            tracker.poll()?;
+                            let (
+                                permutation_product_poly,
+                                permutation_product_commitment_projective,
+                            ) = params.commit_lagrange_and_ifft(
                                 z,
                                 &pk.vk.domain.get_omega_inv(),
                                 &pk.vk.domain.ifft_divisor,
                             );
-
-                        #[cfg(not(feature = "cuda"))]
-                        let permutation_product_coset =
-                            domain.coeff_to_extended(permutation_product_poly.clone());
-
-                        let permutation_product_commitment =
-                            permutation_product_commitment_projective.to_affine();
-
-                        (
-                            permutation_product_commitment,
-                            permutation::prover::CommittedSet {
+                            tracker.step()?;
+                            Ok((
+                                permutation_product_commitment_projective.to_affine(),
                                 permutation_product_poly,
-                                #[cfg(not(feature = "cuda"))]
-                                permutation_product_coset,
-                            },
-                        )
-                    })
-                    .unzip();
-                (c, permutation::prover::Committed { sets })
-            })
-            .collect();
-
-        for (cl, _) in permutations.iter() {
-            for c in cl {
-                transcript.write_point(*c).unwrap();
-            }
-        }
-
-        let permutations: Vec<_> = permutations.into_iter().map(|x| x.1).collect();
+                            ))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?
+                        .into_iter()
+                        .unzip())
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
         end_timer!(timer);
 
-        lookups_z_commitments
-            .into_iter()
-            .for_each(|lookups_z_commitments| {
-                lookups_z_commitments
-                    .into_iter()
-                    .for_each(|lookups_z_commitment| {
-                        transcript.write_point(lookups_z_commitment).unwrap()
-                    })
-            });
+        let timer = start_timer!(|| "vanishing commit");
+        // Commit to the vanishing argument's random polynomial for blinding h(x_3)
+        let (vanishing, vanishing_commitment) = vanishing::Argument::commit(
+            params,
+            domain,
+            round_rng(&seed, ROUND_PRODUCTS, 2 * num_proofs),
+        )?;
+        tracker.step()?;
+        end_timer!(timer);
 
-        (lookups, permutations)
-    });
+        state.lookups = LookupState::Committed(lookups);
+        state.permutations = permutations;
+        state.vanishing = Some(vanishing.random_poly);
+        state.commitments.push(
+            permutations_commitments
+                .into_iter()
+                .flatten()
+                .chain(lookups_z_commitments.into_iter().flatten())
+                .chain(Some(vanishing_commitment))
+                .collect(),
+        );
+        on_checkpoint(&state)?;
+    }
 
-    let timer = start_timer!(|| "vanishing commit");
-    // Commit to the vanishing argument's random polynomial for blinding h(x_3)
-    let vanishing = vanishing::Argument::commit(params, domain, rng, transcript)?;
+    for commitment in state.commitments[ROUND_PRODUCTS].iter() {
+        transcript.write_point(*commitment)?;
+    }
 
     // Obtain challenge for keeping all separate gates linearly independent
     let y: ChallengeY<_> = transcript.squeeze_challenge_scalar();
 
-    end_timer!(timer);
+    let Checkpoint {
+        advice,
+        lookups,
+        permutations,
+        vanishing,
+        ..
+    } = state;
+    let lookups = match lookups {
+        LookupState::Committed(lookups) => lookups,
+        _ => return Err(Error::InvalidCheckpoint),
+    };
+    let vanishing = vanishing::prover::Committed {
+        random_poly: vanishing.ok_or(Error::InvalidCheckpoint)?,
+    };
+
+    // Under a memory budget, the extended cosets are only cached if they fit
+    // together with everything else that is held while `evaluate_h` runs, and
+    // the quotient polynomial is otherwise evaluated by coset.
+    #[cfg(not(feature = "cuda"))]
+    let memory_budget = control.memory_budget();
+    #[cfg(not(feature = "cuda"))]
+    let cosets_fit = memory_budget.map_or(true, |memory_budget| {
+        let scalar_size = std::mem::size_of::<C::Scalar>();
+        let poly_size = params.n as usize * scalar_size;
+        let coset_size = domain.extended_len() * scalar_size;
+        let num_columns = meta.num_advice_columns + meta.num_instance_columns;
+        let num_lookups = num_proofs * meta.lookups.len();
+        let num_sets: usize = permutations.iter().map(|sets| sets.len()).sum();
+
+        // The fixed and permutation polynomials of the proving key, in Lagrange
+        // and coefficient form and as cosets, and the cosets of l_0, l_last
+        // and l_active_row.
+        let proving_key = (pk.fixed_values.len() + pk.fixed_polys.len()) * poly_size
+            + 2 * pk.permutation.polys.len() * poly_size
+            + (pk.fixed_cosets.len() + pk.permutation.cosets.len() + 3) * coset_size;
+        // The instance values, and the coefficient forms of the columns and of
+        // the lookup, permutation product and vanishing random polynomials.
+        let polys = (num_proofs * (num_columns + meta.num_instance_columns)
+            + 3 * num_lookups
+            + num_sets
+            + 1)
+            * poly_size;
+        // The cosets of the columns and of the permutation products.
+        let cosets = (num_proofs * num_columns + num_sets) * coset_size;
+        // The values and lookup tables of `evaluate_h`, and the cosets of the
+        // lookup it evaluates.
+        let buffers = (meta.lookups.len() + 4) * coset_size;
+
+        memory_budget.fits(proving_key + polys + cosets + buffers)
+    });
+    #[cfg(not(feature = "cuda"))]
+    let by_coset = match control.quotient_evaluation() {
+        Some(QuotientEvaluation::ByCoset) => true,
+        Some(QuotientEvaluation::Extended) => false,
+        None => !cosets_fit,
+    };
+    #[cfg(not(feature = "cuda"))]
+    let cache_cosets = cosets_fit && !by_coset;
+
+    let permutations: Vec<_> = permutations
+        .into_iter()
+        .map(|sets| permutation::prover::Committed {
+            sets: sets
+                .into_iter()
+                .map(
+                    |permutation_product_poly| permutation::prover::CommittedSet {
+                        permutation_product_poly,
+                    },
+                )
+                .collect(),
+        })
+        .collect();
+
     let timer = start_timer!(|| "h_poly");
+    #[cfg(feature = "cuda")]
+    let steps = instance.len() * (meta.num_advice_columns + 2 + meta.lookups.len());
+    #[cfg(not(feature = "cuda"))]
+    let steps = if by_coset {
+        instance.len() * meta.num_advice_columns + (domain.extended_len() >> domain.k())
+    } else {
+        instance.len() * (meta.num_advice_columns + 2 + meta.lookups.len())
+    };
+    let tracker = PhaseTracker::start(control, ProverPhase::Quotient, steps)?;
     // Evaluate the h(X) polynomial
 
     let advice = advice
         .into_iter()
-        .map(|advice| {
+        .map(|advice| -> Result<_, Error> {
             let timer = start_timer!(|| "lagrange_to_coeff_st");
             let advice_polys: Vec<_> = advice
                 .into_par_iter()
-                .map(|poly| domain.lagrange_to_coeff_st(poly))
-                .collect();
+                .map(|poly| -> Result<_, Error> {
+                    let poly = domain.lagrange_to_coeff_st(poly);
+                    tracker.step()?;
+                    Ok(poly)
+                })
+                .collect::<Result<_, Error>>()?;
             end_timer!(timer);
 
-            #[cfg(not(feature = "cuda"))]
-            let advice_cosets: Vec<_> = advice_polys
-                .iter()
-                .map(|poly| domain.coeff_to_extended(poly.clone()))
-                .collect();
-
-            AdviceSingle::<C> {
-                advice_polys,
-                #[cfg(not(feature = "cuda"))]
-                advice_cosets,
-            }
+            Ok(AdviceSingle::<C> { advice_polys })
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, _>>()?;
 
     #[cfg(feature = "cuda")]
     let h_poly = pk.ev.evaluate_h(
@@ -665,38 +1055,304 @@ pub fn create_proof<
         *theta,
         &lookups,
         &permutations,
-    );
+        &tracker,
+    )?;
 
+    // The cosets of the advice, instance and permutation product polynomials
+    // of each circuit instance, if they are cached.
     #[cfg(not(feature = "cuda"))]
-    let h_poly = pk.ev.evaluate_h(
-        pk,
-        advice.iter().map(|a| &a.advice_cosets).collect(),
-        instance.iter().map(|i| &i.instance_cosets).collect(),
-        *y,
-        *beta,
-        *gamma,
-        *theta,
-        &lookups,
-        &permutations,
-    );
-
+    let cached_cosets: Vec<_> = if cache_cosets {
+        let to_extended =
+            |poly: &Polynomial<C::Scalar, Coeff>| domain.coeff_to_extended(poly.clone());
+        advice
+            .iter()
+            .zip(instance.iter())
+            .zip(permutations.iter())
+            .map(|((advice, instance), permutation)| {
+                (
+                    advice
+                        .advice_polys
+                        .iter()
+                        .map(to_extended)
+                        .collect::<Vec<_>>(),
+                    instance
+                        .instance_polys
+                        .iter()
+                        .map(to_extended)
+                        .collect::<Vec<_>>(),
+                    permutation
+                        .sets
+                        .iter()
+                        .map(|set| to_extended(&set.permutation_product_poly))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+
+    // If the cosets do not fit, the coefficient forms are not needed again
+    // until the evaluation phase and are spilled.
+    #[cfg(not(feature = "cuda"))]
+    let (mut instance, mut advice, mut lookups, mut permutations) =
+        (instance, advice, lookups, permutations);
+    #[cfg(not(feature = "cuda"))]
+    let spilled = match memory_budget.filter(|_| !cosets_fit) {
+        Some(memory_budget) => {
+            let spill = |poly: &mut Polynomial<C::Scalar, Coeff>| {
+                memory_budget.spill(std::mem::replace(poly, Polynomial::new(vec![])))
+            };
+            Some(
+                instance
+                    .iter_mut()
+                    .zip(advice.iter_mut())
+                    .zip(lookups.iter_mut())
+                    .zip(permutations.iter_mut())
+                    .map(
+                        |(((instance, advice), lookups), permutation)| -> Result<_, Error> {
+                            Ok(SpilledSingle {
+                                advice_polys: advice
+                                    .advice_polys
+                                    .par_iter_mut()
+                                    .map(spill)
+                                    .collect::<Result<_, Error>>()?,
+                                instance_polys: instance
+                                    .instance_polys
+                                    .par_iter_mut()
+                                    .map(spill)
+                                    .collect::<Result<_, Error>>()?,
+                                lookup_polys: lookups
+                                    .par_iter_mut()
+                                    .map(|lookup| -> Result<_, Error> {
+                                        Ok([
+                                            spill(&mut lookup.permuted_input_poly)?,
+                                            spill(&mut lookup.permuted_table_poly)?,
+                                            spill(&mut lookup.product_poly)?,
+                                        ])
+                                    })
+                                    .collect::<Result<_, Error>>()?,
+                                permutation_product_polys: permutation
+                                    .sets
+                                    .par_iter_mut()
+                                    .map(|set| spill(&mut set.permutation_product_poly))
+                                    .collect::<Result<_, Error>>()?,
+                            })
+                        },
+                    )
+                    .collect::<Result<Vec<_>, Error>>()?,
+            )
+        }
+        None => None,
+    };
+
+    // The coefficient forms of the polynomials of a circuit instance, mapped
+    // from their scratch files if they have been spilled.
+    #[cfg(not(feature = "cuda"))]
+    let coefficients = |circuit_idx: usize| -> Result<CircuitColumns<C::Scalar>, Error> {
+        let spilled = match &spilled {
+            Some(spilled) => &spilled[circuit_idx],
+            None => {
+                return Ok(CircuitColumns {
+                    advice: advice[circuit_idx]
+                        .advice_polys
+                        .iter()
+                        .map(|poly| Values::Borrowed(&poly[..]))
+                        .collect(),
+                    instance: instance[circuit_idx]
+                        .instance_polys
+                        .iter()
+                        .map(|poly| Values::Borrowed(&poly[..]))
+                        .collect(),
+                    permutation_products: permutations[circuit_idx]
+                        .sets
+                        .iter()
+                        .map(|set| Values::Borrowed(&set.permutation_product_poly[..]))
+                        .collect(),
+                    lookups: lookups[circuit_idx]
+                        .iter()
+                        .map(|lookup| LookupColumns {
+                            permuted_input: Values::Borrowed(&lookup.permuted_input_poly[..]),
+                            permuted_table: Values::Borrowed(&lookup.permuted_table_poly[..]),
+                            product: Values::Borrowed(&lookup.product_poly[..]),
+                        })
+                        .collect(),
+                })
+            }
+        };
+        let mapped = |poly: &Spilled<C::Scalar, Coeff>| poly.map().map(Values::Mapped);
+        Ok(CircuitColumns {
+            advice: spilled
+                .advice_polys
+                .iter()
+                .map(mapped)
+                .collect::<Result<_, _>>()?,
+            instance: spilled
+                .instance_polys
+                .iter()
+                .map(mapped)
+                .collect::<Result<_, _>>()?,
+            permutation_products: spilled
+                .permutation_product_polys
+                .iter()
+                .map(mapped)
+                .collect::<Result<_, _>>()?,
+            lookups: spilled
+                .lookup_polys
+                .iter()
+                .map(
+                    |[permuted_input, permuted_table, product]| -> Result<_, Error> {
+                        Ok(LookupColumns {
+                            permuted_input: mapped(permuted_input)?,
+                            permuted_table: mapped(permuted_table)?,
+                            product: mapped(product)?,
+                        })
+                    },
+                )
+                .collect::<Result<_, Error>>()?,
+        })
+    };
+
+    #[cfg(not(feature = "cuda"))]
+    let h_poly = if by_coset {
+        pk.ev.evaluate_h_by_coset(
+            pk,
+            num_proofs,
+            coefficients,
+            *y,
+            *beta,
+            *gamma,
+            *theta,
+            &tracker,
+        )?
+    } else {
+        pk.ev.evaluate_h(
+            pk,
+            num_proofs,
+            |circuit_idx| {
+                if cache_cosets {
+                    let (advice, instance, permutation_products) = &cached_cosets[circuit_idx];
+                    return Ok(CircuitColumns {
+                        advice: advice
+                            .iter()
+                            .map(|coset| Values::Borrowed(&coset[..]))
+                            .collect(),
+                        instance: instance
+                            .iter()
+                            .map(|coset| Values::Borrowed(&coset[..]))
+                            .collect(),
+                        permutation_products: permutation_products
+                            .iter()
+                            .map(|coset| Values::Borrowed(&coset[..]))
+                            .collect(),
+                        lookups: coefficients(circuit_idx)?.lookups,
+                    });
+                }
+
+                // Cosets are only recomputed under a memory budget, in which
+                // case they are computed one column at a time and spilled as
+                // soon as they are.
+                let memory_budget = memory_budget.unwrap();
+                let to_extended = |values: &Values<C::Scalar>| {
+                    let coset = domain.coeff_to_extended(domain.coeff_from_vec(values.to_vec()));
+                    memory_budget
+                        .spill(coset)
+                        .and_then(|coset| coset.map())
+                        .map(Values::Mapped)
+                };
+                let columns = coefficients(circuit_idx)?;
+                Ok(CircuitColumns {
+                    advice: columns
+                        .advice
+                        .iter()
+                        .map(to_extended)
+                        .collect::<Result<_, _>>()?,
+                    instance: columns
+                        .instance
+                        .iter()
+                        .map(to_extended)
+                        .collect::<Result<_, _>>()?,
+                    permutation_products: columns
+                        .permutation_products
+                        .iter()
+                        .map(to_extended)
+                        .collect::<Result<_, _>>()?,
+                    lookups: columns.lookups,
+                })
+            },
+            *y,
+            *beta,
+            *gamma,
+            *theta,
+            &tracker,
+        )?
+    };
+    #[cfg(not(feature = "cuda"))]
+    drop(cached_cosets);
+
     end_timer!(timer);
     let timer = start_timer!(|| "vanishing construct");
+    let tracker = PhaseTracker::start(control, ProverPhase::Vanishing, 1)?;
     // Construct the vanishing argument's h(X) commitments
     let vanishing = vanishing.construct(params, domain, h_poly, transcript)?;
+    tracker.step()?;
+
+    #[cfg(not(feature = "cuda"))]
+    if let Some(spilled) = spilled {
+        for ((((instance, advice), lookups), permutation), spilled) in instance
+            .iter_mut()
+            .zip(advice.iter_mut())
+            .zip(lookups.iter_mut())
+            .zip(permutations.iter_mut())
+            .zip(spilled.iter())
+        {
+            advice.advice_polys = spilled
+                .advice_polys
+                .par_iter()
+                .map(|poly| poly.load())
+                .collect::<Result<_, Error>>()?;
+            instance.instance_polys = spilled
+                .instance_polys
+                .par_iter()
+                .map(|poly| poly.load())
+                .collect::<Result<_, Error>>()?;
+            for (lookup, [permuted_input, permuted_table, product]) in
+                lookups.iter_mut().zip(spilled.lookup_polys.iter())
+            {
+                lookup.permuted_input_poly = permuted_input.load()?;
+                lookup.permuted_table_poly = permuted_table.load()?;
+                lookup.product_poly = product.load()?;
+            }
+            for (set, poly) in permutation
+                .sets
+                .iter_mut()
+                .zip(spilled.permutation_product_polys.iter())
+            {
+                set.permutation_product_poly = poly.load()?;
+            }
+        }
+    }
 
     let x: ChallengeX<_> = transcript.squeeze_challenge_scalar();
     let xn = x.pow(&[params.n as u64, 0, 0, 0]);
     end_timer!(timer);
 
     let timer = start_timer!(|| "eval poly");
+    let tracker = PhaseTracker::start(control, ProverPhase::Evaluation, 4)?;
+
+    // The instance columns are neither evaluated nor opened if the verifier
+    // evaluates them itself.
+    let instance_queries: &[(Column<Instance>, Rotation)] = match meta.instance_evaluation {
+        InstanceEvaluation::Committed => &meta.instance_queries,
+        InstanceEvaluation::Direct => &[],
+    };
 
     let mut inputs = vec![];
 
     // Compute and hash instance evals for each circuit instance
     for instance in instance.iter() {
         // Evaluate polynomials at omega^i x
-        meta.instance_queries.iter().for_each(|&(column, at)| {
+        instance_queries.iter().for_each(|&(column, at)| {
             inputs.push((
                 &instance.instance_polys[column.index()],
                 domain.rotate_omega(*x, at),
@@ -727,10 +1383,12 @@ pub fn create_proof<
     {
         transcript.write_scalar(eval)?;
     }
+    tracker.step()?;
 
     end_timer!(timer);
     let timer = start_timer!(|| "eval poly vanishing");
     let vanishing = vanishing.evaluate(x, xn, domain, transcript)?;
+    tracker.step()?;
 
     end_timer!(timer);
     let timer = start_timer!(|| "eval poly permutation");
@@ -742,6 +1400,7 @@ pub fn create_proof<
         .into_iter()
         .map(|permutation| -> Result<_, _> { permutation.construct().evaluate(pk, x, transcript) })
         .collect::<Result<Vec<_>, _>>()?;
+    tracker.step()?;
 
     end_timer!(timer);
 
@@ -761,9 +1420,11 @@ pub fn create_proof<
                 .for_each(|eval| transcript.write_scalar(eval).unwrap())
         })
     });
+    tracker.step()?;
     end_timer!(timer);
 
     let timer = start_timer!(|| "multi open");
+    let tracker = PhaseTracker::start(control, ProverPhase::Opening, 1)?;
     let instances = instance
         .iter()
         .zip(advice.iter())
@@ -772,9 +1433,7 @@ pub fn create_proof<
         .flat_map(|(((instance, advice), permutation), lookups)| {
             iter::empty()
                 .chain(
-                    pk.vk
-                        .cs
-                        .instance_queries
+                    instance_queries
                         .iter()
                         .map(move |&(column, at)| ProverQuery {
                             point: domain.rotate_omega(*x, at),
@@ -813,11 +1472,13 @@ pub fn create_proof<
 
     let res = multiopen::create_proof(params, transcript, instances).map_err(|_| Error::Opening);
     end_timer!(timer);
-
-    res
+    res?;
+    tracker.step()
 }
 
-/// generate and write witness to files
+/// Synthesizes `circuit` and appends its witness, together with its
+/// `instances`, to the witness bundle `fd`. All the circuit instances of a
+/// bundle are proven together by [`create_proof_from_witness`].
 pub fn create_witness<
     C: CurveAffine,
     ConcreteCircuit: Circuit<C::Scalar>,
@@ -827,14 +1488,61 @@ pub fn create_witness<
     circuit: &ConcreteCircuit,
     instances: &[&[C::Scalar]],
     fd: &mut File,
+) -> Result<(), Error> {
+    create_witness_with_encoding(params, pk, circuit, instances, WitnessEncoding::Raw, fd)
+}
+
+/// Like [`create_witness`], but stores the advice columns with `encoding`.
+/// [`WitnessEncoding::Compact`] is much smaller for columns that are mostly
+/// zeros or small values, at the cost of decoding them when proving.
+pub fn create_witness_with_encoding<
+    C: CurveAffine,
+    ConcreteCircuit: Circuit<C::Scalar>,
+>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    circuit: &ConcreteCircuit,
+    instances: &[&[C::Scalar]],
+    encoding: WitnessEncoding,
+    fd: &mut File,
 ) -> Result<(), Error> {
     let meta = &pk.vk.cs;
     let unusable_rows_start = params.n as usize - (meta.blinding_factors() + 1);
-    AssignWitnessCollection::store_witness(params, pk, instances, unusable_rows_start, circuit, fd)?;
+    fd.seek(SeekFrom::End(0))?;
+    AssignWitnessCollection::store_witness(
+        params,
+        pk,
+        instances,
+        unusable_rows_start,
+        circuit,
+        encoding,
+        fd,
+    )?;
     Ok(())
 }
 
-/// create_proof based on vkey and witness
+/// Returns the instance values of each circuit instance in the witness
+/// bundle `fd`, in the order they were added by [`create_witness`]. These
+/// are the instances to verify a proof created from the bundle with.
+pub fn witness_instances<C: CurveAffine>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    fd: &mut File,
+) -> Result<Vec<Vec<Vec<C::Scalar>>>, Error> {
+    let len = fd.metadata()?.len();
+    fd.seek(SeekFrom::Start(0))?;
+    let mut instances = vec![];
+    while fd.stream_position()? < len {
+        instances.push(AssignWitnessCollection::fetch_witness_instances(
+            params, pk, fd,
+        )?);
+    }
+    Ok(instances)
+}
+
+/// Creates a proof for all the circuit instances in the witness bundle `fd`,
+/// written by [`create_witness`], using the instance values stored with
+/// them.
 pub fn create_proof_from_witness<
     C: CurveAffine,
     E: EncodedChallenge<C>,
@@ -843,66 +1551,72 @@ pub fn create_proof_from_witness<
 >(
     params: &Params<C>,
     pk: &ProvingKey<C>,
-    instances: &[&[&[C::Scalar]]],
     mut rng: R,
     transcript: &mut T,
     fd: &mut File,
 ) -> Result<(), Error> {
     let meta = &pk.vk.cs;
-    let domain = &pk.vk.domain;
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+
+    let timer = start_timer!(|| "fetch witness");
+    let len = fd.metadata()?.len();
+    fd.seek(SeekFrom::Start(0))?;
+    let mut witnesses = vec![];
+    while fd.stream_position()? < len {
+        witnesses.push(AssignWitnessCollection::fetch_witness(params, pk, fd)?);
+    }
+    if witnesses.is_empty() {
+        return Err(Error::InvalidWitness("empty witness bundle".to_string()));
+    }
+    end_timer!(timer);
 
     let timer = start_timer!(|| "create single instances");
-    let instance = create_single_instances(params, pk, instances, transcript)?;
+    // The instances are taken out of the witnesses, which are consumed below,
+    // as the checkpoint is bound to them.
+    let witness_instances: Vec<Vec<Vec<C::Scalar>>> = witnesses
+        .iter_mut()
+        .map(|witness| std::mem::take(&mut witness.instances))
+        .collect();
+    let instances: Vec<Vec<&[C::Scalar]>> = witness_instances
+        .iter()
+        .map(|columns| columns.iter().map(|values| &values[..]).collect())
+        .collect();
+    let instances: Vec<&[&[C::Scalar]]> = instances.iter().map(|columns| &columns[..]).collect();
+    let instance = create_single_instances(params, pk, &instances, transcript)?;
 
     end_timer!(timer);
     let timer = start_timer!(|| "advice");
-    struct AdviceSingle<C: CurveAffine> {
-        pub advice_polys: Vec<Polynomial<C::Scalar, Coeff>>,
-
-        #[cfg(not(feature = "cuda"))]
-        pub advice_cosets: Vec<Polynomial<C::Scalar, ExtendedLagrangeCoeff>>,
-    }
-
-    let get_scalar_bits = |x: C::Scalar| {
-        let repr = x.to_repr();
-        let max_scalar_repr_ref: &[u8] = repr.as_ref();
-        max_scalar_repr_ref
-            .iter()
-            .enumerate()
-            .fold(0, |acc, (idx, v)| {
-                if *v == 0 {
-                    acc
-                } else {
-                    idx * 8 + 8 - v.leading_zeros() as usize
-                }
-            })
-    };
-
-    let find_max_scalar_bits = |x: &Vec<C::Scalar>| {
-        get_scalar_bits(x.iter().fold(C::Scalar::zero(), |acc, x| acc.max(*x)))
-    };
-
-    let advice: Vec<Vec<Polynomial<C::Scalar, LagrangeCoeff>>> =
-        instances.iter()
-        .map(|_| -> Vec<Polynomial<C::Scalar, LagrangeCoeff>> {
+    let (advice, advice_commitments): (Vec<_>, Vec<_>) = witnesses
+        .into_iter()
+        .enumerate()
+        .map(|(circuit_idx, witness)| {
             let unusable_rows_start = params.n as usize - (meta.blinding_factors() + 1);
-
-            let mut advice = AssignWitnessCollection::fetch_witness(params, fd).expect("fetch witness should not fail");
+            let mut advice = witness.advice;
 
             let timer = start_timer!(|| "rng");
-            advice.par_iter_mut().for_each(|advice| {
-                for cell in &mut advice[unusable_rows_start..] {
-                    *cell = C::Scalar::from(u16::rand(&mut OsRng) as u64);
+            advice.par_iter_mut().enumerate().for_each(|(i, advice)| {
+                if !meta.is_exposed_advice(i) {
+                    let mut rng = round_rng(
+                        &seed,
+                        ROUND_ADVICE,
+                        circuit_idx * meta.num_advice_columns + i,
+                    );
+                    for cell in &mut advice[unusable_rows_start..] {
+                        *cell = C::Scalar::from(u16::rand(&mut rng) as u64);
+                    }
                 }
             });
             end_timer!(timer);
 
+            // The bit widths recorded in the witness do not account for the
+            // blinding factors, which have at most 16 bits.
             let timer = start_timer!(|| "commit_lagrange");
             let advice_commitments_projective: Vec<_> = advice
                 .par_iter()
-                .map(|advice| {
-                    let max_bits = find_max_scalar_bits(&advice.values);
-                    params.commit_lagrange_with_bound(advice, max_bits)
+                .zip(witness.max_bits.par_iter())
+                .map(|(advice, max_bits)| {
+                    params.commit_lagrange_with_bound(advice, (*max_bits).max(16))
                 })
                 .collect();
             end_timer!(timer);
@@ -914,395 +1628,152 @@ pub fn create_proof_from_witness<
             drop(advice_commitments_projective);
             end_timer!(timer);
 
-            for commitment in &advice_commitments {
-                transcript.write_point(*commitment).unwrap();
-            }
-
-            advice
+            (advice, advice_commitments)
         })
-        .collect::<Vec<_>>();
-
-    // Sample theta challenge for keeping lookup columns linearly independent
-    let theta: ChallengeTheta<_> = transcript.squeeze_challenge_scalar();
-
-    end_timer!(timer);
-    let timer = start_timer!(|| format!("lookups {}", pk.vk.cs.lookups.len()));
-    let (lookups, lookups_commitments): (Vec<Vec<lookup::prover::Permuted<C>>>, Vec<Vec<[C; 2]>>) =
-        instance
-            .iter()
-            .zip(advice.iter())
-            .map(|(instance, advice)| -> (Vec<_>, Vec<_>) {
-                pk.vk
-                    .cs
-                    .lookups
-                    .par_iter()
-                    .map(|lookup| {
-                        lookup
-                            .commit_permuted(
-                                pk,
-                                params,
-                                domain,
-                                theta,
-                                &advice,
-                                &pk.fixed_values,
-                                &instance.instance_values,
-                                &mut OsRng,
-                            )
-                            .unwrap()
-                    })
-                    .unzip()
-            })
-            .unzip();
+        .unzip();
 
-    lookups_commitments.into_iter().for_each(|x| {
-        x.iter().for_each(|x| {
-            transcript.write_point(x[0]).unwrap();
-            transcript.write_point(x[1]).unwrap();
-        })
-    });
     end_timer!(timer);
 
-    // Sample beta challenge
-    let beta: ChallengeBeta<_> = transcript.squeeze_challenge_scalar();
-    // Sample gamma challenge
-    let gamma: ChallengeGamma<_> = transcript.squeeze_challenge_scalar();
-
-    let (lookups, permutations) = std::thread::scope(|s| {
-        let permutations = s.spawn(|| {
-            // prepare permutation value.
-            instance
-                .iter()
-                .zip(advice.iter())
-                .map(|(instance, advice)| {
-                    pk.vk.cs.permutation.commit(
-                        params,
-                        pk,
-                        &pk.permutation,
-                        &advice,
-                        &pk.fixed_values,
-                        &instance.instance_values,
-                        beta,
-                        gamma.clone(),
-                        &mut OsRng,
-                    )
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .unwrap()
-        });
-
-        let timer = start_timer!(|| "lookups commit product");
-        let lookups: Vec<Vec<_>> = lookups
-            .into_iter()
-            .map(|lookups| {
-                lookups
-                    .into_par_iter()
-                    .map(|lookup| lookup.commit_product(pk, params, beta, gamma).unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        end_timer!(timer);
-
-        let timer = start_timer!(|| "lookups add blinding value");
-        let lookups: Vec<Vec<_>> = lookups
-            .into_iter()
-            .map(|lookups| {
-                lookups
-                    .into_iter()
-                    .map(|(l0, l1, mut z)| {
-                        for _ in 0..pk.vk.cs.blinding_factors() {
-                            z.push(C::Scalar::random(&mut rng))
-                        }
-                        (l0, l1, pk.vk.domain.lagrange_from_vec(z))
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<Vec<_>>>();
-        end_timer!(timer);
-
-        let timer = start_timer!(|| "lookups msm and fft");
-        let (lookups_z_commitments, lookups): (Vec<Vec<_>>, Vec<Vec<_>>) = lookups
-            .into_iter()
-            .map(|lookups| {
-                lookups
-                    .into_par_iter()
-                    .map(|l| {
-                        let (product_poly, c) = params.commit_lagrange_and_ifft(
-                            l.2,
-                            &pk.vk.domain.get_omega_inv(),
-                            &pk.vk.domain.ifft_divisor,
-                        );
-                        let c = c.to_affine();
-                        (
-                            c,
-                            lookup::prover::Committed {
-                                permuted_input_poly: pk.vk.domain.lagrange_to_coeff_st(l.0),
-                                permuted_table_poly: pk.vk.domain.lagrange_to_coeff_st(l.1),
-                                product_poly,
-                            },
-                        )
-                    })
-                    .collect::<Vec<_>>()
-                    .into_iter()
-                    .unzip()
-            })
-            .unzip();
-        end_timer!(timer);
-
-        let timer = start_timer!(|| "permutation commit");
-        let permutations = permutations
-            .join()
-            .expect("permutations thread failed unexpectedly");
-
-        let permutations: Vec<_> = permutations
-            .into_iter()
-            .map(|permutations| {
-                let (c, sets): (Vec<_>, _) = permutations
-                    .into_par_iter()
-                    .map(|z| {
-                        let (permutation_product_poly, permutation_product_commitment_projective) =
-                            params.commit_lagrange_and_ifft(
-                                z,
-                                &pk.vk.domain.get_omega_inv(),
-                                &pk.vk.domain.ifft_divisor,
-                            );
-
-                        #[cfg(not(feature = "cuda"))]
-                        let permutation_product_coset =
-                            domain.coeff_to_extended(permutation_product_poly.clone());
-
-                        let permutation_product_commitment =
-                            permutation_product_commitment_projective.to_affine();
-
-                        (
-                            permutation_product_commitment,
-                            permutation::prover::CommittedSet {
-                                permutation_product_poly,
-                                #[cfg(not(feature = "cuda"))]
-                                permutation_product_coset,
-                            },
-                        )
-                    })
-                    .unzip();
-                (c, permutation::prover::Committed { sets })
-            })
-            .collect();
+    let checkpoint = Checkpoint::new(seed, &instances, advice, advice_commitments.concat());
+    create_proof_from_checkpoint(
+        params,
+        pk,
+        instance,
+        checkpoint,
+        transcript,
+        &NoControl,
+        &mut |_| Ok(()),
+    )
+}
 
-        for (cl, _) in permutations.iter() {
-            for c in cl {
-                transcript.write_point(*c).unwrap();
-            }
+/// Creates a proof from advice columns computed without synthesizing a
+/// circuit. For each circuit instance, `advice` holds the values of each
+/// advice column of the circuit from its first row; the columns must fit in
+/// the usable rows, and are padded with zeros.
+///
+/// The columns are not checked against the constraints of the circuit, so
+/// an unsatisfying witness only yields a proof that does not verify. See
+/// [`create_proof_from_columns_checked`].
+pub fn create_proof_from_columns<
+    C: CurveAffine,
+    E: EncodedChallenge<C>,
+    R: RngCore,
+    T: TranscriptWrite<C, E>,
+>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    advice: Vec<Vec<Vec<C::Scalar>>>,
+    instances: &[&[&[C::Scalar]]],
+    mut rng: R,
+    transcript: &mut T,
+) -> Result<(), Error> {
+    let meta = &pk.vk.cs;
+    let unusable_rows_start = params.n as usize - (meta.blinding_factors() + 1);
+    if advice.len() != instances.len() {
+        return Err(Error::InvalidInstances);
+    }
+    for columns in advice.iter() {
+        if columns.len() != meta.num_advice_columns {
+            return Err(Error::InvalidAdvice);
         }
+        if columns.iter().any(|column| column.len() > unusable_rows_start) {
+            return Err(Error::not_enough_rows_available(params.k));
+        }
+    }
 
-        let permutations: Vec<_> = permutations.into_iter().map(|x| x.1).collect();
-        end_timer!(timer);
-
-        lookups_z_commitments
-            .into_iter()
-            .for_each(|lookups_z_commitments| {
-                lookups_z_commitments
-                    .into_iter()
-                    .for_each(|lookups_z_commitment| {
-                        transcript.write_point(lookups_z_commitment).unwrap()
-                    })
-            });
-
-        (lookups, permutations)
-    });
-
-    let timer = start_timer!(|| "vanishing commit");
-    // Commit to the vanishing argument's random polynomial for blinding h(x_3)
-    let vanishing = vanishing::Argument::commit(params, domain, rng, transcript)?;
-
-    // Obtain challenge for keeping all separate gates linearly independent
-    let y: ChallengeY<_> = transcript.squeeze_challenge_scalar();
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
 
+    let timer = start_timer!(|| "create single instances");
+    let instance = create_single_instances(params, pk, instances, transcript)?;
     end_timer!(timer);
-    let timer = start_timer!(|| "h_poly");
-    // Evaluate the h(X) polynomial
 
-    let advice = advice
+    let timer = start_timer!(|| "advice");
+    let (advice, advice_commitments): (Vec<_>, Vec<_>) = advice
         .into_iter()
-        .map(|advice| {
-            let timer = start_timer!(|| "lagrange_to_coeff_st");
-            let advice_polys: Vec<_> = advice
+        .enumerate()
+        .map(|(circuit_idx, columns)| {
+            let timer = start_timer!(|| "rng");
+            let advice: Vec<Polynomial<C::Scalar, LagrangeCoeff>> = columns
                 .into_par_iter()
-                .map(|poly| domain.lagrange_to_coeff_st(poly))
+                .enumerate()
+                .map(|(i, mut column)| {
+                    column.resize(params.n as usize, C::Scalar::zero());
+                    if !meta.is_exposed_advice(i) {
+                        let mut rng = round_rng(
+                            &seed,
+                            ROUND_ADVICE,
+                            circuit_idx * meta.num_advice_columns + i,
+                        );
+                        for cell in &mut column[unusable_rows_start..] {
+                            *cell = C::Scalar::from(u16::rand(&mut rng) as u64);
+                        }
+                    }
+                    Polynomial::new(column)
+                })
                 .collect();
             end_timer!(timer);
 
-            #[cfg(not(feature = "cuda"))]
-            let advice_cosets: Vec<_> = advice_polys
-                .iter()
-                .map(|poly| domain.coeff_to_extended(poly.clone()))
+            let timer = start_timer!(|| "commit_lagrange");
+            let advice_commitments_projective: Vec<_> = advice
+                .par_iter()
+                .map(|advice| {
+                    let max_bits = find_max_scalar_bits(advice);
+                    params.commit_lagrange_with_bound(advice, max_bits)
+                })
                 .collect();
+            end_timer!(timer);
 
-            AdviceSingle::<C> {
-                advice_polys,
-                #[cfg(not(feature = "cuda"))]
-                advice_cosets,
-            }
-        })
-        .collect::<Vec<_>>();
-
-    #[cfg(feature = "cuda")]
-    let h_poly = pk.ev.evaluate_h(
-        pk,
-        advice.iter().map(|a| &a.advice_polys).collect(),
-        instance.iter().map(|i| &i.instance_polys).collect(),
-        *y,
-        *beta,
-        *gamma,
-        *theta,
-        &lookups,
-        &permutations,
-    );
-
-    #[cfg(not(feature = "cuda"))]
-    let h_poly = pk.ev.evaluate_h(
-        pk,
-        advice.iter().map(|a| &a.advice_cosets).collect(),
-        instance.iter().map(|i| &i.instance_cosets).collect(),
-        *y,
-        *beta,
-        *gamma,
-        *theta,
-        &lookups,
-        &permutations,
-    );
-
-    end_timer!(timer);
-    let timer = start_timer!(|| "vanishing construct");
-    // Construct the vanishing argument's h(X) commitments
-    let vanishing = vanishing.construct(params, domain, h_poly, transcript)?;
+            let mut advice_commitments = vec![C::identity(); advice_commitments_projective.len()];
+            C::Curve::batch_normalize(&advice_commitments_projective, &mut advice_commitments);
 
-    let x: ChallengeX<_> = transcript.squeeze_challenge_scalar();
-    let xn = x.pow(&[params.n as u64, 0, 0, 0]);
+            (advice, advice_commitments)
+        })
+        .unzip();
     end_timer!(timer);
 
-    let timer = start_timer!(|| "eval poly");
-
-    let mut inputs = vec![];
-
-    // Compute and hash instance evals for each circuit instance
-    for instance in instance.iter() {
-        // Evaluate polynomials at omega^i x
-        meta.instance_queries.iter().for_each(|&(column, at)| {
-            inputs.push((
-                &instance.instance_polys[column.index()],
-                domain.rotate_omega(*x, at),
-            ))
-        })
-    }
+    let checkpoint = Checkpoint::new(seed, instances, advice, advice_commitments.concat());
+    create_proof_from_checkpoint(
+        params,
+        pk,
+        instance,
+        checkpoint,
+        transcript,
+        &NoControl,
+        &mut |_| Ok(()),
+    )
+}
 
-    // Compute and hash advice evals for each circuit instance
-    for advice in advice.iter() {
-        // Evaluate polynomials at omega^i x
-        meta.advice_queries.iter().for_each(|&(column, at)| {
-            inputs.push((
-                &advice.advice_polys[column.index()],
-                domain.rotate_omega(*x, at),
-            ))
-        })
+/// Like [`create_proof_from_columns`], but first checks the columns of each
+/// circuit instance against the gates, lookups and copy constraints of the
+/// circuit, and returns [`Error::ConstraintSystemFailure`] if they are not
+/// satisfied. [`MockProver::from_columns`] reports the failures in detail.
+pub fn create_proof_from_columns_checked<
+    C: CurveAffine,
+    E: EncodedChallenge<C>,
+    R: RngCore,
+    T: TranscriptWrite<C, E>,
+>(
+    params: &Params<C>,
+    pk: &ProvingKey<C>,
+    advice: Vec<Vec<Vec<C::Scalar>>>,
+    instances: &[&[&[C::Scalar]]],
+    rng: R,
+    transcript: &mut T,
+) -> Result<(), Error> {
+    if advice.len() != instances.len() {
+        return Err(Error::InvalidInstances);
     }
-
-    // Compute and hash fixed evals (shared across all circuit instances)
-    meta.fixed_queries.iter().for_each(|&(column, at)| {
-        inputs.push((&pk.fixed_polys[column.index()], domain.rotate_omega(*x, at)))
-    });
-
-    for eval in inputs
-        .into_par_iter()
-        .map(|(a, b)| eval_polynomial_st(a, b))
-        .collect::<Vec<_>>()
-    {
-        transcript.write_scalar(eval)?;
+    for (columns, instance) in advice.iter().zip(instances.iter()) {
+        let prover = MockProver::from_columns(
+            pk,
+            columns.clone(),
+            instance.iter().map(|values| values.to_vec()).collect(),
+        )?;
+        if prover.verify().is_err() {
+            return Err(Error::ConstraintSystemFailure);
+        }
     }
 
-    end_timer!(timer);
-    let timer = start_timer!(|| "eval poly vanishing");
-    let vanishing = vanishing.evaluate(x, xn, domain, transcript)?;
-
-    end_timer!(timer);
-    let timer = start_timer!(|| "eval poly permutation");
-    // Evaluate common permutation data
-    pk.permutation.evaluate(x, transcript)?;
-
-    // Evaluate the permutations, if any, at omega^i x.
-    let permutations: Vec<permutation::prover::Evaluated<C>> = permutations
-        .into_iter()
-        .map(|permutation| -> Result<_, _> { permutation.construct().evaluate(pk, x, transcript) })
-        .collect::<Result<Vec<_>, _>>()?;
-
-    end_timer!(timer);
-
-    let timer = start_timer!(|| "eval poly lookups");
-    // Evaluate the lookups, if any, at omega^i x.
-    let (lookups, evals): (
-        Vec<Vec<lookup::prover::Evaluated<C>>>,
-        Vec<Vec<Vec<C::ScalarExt>>>,
-    ) = lookups
-        .into_iter()
-        .map(|lookups| lookups.into_par_iter().map(|p| p.evaluate(pk, x)).unzip())
-        .unzip();
-    evals.into_iter().for_each(|evals| {
-        evals.into_iter().for_each(|evals| {
-            evals
-                .into_iter()
-                .for_each(|eval| transcript.write_scalar(eval).unwrap())
-        })
-    });
-    end_timer!(timer);
-
-    let timer = start_timer!(|| "multi open");
-    let instances = instance
-        .iter()
-        .zip(advice.iter())
-        .zip(permutations.iter())
-        .zip(lookups.iter())
-        .flat_map(|(((instance, advice), permutation), lookups)| {
-            iter::empty()
-                .chain(
-                    pk.vk
-                        .cs
-                        .instance_queries
-                        .iter()
-                        .map(move |&(column, at)| ProverQuery {
-                            point: domain.rotate_omega(*x, at),
-                            rotation: at,
-                            poly: &instance.instance_polys[column.index()],
-                        }),
-                )
-                .chain(
-                    pk.vk
-                        .cs
-                        .advice_queries
-                        .iter()
-                        .map(move |&(column, at)| ProverQuery {
-                            point: domain.rotate_omega(*x, at),
-                            rotation: at,
-                            poly: &advice.advice_polys[column.index()],
-                        }),
-                )
-                .chain(permutation.open(pk, x))
-                .chain(lookups.iter().flat_map(move |p| p.open(pk, x)).into_iter())
-        })
-        .chain(
-            pk.vk
-                .cs
-                .fixed_queries
-                .iter()
-                .map(|&(column, at)| ProverQuery {
-                    point: domain.rotate_omega(*x, at),
-                    rotation: at,
-                    poly: &pk.fixed_polys[column.index()],
-                }),
-        )
-        .chain(pk.permutation.open(x))
-        // We query the h(X) polynomial at x
-        .chain(vanishing.open(x));
-
-    let res = multiopen::create_proof(params, transcript, instances).map_err(|_| Error::Opening);
-    end_timer!(timer);
-    res
+    create_proof_from_columns(params, pk, advice, instances, rng, transcript)
 }
-
@@ -1,8 +1,9 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use crate::arithmetic::CurveAffine;
 
-mod prover;
+#[cfg(feature = "std")]
+pub(crate) mod prover;
 mod verifier;
 
 /// A vanishing argument.
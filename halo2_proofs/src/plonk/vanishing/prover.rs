@@ -2,11 +2,13 @@ use std::iter;
 
 use ff::Field;
 use group::Curve;
-use rand::rngs::ThreadRng;
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use rand_core::RngCore;
-use rayon::prelude::{ParallelIterator, IntoParallelRefIterator};
-use rayon::slice::ParallelSlice;
+use rayon::prelude::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+use rayon::slice::{ParallelSlice, ParallelSliceMut};
 
 use super::Argument;
 use crate::poly::Rotation;
@@ -23,8 +25,12 @@ use crate::{
 };
 use rayon::iter::IntoParallelRefMutIterator;
 
+/// The number of coefficients of the random polynomial drawn from each
+/// generator.
+const RANDOM_POLY_CHUNK_SIZE: usize = 1 << 12;
+
 pub(in crate::plonk) struct Committed<C: CurveAffine> {
-    random_poly: Polynomial<C::Scalar, Coeff>,
+    pub(in crate::plonk) random_poly: Polynomial<C::Scalar, Coeff>,
 }
 
 pub(in crate::plonk) struct Constructed<C: CurveAffine> {
@@ -38,33 +44,42 @@ pub(in crate::plonk) struct Evaluated<C: CurveAffine> {
 }
 
 impl<C: CurveAffine> Argument<C> {
-    pub(in crate::plonk) fn commit<E: EncodedChallenge<C>, R: RngCore, T: TranscriptWrite<C, E>>(
+    /// Samples the random polynomial used to blind `h(X)` and returns it
+    /// together with its commitment, which the caller adds to the transcript.
+    pub(in crate::plonk) fn commit<R: RngCore>(
         params: &Params<C>,
         domain: &EvaluationDomain<C::Scalar>,
         mut rng: R,
-        transcript: &mut T,
-    ) -> Result<Committed<C>, Error> {
+    ) -> Result<(Committed<C>, C), Error> {
         // Sample a random polynomial of degree n - 1
         let mut random_poly = domain.empty_coeff();
 
-        let random = vec![0; domain.k() as usize]
-            .iter()
-            .map(|_| C::ScalarExt::random(&mut rng))
+        // Each chunk draws from its own generator, seeded from `rng`. The
+        // chunks have a fixed size rather than one per thread, so that the
+        // polynomial only depends on `rng`.
+        let seeds = random_poly
+            .chunks(RANDOM_POLY_CHUNK_SIZE)
+            .map(|_| {
+                let mut seed = <StdRng as SeedableRng>::Seed::default();
+                rng.fill_bytes(&mut seed);
+                seed
+            })
             .collect::<Vec<_>>();
 
-        random_poly.par_iter_mut().for_each(|coeff| {
-            let mut rng = thread_rng();
-            *coeff = (C::ScalarExt::random(&mut rng)
-                + random[rng.next_u64() as usize % domain.k() as usize])
-                * (C::ScalarExt::random(&mut rng)
-                    + random[rng.next_u64() as usize % domain.k() as usize])
-        });
+        random_poly
+            .par_chunks_mut(RANDOM_POLY_CHUNK_SIZE)
+            .zip(seeds.into_par_iter())
+            .for_each(|(coeffs, seed)| {
+                let mut rng = StdRng::from_seed(seed);
+                for coeff in coeffs.iter_mut() {
+                    *coeff = C::ScalarExt::random(&mut rng);
+                }
+            });
 
         // Commit
         let c = params.commit(&random_poly).to_affine();
-        transcript.write_point(c)?;
 
-        Ok(Committed { random_poly })
+        Ok((Committed { random_poly }, c))
     }
 }
 
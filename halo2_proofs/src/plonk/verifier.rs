@@ -1,13 +1,14 @@
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::iter;
+use core::marker::PhantomData;
+use core::ops::Mul;
 use ff::Field;
 use group::Curve;
 use rand_core::RngCore;
-use std::iter;
-use std::marker::PhantomData;
-use std::ops::Mul;
 
 use super::{
-    vanishing, ChallengeBeta, ChallengeGamma, ChallengeTheta, ChallengeX, ChallengeY, Error,
-    VerifyingKey,
+    vanishing, ChallengeBeta, ChallengeGamma, ChallengeTheta, ChallengeX, ChallengeY, Column,
+    Error, Instance, InstanceEvaluation, VerifyingKey,
 };
 use crate::arithmetic::{BaseExt, CurveAffine, FieldExt, MultiMillerLoop};
 
@@ -15,7 +16,7 @@ use crate::poly::{
     commitment::{Blind, Params, ParamsVerifier},
     multiopen::Decider,
     multiopen::{self, VerifierQuery},
-    PairMSM, MSM,
+    PairMSM, Rotation, MSM,
 };
 use crate::transcript::{read_n_points, read_n_scalars, EncodedChallenge, TranscriptRead};
 
@@ -110,6 +111,131 @@ impl<'a, C: MultiMillerLoop, R: RngCore> VerificationStrategy<C::G1Affine>
     }
 }
 
+/// A verifier that checks proofs of different circuits in a single batch.
+///
+/// The proofs may be of circuits of different sizes and checked with different
+/// [`ParamsVerifier`]s, as long as the parameters share the same `g2` and `s_g2`,
+/// so that the proofs can be folded into a single pairing check. If the batch
+/// fails, it is bisected to find the invalid proofs.
+#[derive(Debug)]
+pub struct MultiCircuitBatchVerifier<'a, E: MultiMillerLoop> {
+    params: Vec<&'a ParamsVerifier<E>>,
+    // The MSM of each proof, or `None` if the proof could not be read.
+    msms: Vec<Option<PairMSM<E::G1Affine>>>,
+}
+
+impl<'a, E: MultiMillerLoop> Default for MultiCircuitBatchVerifier<'a, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, E: MultiMillerLoop> MultiCircuitBatchVerifier<'a, E> {
+    /// Constructs a new, empty batch verifier.
+    pub fn new() -> Self {
+        MultiCircuitBatchVerifier {
+            params: vec![],
+            msms: vec![],
+        }
+    }
+
+    /// Returns the number of proofs in the batch.
+    pub fn len(&self) -> usize {
+        self.msms.len()
+    }
+
+    /// Returns `true` if the batch has no proofs.
+    pub fn is_empty(&self) -> bool {
+        self.msms.is_empty()
+    }
+
+    /// Reads a proof of the circuit with verifying key `vk` and adds it to the
+    /// batch, returning its index in the batch.
+    ///
+    /// A proof that cannot be read, or whose instances do not match the circuit,
+    /// is added to the batch as an invalid proof. Returns
+    /// [`Error::IncompatibleParams`] if `params` do not share the same `g2` and
+    /// `s_g2` as the parameters of the proofs already in the batch.
+    pub fn add_proof<EC: EncodedChallenge<E::G1Affine>, T: TranscriptRead<E::G1Affine, EC>>(
+        &mut self,
+        params: &'a ParamsVerifier<E>,
+        vk: &VerifyingKey<E::G1Affine>,
+        instances: &[&[&[E::Scalar]]],
+        transcript: &mut T,
+    ) -> Result<usize, Error> {
+        if let Some(first) = self.params.first() {
+            if first.g2 != params.g2 || first.s_g2 != params.s_g2 {
+                return Err(Error::IncompatibleParams);
+            }
+        }
+
+        let msm = verify_proof(params, vk, MsmAccumulator, instances, transcript).ok();
+        self.params.push(params);
+        self.msms.push(msm);
+        Ok(self.msms.len() - 1)
+    }
+
+    /// Finalizes the batch and checks its validity.
+    ///
+    /// Returns the indices of the invalid proofs, in order, if any proof was
+    /// invalid. An invalid batch is checked again in halves, recursively, so
+    /// that only the parts of the batch containing invalid proofs are checked
+    /// down to the individual proofs.
+    pub fn finalize<R: RngCore>(self, mut rng: R) -> Result<(), Vec<usize>> {
+        // The proofs that could not be read are invalid.
+        let (readable, mut invalid): (Vec<_>, Vec<_>) =
+            (0..self.msms.len()).partition(|&index| self.msms[index].is_some());
+
+        self.bisect(&readable, &mut rng, &mut invalid);
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            invalid.sort_unstable();
+            Err(invalid)
+        }
+    }
+
+    /// Checks the proofs at `indices` together, and pushes the indices of the
+    /// invalid ones to `invalid`.
+    fn bisect<R: RngCore>(&self, indices: &[usize], rng: &mut R, invalid: &mut Vec<usize>) {
+        if indices.is_empty() || self.check(indices, rng) {
+            return;
+        }
+        if indices.len() == 1 {
+            invalid.push(indices[0]);
+            return;
+        }
+
+        let (left, right) = indices.split_at(indices.len() / 2);
+        self.bisect(left, rng, invalid);
+        self.bisect(right, rng, invalid);
+    }
+
+    /// Folds the MSMs of the proofs at `indices` into a single pairing check.
+    fn check<R: RngCore>(&self, indices: &[usize], rng: &mut R) -> bool {
+        let mut msm = PairMSM::default();
+        for &index in indices {
+            // Scale the MSM by a random factor, as in `BatchVerifier`, so that
+            // the proofs cannot cancel each other out.
+            msm.scale(E::Scalar::random(&mut *rng));
+            msm.add_msm(self.msms[index].clone().expect("proof was read"));
+        }
+        Decider::verify(self.params[indices[0]], msm)
+    }
+}
+
+/// A strategy that returns the MSM of a proof rather than checking it.
+struct MsmAccumulator;
+
+impl<C: CurveAffine> VerificationStrategy<C> for MsmAccumulator {
+    type Output = PairMSM<C>;
+
+    fn process(self, f: impl FnOnce() -> Result<PairMSM<C>, Error>) -> Result<Self::Output, Error> {
+        f()
+    }
+}
+
 /// Returns a boolean indicating whether or not the proof is valid
 pub fn get_advice_commitments_from_transcript<
     'params,
@@ -124,6 +250,90 @@ pub fn get_advice_commitments_from_transcript<
     Ok(res)
 }
 
+/// The commitments to the exposed advice columns of a proof, for each circuit
+/// instance that it proves.
+///
+/// Exposed advice columns are not blinded, so two proofs created with the same
+/// parameters committed to the same contents of their exposed columns if and only
+/// if the commitments are equal. See [`verify_commitment_link`].
+///
+/// When the proof is checked with a [`BatchVerifier`], the commitments can only be
+/// relied on once the batch has been finalized successfully.
+#[derive(Clone, Debug)]
+pub struct ExposedAdvice<C: CurveAffine> {
+    names: Vec<String>,
+    // The commitments of each circuit instance, in the order of `names`.
+    commitments: Vec<Vec<C>>,
+}
+
+impl<C: CurveAffine> ExposedAdvice<C> {
+    fn new(vk: &VerifyingKey<C>, advice_commitments: Vec<Vec<C>>) -> Self {
+        let (names, columns): (Vec<_>, Vec<_>) = vk
+            .cs
+            .exposed_advice_columns()
+            .map(|(name, column)| (name.to_owned(), column))
+            .unzip();
+        let commitments = advice_commitments
+            .iter()
+            .map(|commitments| {
+                columns
+                    .iter()
+                    .map(|column| commitments[column.index()])
+                    .collect()
+            })
+            .collect();
+
+        ExposedAdvice { names, commitments }
+    }
+
+    /// Returns the number of circuit instances proven by the proof.
+    pub fn num_instances(&self) -> usize {
+        self.commitments.len()
+    }
+
+    /// Returns the names of the exposed advice columns.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|name| name.as_str())
+    }
+
+    /// Returns the commitment to the exposed advice column `name` of the given
+    /// circuit instance.
+    pub fn commitment(&self, instance: usize, name: &str) -> Result<C, Error> {
+        let column = self
+            .names
+            .iter()
+            .position(|exposed| exposed == name)
+            .ok_or_else(|| Error::ColumnNotExposed(name.to_owned()))?;
+        self.commitments
+            .get(instance)
+            .map(|commitments| commitments[column])
+            .ok_or(Error::InvalidInstances)
+    }
+}
+
+/// Checks that the exposed advice column `left_column` of the circuit instance
+/// `left_instance` of one proof has the same contents as the exposed advice column
+/// `right_column` of the circuit instance `right_instance` of another proof.
+///
+/// The proofs may be of the same circuit or of different circuits, but must have
+/// been created with the same parameters.
+pub fn verify_commitment_link<C: CurveAffine>(
+    left: &ExposedAdvice<C>,
+    left_instance: usize,
+    left_column: &str,
+    right: &ExposedAdvice<C>,
+    right_instance: usize,
+    right_column: &str,
+) -> Result<(), Error> {
+    let left = left.commitment(left_instance, left_column)?;
+    let right = right.commitment(right_instance, right_column)?;
+    if left == right {
+        Ok(())
+    } else {
+        Err(Error::CommitmentLinkFailure)
+    }
+}
+
 /// Returns a boolean indicating whether or not the proof is valid
 pub fn verify_proof<
     'params,
@@ -138,6 +348,107 @@ pub fn verify_proof<
     instances: &[&[&[C::Scalar]]],
     transcript: &mut T,
 ) -> Result<V::Output, Error> {
+    verify_proof_reading_advice(params, vk, strategy, instances, transcript)
+        .map(|(output, _)| output)
+}
+
+/// Checks the proof like [`verify_proof`], and also returns the commitments to the
+/// exposed advice columns of each circuit instance.
+pub fn verify_proof_with_exposed_advice<
+    'params,
+    C: MultiMillerLoop,
+    E: EncodedChallenge<C::G1Affine>,
+    T: TranscriptRead<C::G1Affine, E>,
+    V: VerificationStrategy<C::G1Affine>,
+>(
+    params: &'params ParamsVerifier<C>,
+    vk: &VerifyingKey<C::G1Affine>,
+    strategy: V,
+    instances: &[&[&[C::Scalar]]],
+    transcript: &mut T,
+) -> Result<(V::Output, ExposedAdvice<C::G1Affine>), Error> {
+    let (output, advice_commitments) =
+        verify_proof_reading_advice(params, vk, strategy, instances, transcript)?;
+    Ok((output, ExposedAdvice::new(vk, advice_commitments)))
+}
+
+/// Checks the proof like [`verify_proof`], using commitments to the instance columns
+/// of each circuit instance rather than their values.
+///
+/// This avoids committing to the instance columns during verification, so the
+/// verifier parameters do not need to cover the public inputs. The commitments can
+/// be computed with [`commit_instances`].
+///
+/// Returns [`Error::InvalidInstances`] if the circuit uses
+/// [`InstanceEvaluation::Direct`], as its instance columns are not committed to.
+///
+/// [`commit_instances`]: crate::plonk::commit_instances
+pub fn verify_proof_with_instance_commitments<
+    'params,
+    C: MultiMillerLoop,
+    E: EncodedChallenge<C::G1Affine>,
+    T: TranscriptRead<C::G1Affine, E>,
+    V: VerificationStrategy<C::G1Affine>,
+>(
+    params: &'params ParamsVerifier<C>,
+    vk: &VerifyingKey<C::G1Affine>,
+    strategy: V,
+    instance_commitments: &[&[C::G1Affine]],
+    transcript: &mut T,
+) -> Result<V::Output, Error> {
+    if vk.cs.instance_evaluation == InstanceEvaluation::Direct {
+        return Err(Error::InvalidInstances);
+    }
+
+    verify_proof_from_instances(
+        params,
+        vk,
+        strategy,
+        Instances::Commitments(instance_commitments),
+        transcript,
+    )
+    .map(|(output, _)| output)
+}
+
+/// The instance columns of the circuit instances of a proof, as the verifier
+/// hashes them into the transcript.
+#[derive(Clone, Copy)]
+enum Instances<'a, C: CurveAffine> {
+    /// Commitments to the instance columns, opened at `x` by the proof.
+    Commitments(&'a [&'a [C]]),
+    /// The values of the instance columns, evaluated at `x` by the verifier.
+    Values(&'a [&'a [&'a [C::Scalar]]]),
+}
+
+impl<'a, C: CurveAffine> Instances<'a, C> {
+    fn len(&self) -> usize {
+        match self {
+            Instances::Commitments(commitments) => commitments.len(),
+            Instances::Values(values) => values.len(),
+        }
+    }
+
+    fn num_columns(&self, proof: usize) -> usize {
+        match self {
+            Instances::Commitments(commitments) => commitments[proof].len(),
+            Instances::Values(values) => values[proof].len(),
+        }
+    }
+}
+
+fn verify_proof_reading_advice<
+    'params,
+    C: MultiMillerLoop,
+    E: EncodedChallenge<C::G1Affine>,
+    T: TranscriptRead<C::G1Affine, E>,
+    V: VerificationStrategy<C::G1Affine>,
+>(
+    params: &'params ParamsVerifier<C>,
+    vk: &VerifyingKey<C::G1Affine>,
+    strategy: V,
+    instances: &[&[&[C::Scalar]]],
+    transcript: &mut T,
+) -> Result<(V::Output, Vec<Vec<C::G1Affine>>), Error> {
     // Check that instances matches the expected number of instance columns
     for instances in instances.iter() {
         if instances.len() != vk.cs.num_instance_columns {
@@ -145,31 +456,91 @@ pub fn verify_proof<
         }
     }
 
-    let instance_commitments = instances
-        .iter()
-        .map(|instance| {
-            instance
+    for instance in instances.iter().flat_map(|instance| instance.iter()) {
+        if instance.len() > params.n as usize - (vk.cs.blinding_factors() + 1) {
+            return Err(Error::InstanceTooLarge);
+        }
+    }
+
+    match vk.cs.instance_evaluation {
+        InstanceEvaluation::Committed => {
+            let instance_commitments = instances
                 .iter()
                 .map(|instance| {
-                    if instance.len() > params.n as usize - (vk.cs.blinding_factors() + 1) {
-                        return Err(Error::InstanceTooLarge);
-                    }
-
-                    Ok(params.commit_lagrange(instance.to_vec()).to_affine())
+                    instance
+                        .iter()
+                        .map(|instance| params.commit_lagrange(instance.to_vec()).to_affine())
+                        .collect::<Vec<_>>()
                 })
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+                .collect::<Vec<_>>();
+            let instance_commitments: Vec<_> = instance_commitments
+                .iter()
+                .map(|commitments| &commitments[..])
+                .collect();
+
+            verify_proof_from_instances(
+                params,
+                vk,
+                strategy,
+                Instances::Commitments(&instance_commitments),
+                transcript,
+            )
+        }
+        InstanceEvaluation::Direct => verify_proof_from_instances(
+            params,
+            vk,
+            strategy,
+            Instances::Values(instances),
+            transcript,
+        ),
+    }
+}
+
+fn verify_proof_from_instances<
+    'params,
+    C: MultiMillerLoop,
+    E: EncodedChallenge<C::G1Affine>,
+    T: TranscriptRead<C::G1Affine, E>,
+    V: VerificationStrategy<C::G1Affine>,
+>(
+    params: &'params ParamsVerifier<C>,
+    vk: &VerifyingKey<C::G1Affine>,
+    strategy: V,
+    instances: Instances<'_, C::G1Affine>,
+    transcript: &mut T,
+) -> Result<(V::Output, Vec<Vec<C::G1Affine>>), Error> {
+    let num_proofs = instances.len();
 
-    let num_proofs = instance_commitments.len();
+    // Check that the instances match the expected number of instance columns
+    for proof in 0..num_proofs {
+        if instances.num_columns(proof) != vk.cs.num_instance_columns {
+            return Err(Error::InvalidInstances);
+        }
+    }
 
     // Hash verification key into transcript
     vk.hash_into(transcript)?;
 
-    for instance_commitments in instance_commitments.iter() {
-        // Hash the instance (external) commitments into the transcript
-        for commitment in instance_commitments {
-            transcript.common_point(*commitment)?
+    match instances {
+        Instances::Commitments(instance_commitments) => {
+            for instance_commitments in instance_commitments.iter() {
+                // Hash the instance (external) commitments into the transcript
+                for commitment in instance_commitments.iter() {
+                    transcript.common_point(*commitment)?
+                }
+            }
+        }
+        Instances::Values(instance_values) => {
+            for instance_values in instance_values.iter() {
+                // Hash the instance values into the transcript, as the prover
+                // does in place of the commitments
+                for values in instance_values.iter() {
+                    transcript.common_scalar(C::Scalar::from(values.len() as u64))?;
+                    for value in values.iter() {
+                        transcript.common_scalar(*value)?;
+                    }
+                }
+            }
         }
     }
 
@@ -228,9 +599,52 @@ pub fn verify_proof<
     // Sample x challenge, which is used to ensure the circuit is
     // satisfied with high probability.
     let x: ChallengeX<_> = transcript.squeeze_challenge_scalar();
-    let instance_evals = (0..num_proofs)
-        .map(|_| -> Result<Vec<_>, _> { read_n_scalars(transcript, vk.cs.instance_queries.len()) })
-        .collect::<Result<Vec<_>, _>>()?;
+
+    // x^n
+    let xn = x.pow(&[params.n as u64, 0, 0, 0]);
+
+    // The instance columns are only opened at x if they are committed to.
+    let (instance_commitments, instance_queries, instance_evals): (
+        Vec<&[C::G1Affine]>,
+        &[(Column<Instance>, Rotation)],
+        Vec<Vec<C::Scalar>>,
+    ) = match instances {
+        Instances::Commitments(instance_commitments) => {
+            let instance_evals = (0..num_proofs)
+                .map(|_| -> Result<Vec<_>, _> {
+                    read_n_scalars(transcript, vk.cs.instance_queries.len())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            (
+                instance_commitments.to_vec(),
+                &vk.cs.instance_queries[..],
+                instance_evals,
+            )
+        }
+        Instances::Values(instance_values) => {
+            // Since L_i(x \omega^a) = L_{i - a}(x), the instance column queried at
+            // rotation a is evaluated at x with the Lagrange basis shifted by -a.
+            let instance_evals = instance_values
+                .iter()
+                .map(|instance| {
+                    vk.cs
+                        .instance_queries
+                        .iter()
+                        .map(|&(column, at)| {
+                            let values = instance[column.index()];
+                            let rotations = -at.0..(values.len() as i32 - at.0);
+                            vk.domain
+                                .l_i_range(*x, xn, rotations)
+                                .iter()
+                                .zip(values.iter())
+                                .fold(C::Scalar::zero(), |acc, (l, value)| acc + *l * value)
+                        })
+                        .collect()
+                })
+                .collect();
+            (vec![&[][..]; num_proofs], &[][..], instance_evals)
+        }
+    };
 
     let advice_evals = (0..num_proofs)
         .map(|_| -> Result<Vec<_>, _> { read_n_scalars(transcript, vk.cs.advice_queries.len()) })
@@ -260,9 +674,6 @@ pub fn verify_proof<
     // This check ensures the circuit is satisfied so long as the polynomial
     // commitments open to the correct values.
     let vanishing = {
-        // x^n
-        let xn = x.pow(&[params.n as u64, 0, 0, 0]);
-
         let blinding_factors = vk.cs.blinding_factors();
         let l_evals = vk
             .domain
@@ -282,7 +693,7 @@ pub fn verify_proof<
             .zip(lookups_evaluated.iter())
             .flat_map(|(((advice_evals, instance_evals), permutation), lookups)| {
                 let fixed_evals = &fixed_evals;
-                std::iter::empty()
+                core::iter::empty()
                     // Evaluate the circuit using the custom gates provided
                     .chain(vk.cs.gates.iter().flat_map(move |gate| {
                         gate.polynomials().iter().map(move |poly| {
@@ -354,7 +765,7 @@ pub fn verify_proof<
                 lookups,
             )| {
                 iter::empty()
-                    .chain(vk.cs.instance_queries.iter().enumerate().map(
+                    .chain(instance_queries.iter().enumerate().map(
                         move |(query_index, &(column, at))| {
                             VerifierQuery::new_commitment(
                                 &instance_commitments[column.index()],
@@ -402,7 +813,9 @@ pub fn verify_proof<
 
     // We are now convinced the circuit is satisfied so long as the
     // polynomial commitments open to the correct values.
-    strategy.process(|| {
+    let output = strategy.process(|| {
         multiopen::verify_proof(params, transcript, queries).map_err(|_| Error::Opening)
-    })
+    })?;
+
+    Ok((output, advice_commitments))
 }
@@ -6,11 +6,13 @@ use crate::arithmetic::parallelize;
 use crate::plonk::Assigned;
 
 use group::ff::{BatchInvert, Field};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::ops::{Add, Deref, DerefMut, Index, IndexMut, Mul, RangeFrom, RangeFull, Sub};
 use pairing::arithmetic::FieldExt;
+#[cfg(feature = "std")]
 use rayon::iter::*;
-use std::fmt::Debug;
-use std::marker::PhantomData;
-use std::ops::{Add, Deref, DerefMut, Index, IndexMut, Mul, RangeFrom, RangeFull, Sub};
 
 pub mod commitment;
 mod domain;
@@ -146,6 +148,7 @@ impl<F, B> Polynomial<F, B> {
     }
 }
 
+#[cfg(feature = "std")]
 pub(crate) fn batch_invert_assigned<F: FieldExt>(
     assigned: Vec<Polynomial<Assigned<F>, LagrangeCoeff>>,
 ) -> Vec<Polynomial<F, LagrangeCoeff>> {
@@ -173,6 +176,7 @@ pub(crate) fn batch_invert_assigned<F: FieldExt>(
         .collect()
 }
 
+#[cfg(feature = "std")]
 impl<F: Field> Polynomial<Assigned<F>, LagrangeCoeff> {
     pub(crate) fn invert(&self, inv_denoms: Vec<Option<F>>) -> Polynomial<F, LagrangeCoeff> {
         Polynomial {
@@ -4,19 +4,25 @@
 //! [halo]: https://eprint.iacr.org/2019/1021
 
 use super::{Coeff, LagrangeCoeff, Polynomial, MSM};
+#[cfg(feature = "std")]
+use crate::arithmetic::best_fft_cpu;
 use crate::arithmetic::{
     best_fft, best_multiexp, best_multiexp_gpu_cond, parallelize, CurveAffine, CurveExt, Engine,
     FieldExt, Group,
 };
-use crate::helpers::CurveRead;
+use crate::helpers::{invalid_data, read_exact_vec, CurveRead};
 
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Mul, MulAssign};
 use ff::{Field, PrimeField};
 use group::{prime::PrimeCurveAffine, Curve, Group as _, GroupEncoding};
+#[cfg(feature = "std")]
 use rand_core::OsRng;
-use std::marker::PhantomData;
-use std::ops::{Add, AddAssign, Mul, MulAssign};
+#[cfg(feature = "std")]
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 
-use std::io;
+use crate::io;
 
 /// These are the prover parameters for the polynomial commitment scheme.
 #[derive(Debug)]
@@ -53,6 +59,8 @@ mod tests {
 impl<C: CurveAffine> Params<C> {
     /// Initializes parameters for the curve, Draws random toxic point inside of the function
     /// MUST NOT be used in production
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     pub fn unsafe_setup<E: Engine>(k: u32) -> Params<E::G1Affine> {
         // TODO: Make this function only available in test mod
         // Largest root of unity exponent of the Engine is `2^E::Scalar::S`, so we can
@@ -257,21 +265,32 @@ impl<C: CurveAffine> Params<C> {
         let mut k = [0u8; 4];
         reader.read_exact(&mut k[..])?;
         let k = u32::from_le_bytes(k);
-        let n = 1 << k;
+        if k > C::Scalar::S {
+            return Err(invalid_data(
+                "k is larger than the two-adicity of the field",
+            ));
+        }
+        let n: usize = 1 << k;
 
+        // The points are read one at a time, so that a corrupted `k` cannot
+        // allocate more than the input holds.
         let load_points_from_file_parallelly = |reader: &mut R| -> io::Result<Vec<C>> {
-            let mut points_compressed: Vec<C::Repr> = vec![C::Repr::default(); n];
-            for points_compressed in points_compressed.iter_mut() {
-                reader.read_exact((*points_compressed).as_mut())?;
+            let mut points_compressed: Vec<C::Repr> = vec![];
+            for _ in 0..n {
+                let mut point_compressed = C::Repr::default();
+                reader.read_exact(point_compressed.as_mut())?;
+                points_compressed.push(point_compressed);
             }
 
-            let mut points = vec![C::default(); n];
-            parallelize(&mut points, |points, chunks| {
-                for (i, point) in points.iter_mut().enumerate() {
-                    *point = Option::from(C::from_bytes(&points_compressed[chunks + i])).unwrap();
-                }
-            });
-            Ok(points)
+            #[cfg(feature = "std")]
+            let points_compressed = points_compressed.par_iter();
+            #[cfg(not(feature = "std"))]
+            let points_compressed = points_compressed.iter();
+
+            points_compressed
+                .map(C::from_canonical_bytes)
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid_data("invalid point encoding in parameters"))
         };
 
         let g = load_points_from_file_parallelly(&mut reader)?;
@@ -280,9 +299,7 @@ impl<C: CurveAffine> Params<C> {
         let mut additional_data_len = [0u8; 4];
         reader.read_exact(&mut additional_data_len[..])?;
         let additional_data_len = u32::from_le_bytes(additional_data_len);
-        let mut additional_data = vec![0u8; additional_data_len as usize];
-
-        reader.read_exact(&mut additional_data[..])?;
+        let additional_data = read_exact_vec(&mut reader, additional_data_len as usize)?;
 
         Ok(Params {
             k,
@@ -315,6 +332,46 @@ impl<C: CurveAffine> Params<C> {
             s_g2,
         })
     }
+
+    /// Returns the parameters of the same setup for circuits of size $2^k$,
+    /// which share their `s_g2` with these parameters.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn downsize(&self, k: u32) -> Params<C> {
+        assert!(k <= self.k);
+        let n: u64 = 1 << k;
+        let g = self.g[..n as usize].to_vec();
+
+        // The Lagrange basis is the inverse FFT of the monomial basis.
+        let mut g_lagrange_projective: Vec<C::Curve> = g.iter().map(|g| g.to_curve()).collect();
+        let mut omega_inv = C::Scalar::ROOT_OF_UNITY_INV;
+        for _ in k..C::Scalar::S {
+            omega_inv = omega_inv.square();
+        }
+        best_fft_cpu(&mut g_lagrange_projective, omega_inv, k);
+        let n_inv = C::Scalar::from(n).invert().unwrap();
+        parallelize(&mut g_lagrange_projective, |g, _| {
+            for g in g.iter_mut() {
+                *g *= n_inv;
+            }
+        });
+
+        let mut g_lagrange = vec![C::identity(); n as usize];
+        parallelize(&mut g_lagrange, |g_lagrange, starts| {
+            C::Curve::batch_normalize(
+                &g_lagrange_projective[starts..(starts + g_lagrange.len())],
+                g_lagrange,
+            );
+        });
+
+        Params {
+            k,
+            n,
+            g,
+            g_lagrange,
+            additional_data: self.additional_data.clone(),
+        }
+    }
 }
 
 /// Wrapper type around a blinding factor.
@@ -413,7 +470,15 @@ impl<E: Engine> ParamsVerifier<E> {
         reader.read_exact(&mut public_inputs_size[..])?;
         let public_inputs_size = u32::from_le_bytes(public_inputs_size);
 
+        if k > E::Scalar::S {
+            return Err(invalid_data(
+                "k is larger than the two-adicity of the field",
+            ));
+        }
         let n = 1 << k;
+        if public_inputs_size as u64 > n {
+            return Err(invalid_data("more public inputs than rows"));
+        }
 
         let g1 = E::G1Affine::read(&mut reader)?;
         let g2 = E::G2Affine::read(&mut reader)?;
@@ -493,3 +558,24 @@ fn test_commit_lagrange() {
     let b = domain.lagrange_to_coeff(a.clone());
     assert_eq!(params.commit(&b), params.commit_lagrange(&a));
 }
+
+#[test]
+fn test_downsize() {
+    const K: u32 = 6;
+
+    let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K);
+    let small = params.downsize(K - 2);
+    assert_eq!(small.k, K - 2);
+    assert_eq!(small.g[..], params.g[..1 << (K - 2)]);
+    assert_eq!(small.additional_data, params.additional_data);
+
+    // The Lagrange basis of the smaller domain commits to the same
+    // polynomials as its monomial basis.
+    let domain = super::EvaluationDomain::new(1, K - 2);
+    let mut a = domain.empty_lagrange();
+    for (i, a) in a.iter_mut().enumerate() {
+        *a = Fr::from(i as u64);
+    }
+    let b = domain.lagrange_to_coeff(a.clone());
+    assert_eq!(small.commit(&b), small.commit_lagrange(&a));
+}
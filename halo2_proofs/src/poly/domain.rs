@@ -14,7 +14,8 @@ use super::{
 use ark_std::{end_timer, start_timer};
 use group::ff::{BatchInvert, Field, PrimeField};
 
-use std::marker::PhantomData;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
 
 /// This structure contains precomputed constants and other details needed for
 /// performing operations on an evaluation domain of size $2^k$ and an extended
@@ -47,6 +48,7 @@ impl<G: Group> EvaluationDomain<G> {
         // n = 2^k
         let n = 1u64 << k;
 
+        #[cfg(feature = "std")]
         println!("quotient_poly_degree {}", quotient_poly_degree);
 
         // We need to work within an extended domain, not params.k but params.k + i
@@ -285,6 +287,30 @@ impl<G: Group> EvaluationDomain<G> {
         }
     }
 
+    /// This takes us from an n-length coefficient vector into the `index`-th
+    /// of the `extended_len() / n` cosets of the original domain that make up
+    /// the extended domain. The result holds the evaluations at
+    /// `zeta * extended_omega^(index + j * 2^(extended_k - k))` for `j` in
+    /// `0..n`, which are the entries of [`Self::coeff_to_extended`] at those
+    /// indices.
+    pub fn coeff_to_extended_part(
+        &self,
+        mut a: Polynomial<G, Coeff>,
+        index: usize,
+    ) -> Polynomial<G, ExtendedLagrangeCoeff> {
+        assert_eq!(a.values.len(), 1 << self.k);
+        assert!(index < 1 << (self.extended_k - self.k));
+
+        let c = self.g_coset * self.extended_omega.pow_vartime(&[index as u64, 0, 0, 0]);
+        self.distribute_powers(&mut a.values, c);
+        best_fft(&mut a.values, self.omega, self.k);
+
+        Polynomial {
+            values: a.values,
+            _marker: PhantomData,
+        }
+    }
+
     pub fn coeff_to_extended_without_fft(
         &self,
         mut a: Polynomial<G, Coeff>,
@@ -396,6 +422,18 @@ impl<G: Group> EvaluationDomain<G> {
         });
     }
 
+    /// Given a slice of group elements `[a_0, a_1, a_2, ...]`, this returns
+    /// `[a_0, [c]a_1, [c^2]a_2, [c^3]a_3, ...]`.
+    fn distribute_powers(&self, a: &mut [G], c: G::Scalar) {
+        parallelize(a, |a, index| {
+            let mut c_power = c.pow_vartime(&[index as u64, 0, 0, 0]);
+            for a in a {
+                a.group_scale(&c_power);
+                c_power *= &c;
+            }
+        });
+    }
+
     fn ifft(a: &mut [G], omega_inv: G::Scalar, log_n: u32, divisor: G::Scalar) {
         #[cfg(not(feature = "cuda"))]
         {
@@ -616,3 +654,22 @@ fn test_l_i() {
         assert_eq!(eval_polynomial(&l[(8 - i) % 8][..], x), evaluations[7 - i]);
     }
 }
+
+#[test]
+fn test_coeff_to_extended_part() {
+    use rand_core::OsRng;
+
+    use pairing::bn256::Fr as Scalar;
+    let domain = EvaluationDomain::<Scalar>::new(5, 4);
+
+    let poly = domain.coeff_from_vec((0..16).map(|_| Scalar::random(OsRng)).collect());
+    let extended = domain.coeff_to_extended(poly.clone());
+
+    let parts = 1 << (domain.extended_k() - domain.k());
+    for index in 0..parts {
+        let part = domain.coeff_to_extended_part(poly.clone(), index);
+        for (j, value) in part.iter().enumerate() {
+            assert_eq!(*value, extended[index + j * parts]);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::arithmetic::{best_multiexp, parallelize, CurveAffine, Engine};
+use alloc::vec::Vec;
 use group::Curve;
 
 /// A multiscalar multiplication in the polynomial commitment scheme
@@ -70,7 +71,7 @@ impl<'a, C: CurveAffine> MSM<C> {
 }
 
 /// A guard returned by the verifier
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct PairMSM<C: CurveAffine> {
     left: MSM<C>,
     right: MSM<C>,
@@ -12,13 +12,13 @@ use crate::{
 
 use crate::poly::Rotation;
 use ff::Field;
-use group::Group;
-use rand::RngCore;
-use std::{
+use alloc::{
     collections::{BTreeMap, BTreeSet},
-    marker::PhantomData,
-    thread::AccessError,
+    vec::Vec,
 };
+use core::marker::PhantomData;
+use group::Group;
+use rand_core::RngCore;
 use subtle::Choice;
 
 cfg_if::cfg_if! {
@@ -121,9 +121,9 @@ impl<'r, 'params: 'r, C: CurveAffine> PartialEq for CommitmentReference<'r, C> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (&CommitmentReference::Commitment(a), &CommitmentReference::Commitment(b)) => {
-                std::ptr::eq(a, b)
+                core::ptr::eq(a, b)
             }
-            (&CommitmentReference::MSM(a), &CommitmentReference::MSM(b)) => std::ptr::eq(a, b),
+            (&CommitmentReference::MSM(a), &CommitmentReference::MSM(b)) => core::ptr::eq(a, b),
             _ => false,
         }
     }
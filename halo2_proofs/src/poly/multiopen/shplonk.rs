@@ -1,3 +1,4 @@
+#[cfg(feature = "std")]
 mod prover;
 mod verifier;
 
@@ -8,12 +9,14 @@ use crate::{
     transcript::ChallengeScalar,
 };
 
-use std::{
+use alloc::{
     collections::{btree_map::Entry, BTreeMap, BTreeSet},
-    marker::PhantomData,
+    vec::Vec,
 };
+use core::marker::PhantomData;
 
 use crate::poly::Rotation;
+#[cfg(feature = "std")]
 pub use prover::create_proof;
 pub use verifier::verify_proof;
 
@@ -11,12 +11,12 @@ use crate::poly::{
 };
 use crate::transcript::{EncodedChallenge, TranscriptRead};
 
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::MulAssign;
 use ff::Field;
 use group::prime::PrimeCurveAffine;
 use group::{Curve, Group};
-use rand::rngs::OsRng;
-use std::marker::PhantomData;
-use std::ops::MulAssign;
 use subtle::Choice;
 
 /// Verify a multi-opening proof
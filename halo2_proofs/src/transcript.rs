@@ -3,13 +3,14 @@
 
 use blake2b_simd::{Params as Blake2bParams, State as Blake2bState};
 use group::ff::PrimeField;
-use num_bigint::BigUint;
-use std::convert::TryInto;
 
 use crate::arithmetic::{BaseExt, Coordinates, CurveAffine, FieldExt};
+use crate::helpers::CurveRead;
 
-use std::io::{self, Read, Write};
-use std::marker::PhantomData;
+use crate::io::{self, Read, Write};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::marker::PhantomData;
 
 /// Prefix to a prover's message soliciting a challenge
 const BLAKE2B_PREFIX_CHALLENGE: u8 = 0;
@@ -90,11 +91,7 @@ impl<R: Read, C: CurveAffine> TranscriptRead<C, Challenge255<C>>
     for Blake2bRead<R, C, Challenge255<C>>
 {
     fn read_point(&mut self) -> io::Result<C> {
-        let mut compressed = C::Repr::default();
-        self.reader.read_exact(compressed.as_mut())?;
-        let point: C = Option::from(C::from_bytes(&compressed)).ok_or_else(|| {
-            io::Error::new(io::ErrorKind::Other, "invalid point encoding in proof")
-        })?;
+        let point = C::read(&mut self.reader)?;
         self.common_point(point)?;
 
         Ok(point)
@@ -133,8 +130,7 @@ impl<R: Read, C: CurveAffine> Transcript<C, Challenge255<C>>
                 "cannot write points at infinity to the transcript",
             )
         })?;
-        coords.x().write(&mut self.state)?;
-        coords.y().write(&mut self.state)?;
+        update_coordinates(&mut self.state, &coords)?;
 
         Ok(())
     }
@@ -147,6 +143,27 @@ impl<R: Read, C: CurveAffine> Transcript<C, Challenge255<C>>
     }
 }
 
+/// Hashes the coordinates of a point into a transcript.
+fn update_coordinates<C: CurveAffine>(
+    state: &mut Blake2bState,
+    coords: &Coordinates<C>,
+) -> io::Result<()> {
+    #[cfg(feature = "std")]
+    {
+        coords.x().write(state)?;
+        coords.y().write(state)?;
+    }
+    // Without `std`, the state is not an `io::Write`; `to_repr` is the same
+    // encoding as `BaseExt::write`.
+    #[cfg(not(feature = "std"))]
+    {
+        state.update(coords.x().to_repr().as_ref());
+        state.update(coords.y().to_repr().as_ref());
+    }
+
+    Ok(())
+}
+
 /// We will replace BLAKE2b with an algebraic hash function in a later version.
 #[derive(Debug, Clone)]
 pub struct Blake2bWrite<W: Write, C: CurveAffine, E: EncodedChallenge<C>> {
@@ -208,8 +225,7 @@ impl<W: Write, C: CurveAffine> Transcript<C, Challenge255<C>>
                 "cannot write points at infinity to the transcript",
             )
         })?;
-        coords.x().write(&mut self.state)?;
-        coords.y().write(&mut self.state)?;
+        update_coordinates(&mut self.state, &coords)?;
 
         Ok(())
     }
@@ -232,7 +248,7 @@ pub struct ChallengeScalar<C: CurveAffine, T> {
     _marker: PhantomData<T>,
 }
 
-impl<C: CurveAffine, T> std::ops::Deref for ChallengeScalar<C, T> {
+impl<C: CurveAffine, T> core::ops::Deref for ChallengeScalar<C, T> {
     type Target = C::Scalar;
 
     fn deref(&self) -> &Self::Target {
@@ -268,7 +284,7 @@ pub trait EncodedChallenge<C: CurveAffine> {
 #[derive(Copy, Clone, Debug)]
 pub struct Challenge255<C: CurveAffine>([u8; 32], PhantomData<C>);
 
-impl<C: CurveAffine> std::ops::Deref for Challenge255<C> {
+impl<C: CurveAffine> core::ops::Deref for Challenge255<C> {
     type Target = [u8; 32];
 
     fn deref(&self) -> &Self::Target {
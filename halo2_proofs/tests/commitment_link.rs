@@ -0,0 +1,147 @@
+use assert_matches::assert_matches;
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
+use halo2_proofs::helpers::Serializable;
+use halo2_proofs::plonk::{
+    create_proof, keygen_pk, keygen_vk, verify_commitment_link, verify_proof_with_exposed_advice,
+    Advice, Circuit, Column, ConstraintSystem, Error, ExposedAdvice, Selector, SingleVerifier,
+    VerifyingKey,
+};
+use halo2_proofs::poly::{
+    commitment::{Params, ParamsVerifier},
+    Rotation,
+};
+use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255};
+use pairing::bn256::Fr as Fp;
+use pairing::bn256::{Bn256, G1Affine};
+use rand_core::OsRng;
+
+#[test]
+fn commitment_link() {
+    const K: u32 = 4;
+
+    #[derive(Clone)]
+    struct MapConfig {
+        x: Column<Advice>,
+        y: Column<Advice>,
+        s: Selector,
+    }
+
+    /// Maps each `x` to `x * x`, or to `x + x` if `DOUBLE` is set. The two circuits
+    /// expose their inputs under different names and in different columns.
+    #[derive(Clone)]
+    struct MapCircuit<const DOUBLE: bool> {
+        xs: Vec<Fp>,
+    }
+
+    impl<const DOUBLE: bool> Circuit<Fp> for MapCircuit<DOUBLE> {
+        type Config = MapConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                xs: vec![Fp::from(0); self.xs.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> MapConfig {
+            let (x, y) = if DOUBLE {
+                let y = meta.advice_column();
+                (meta.exposed_advice_column("input"), y)
+            } else {
+                (meta.exposed_advice_column("x"), meta.advice_column())
+            };
+            let s = meta.selector();
+
+            meta.create_gate("map", |meta| {
+                let x = meta.query_advice(x, Rotation::cur());
+                let y = meta.query_advice(y, Rotation::cur());
+                let s = meta.query_selector(s);
+                let mapped = if DOUBLE { x.clone() + x } else { x.clone() * x };
+                vec![s * (mapped - y)]
+            });
+
+            MapConfig { x, y, s }
+        }
+
+        fn synthesize(
+            &self,
+            config: MapConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "map",
+                |mut region| {
+                    for (row, x) in self.xs.iter().cloned().enumerate() {
+                        let y = if DOUBLE { x + x } else { x * x };
+                        config.s.enable(&mut region, row)?;
+                        region.assign_advice(|| "x", config.x, row, || Ok(x))?;
+                        region.assign_advice(|| "y", config.y, row, || Ok(y))?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn prove<const DOUBLE: bool>(
+        params: &Params<G1Affine>,
+        params_verifier: &ParamsVerifier<Bn256>,
+        xs: &[u64],
+    ) -> ExposedAdvice<G1Affine> {
+        let circuit = MapCircuit::<DOUBLE> {
+            xs: xs.iter().map(|x| Fp::from(*x)).collect(),
+        };
+        let empty_circuit = circuit.without_witnesses();
+        let vk = keygen_vk(params, &empty_circuit).expect("keygen_vk should not fail");
+        let pk = keygen_pk(params, vk, &empty_circuit).expect("keygen_pk should not fail");
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(params, &pk, &[circuit], &[&[]], OsRng, &mut transcript)
+            .expect("proof generation should not fail");
+        let proof: Vec<u8> = transcript.finalize();
+
+        // The names of the exposed advice columns come from the verifying key,
+        // so verify with a key read back without the circuit.
+        let mut vk_bytes = vec![];
+        pk.get_vk().store(&mut vk_bytes).unwrap();
+        let vk = VerifyingKey::<G1Affine>::fetch(&mut &vk_bytes[..]).unwrap();
+
+        let strategy = SingleVerifier::new(params_verifier);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+        let ((), exposed) = verify_proof_with_exposed_advice(
+            params_verifier,
+            &vk,
+            strategy,
+            &[&[]],
+            &mut transcript,
+        )
+        .expect("proof verification should not fail");
+        exposed
+    }
+
+    let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K);
+    let params_verifier: ParamsVerifier<Bn256> = params.verifier(0).unwrap();
+
+    let squares = prove::<false>(&params, &params_verifier, &[1, 2, 3, 4]);
+    let doubles = prove::<true>(&params, &params_verifier, &[1, 2, 3, 4]);
+    let other_doubles = prove::<true>(&params, &params_verifier, &[1, 2, 3, 5]);
+
+    assert_eq!(squares.num_instances(), 1);
+    assert_eq!(squares.names().collect::<Vec<_>>(), vec!["x"]);
+    assert_eq!(doubles.names().collect::<Vec<_>>(), vec!["input"]);
+
+    // The inputs of the squaring and doubling proofs are linked, even though the
+    // circuits are different and the proofs were created with fresh randomness.
+    assert!(verify_commitment_link(&squares, 0, "x", &doubles, 0, "input").is_ok());
+    assert_matches!(
+        verify_commitment_link(&squares, 0, "x", &other_doubles, 0, "input"),
+        Err(Error::CommitmentLinkFailure)
+    );
+    assert_matches!(
+        verify_commitment_link(&squares, 0, "y", &doubles, 0, "input"),
+        Err(Error::ColumnNotExposed(name)) if name == "y"
+    );
+    assert_matches!(
+        verify_commitment_link(&squares, 1, "x", &doubles, 0, "input"),
+        Err(Error::InvalidInstances)
+    );
+}
@@ -0,0 +1,253 @@
+use std::ptr;
+
+use ff::PrimeField;
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
+use halo2_proofs::ffi::{
+    halo2_params_verifier_free, halo2_params_verifier_read, halo2_verify_batch, halo2_verify_proof,
+    halo2_verifying_key_free, halo2_verifying_key_read, Halo2BatchEntry, Halo2Instances,
+    Halo2ParamsVerifier, Halo2Status, Halo2VerifyingKey,
+};
+use halo2_proofs::helpers::Serializable;
+use halo2_proofs::plonk::{
+    create_proof, keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error,
+    ProvingKey, Selector,
+};
+use halo2_proofs::poly::{
+    commitment::{Params, ParamsVerifier},
+    Rotation,
+};
+use halo2_proofs::transcript::{Blake2bWrite, Challenge255};
+use pairing::bn256::Fr as Fp;
+use pairing::bn256::{Bn256, G1Affine};
+use rand_core::OsRng;
+
+#[test]
+fn ffi() {
+    const K: u32 = 4;
+
+    #[derive(Clone)]
+    struct MapConfig {
+        x: Column<Advice>,
+        s: Selector,
+    }
+
+    /// Maps `x` to the public input `x * x`, or to `x + x` if `DOUBLE` is set.
+    #[derive(Clone)]
+    struct MapCircuit<const DOUBLE: bool> {
+        x: Fp,
+    }
+
+    impl<const DOUBLE: bool> Circuit<Fp> for MapCircuit<DOUBLE> {
+        type Config = MapConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { x: Fp::from(0) }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> MapConfig {
+            let x = meta.advice_column();
+            let y = meta.instance_column();
+            let s = meta.selector();
+
+            meta.create_gate("map", |meta| {
+                let x = meta.query_advice(x, Rotation::cur());
+                let y = meta.query_instance(y, Rotation::cur());
+                let s = meta.query_selector(s);
+                let mapped = if DOUBLE { x.clone() + x } else { x.clone() * x };
+                vec![s * (mapped - y)]
+            });
+
+            MapConfig { x, s }
+        }
+
+        fn synthesize(
+            &self,
+            config: MapConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "map",
+                |mut region| {
+                    config.s.enable(&mut region, 0)?;
+                    region.assign_advice(|| "x", config.x, 0, || Ok(self.x))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn keygen<const DOUBLE: bool>(params: &Params<G1Affine>) -> ProvingKey<G1Affine> {
+        let empty_circuit = MapCircuit::<DOUBLE> { x: Fp::from(0) };
+        let vk = keygen_vk(params, &empty_circuit).expect("keygen_vk should not fail");
+        keygen_pk(params, vk, &empty_circuit).expect("keygen_pk should not fail")
+    }
+
+    /// Proves the two circuit instances mapping `xs`, and returns their public
+    /// inputs as bytes and the proof.
+    fn prove<const DOUBLE: bool>(
+        params: &Params<G1Affine>,
+        pk: &ProvingKey<G1Affine>,
+        xs: [u64; 2],
+    ) -> (Vec<u8>, Vec<u8>) {
+        let xs = xs.map(Fp::from);
+        let ys = xs.map(|x| if DOUBLE { x + x } else { x * x });
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(
+            params,
+            pk,
+            &[MapCircuit::<DOUBLE> { x: xs[0] }, MapCircuit { x: xs[1] }],
+            &[&[&ys[..1]], &[&ys[1..]]],
+            OsRng,
+            &mut transcript,
+        )
+        .expect("proof generation should not fail");
+        let inputs = ys.iter().flat_map(|y| y.to_repr()).collect();
+        (inputs, transcript.finalize())
+    }
+
+    fn read_params(bytes: &[u8]) -> (Halo2Status, *mut Halo2ParamsVerifier) {
+        let mut params = ptr::null_mut();
+        let status =
+            unsafe { halo2_params_verifier_read(bytes.as_ptr(), bytes.len(), &mut params) };
+        (status, params)
+    }
+
+    fn read_vk(bytes: &[u8]) -> (Halo2Status, *mut Halo2VerifyingKey) {
+        let mut vk = ptr::null_mut();
+        let status = unsafe { halo2_verifying_key_read(bytes.as_ptr(), bytes.len(), &mut vk) };
+        (status, vk)
+    }
+
+    /// The instances of a proof of two circuit instances, with one instance
+    /// column each.
+    fn instances(inputs: &[u8]) -> Halo2Instances {
+        static COLUMN_LENGTHS: [usize; 2] = [1, 1];
+        Halo2Instances {
+            values: inputs.as_ptr(),
+            num_values: inputs.len() / 32,
+            column_lengths: COLUMN_LENGTHS.as_ptr(),
+            num_circuits: 2,
+            num_columns: 1,
+        }
+    }
+
+    let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K);
+    let params_verifier: ParamsVerifier<Bn256> = params.verifier(2).unwrap();
+    let squares_pk = keygen::<false>(&params);
+    let doubles_pk = keygen::<true>(&params);
+
+    let mut params_bytes = vec![];
+    params_verifier.write(&mut params_bytes).unwrap();
+    let mut squares_vk_bytes = vec![];
+    squares_pk.get_vk().store(&mut squares_vk_bytes).unwrap();
+    let mut doubles_vk_bytes = vec![];
+    doubles_pk.get_vk().store(&mut doubles_vk_bytes).unwrap();
+
+    let (status, params_handle) = read_params(&params_bytes);
+    assert_eq!(status, Halo2Status::Ok);
+    let (status, squares_vk) = read_vk(&squares_vk_bytes);
+    assert_eq!(status, Halo2Status::Ok);
+    let (status, doubles_vk) = read_vk(&doubles_vk_bytes);
+    assert_eq!(status, Halo2Status::Ok);
+
+    // Malformed parameters and keys are rejected.
+    let truncated = &params_bytes[..params_bytes.len() - 1];
+    assert_eq!(read_params(truncated).0, Halo2Status::InvalidParams);
+    let mut extended = squares_vk_bytes.clone();
+    extended.push(0);
+    assert_eq!(read_vk(&extended).0, Halo2Status::InvalidVerifyingKey);
+    assert_eq!(
+        unsafe { halo2_verifying_key_read(squares_vk_bytes.as_ptr(), 1, ptr::null_mut()) },
+        Halo2Status::NullPointer
+    );
+
+    let (square_inputs, square_proof) = prove::<false>(&params, &squares_pk, [3, 4]);
+    let (double_inputs, double_proof) = prove::<true>(&params, &doubles_pk, [5, 6]);
+
+    let verify = |vk: *const Halo2VerifyingKey, inputs: &[u8], proof: &[u8]| unsafe {
+        halo2_verify_proof(
+            params_handle,
+            vk,
+            &instances(inputs),
+            proof.as_ptr(),
+            proof.len(),
+        )
+    };
+    assert_eq!(
+        verify(squares_vk, &square_inputs, &square_proof),
+        Halo2Status::Ok
+    );
+    assert_eq!(
+        verify(doubles_vk, &double_inputs, &double_proof),
+        Halo2Status::Ok
+    );
+    assert_eq!(
+        verify(doubles_vk, &square_inputs, &double_proof),
+        Halo2Status::VerificationFailed
+    );
+    assert_eq!(
+        verify(squares_vk, &square_inputs, &square_proof[..64]),
+        Halo2Status::InvalidProof
+    );
+    assert_eq!(
+        verify(squares_vk, &square_inputs[..32], &square_proof),
+        Halo2Status::InvalidInstances
+    );
+
+    // Public inputs must be canonical scalars.
+    let mut non_canonical = square_inputs.clone();
+    non_canonical[..32].copy_from_slice(&[0xff; 32]);
+    assert_eq!(
+        verify(squares_vk, &non_canonical, &square_proof),
+        Halo2Status::InvalidInstances
+    );
+
+    // A batch of proofs for both circuits, in which the third is invalid.
+    let entry = |vk: *const Halo2VerifyingKey, inputs: &[u8], proof: &[u8]| Halo2BatchEntry {
+        params: params_handle,
+        vk,
+        instances: instances(inputs),
+        proof: proof.as_ptr(),
+        proof_len: proof.len(),
+    };
+    let entries = [
+        entry(squares_vk, &square_inputs, &square_proof),
+        entry(doubles_vk, &double_inputs, &double_proof),
+        entry(doubles_vk, &square_inputs, &double_proof),
+        entry(squares_vk, &square_inputs, &square_proof),
+    ];
+    let mut results = [2u8; 4];
+    let status = unsafe { halo2_verify_batch(entries.as_ptr(), 2, results.as_mut_ptr()) };
+    assert_eq!(status, Halo2Status::Ok);
+    assert_eq!(results, [1, 1, 2, 2]);
+    let status = unsafe { halo2_verify_batch(entries.as_ptr(), 4, results.as_mut_ptr()) };
+    assert_eq!(status, Halo2Status::VerificationFailed);
+    assert_eq!(results, [1, 1, 0, 1]);
+
+    unsafe {
+        halo2_verifying_key_free(doubles_vk);
+        halo2_verifying_key_free(squares_vk);
+        halo2_params_verifier_free(params_handle);
+    }
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = env!("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+    let mut generated = vec![];
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("the header should be generated")
+        .write(&mut generated);
+
+    let header = std::fs::read(format!("{}/include/halo2_proofs.h", crate_dir)).unwrap();
+    assert!(
+        generated == header,
+        "include/halo2_proofs.h is out of date, regenerate it with \
+         `cbindgen --config cbindgen.toml --output include/halo2_proofs.h`"
+    );
+}
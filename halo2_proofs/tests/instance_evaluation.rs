@@ -0,0 +1,182 @@
+use assert_matches::assert_matches;
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
+use halo2_proofs::helpers::Serializable;
+use halo2_proofs::plonk::{
+    create_proof, keygen_pk, keygen_vk, verify_proof, verify_proof_with_instance_commitments,
+    Advice, Circuit, Column, ConstraintSystem, Error, Instance, InstanceEvaluation, ProvingKey,
+    Selector, SingleVerifier, VerifyingKey,
+};
+use halo2_proofs::poly::{
+    commitment::{Params, ParamsVerifier},
+    Rotation,
+};
+use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255};
+use pairing::bn256::Fr as Fp;
+use pairing::bn256::{Bn256, G1Affine};
+use rand_core::OsRng;
+
+#[test]
+fn instance_evaluation() {
+    const K: u32 = 5;
+
+    #[derive(Clone)]
+    struct SumConfig {
+        a: Column<Advice>,
+        b: Column<Advice>,
+        instance: Column<Instance>,
+        s: Selector,
+    }
+
+    /// Checks that `a` is the sum of each instance value and the next one, and
+    /// copies the first instance value into `b`. The instance columns are
+    /// evaluated by the verifier if `DIRECT` is set.
+    #[derive(Clone)]
+    struct SumCircuit<const DIRECT: bool> {
+        values: Vec<Fp>,
+    }
+
+    impl<const DIRECT: bool> Circuit<Fp> for SumCircuit<DIRECT> {
+        type Config = SumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Fp::from(0); self.values.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> SumConfig {
+            if DIRECT {
+                meta.set_instance_evaluation(InstanceEvaluation::Direct);
+            }
+
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let instance = meta.instance_column();
+            let s = meta.selector();
+            meta.enable_equality(b);
+            meta.enable_equality(instance);
+
+            meta.create_gate("sum", |meta| {
+                let a = meta.query_advice(a, Rotation::cur());
+                let cur = meta.query_instance(instance, Rotation::cur());
+                let next = meta.query_instance(instance, Rotation::next());
+                let s = meta.query_selector(s);
+                vec![s * (a - cur - next)]
+            });
+
+            SumConfig { a, b, instance, s }
+        }
+
+        fn synthesize(
+            &self,
+            config: SumConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let first = layouter.assign_region(
+                || "sum",
+                |mut region| {
+                    // The instance column is zero after its last value.
+                    for row in 0..self.values.len() {
+                        let next = self.values.get(row + 1).cloned().unwrap_or(Fp::from(0));
+                        let a = self.values[row] + next;
+                        config.s.enable(&mut region, row)?;
+                        region.assign_advice(|| "a", config.a, row, || Ok(a))?;
+                    }
+                    region.assign_advice(|| "b", config.b, 0, || Ok(self.values[0]))
+                },
+            )?;
+            layouter.constrain_instance(first.cell(), config.instance, 0)
+        }
+    }
+
+    fn keygen<const DIRECT: bool>(params: &Params<G1Affine>) -> ProvingKey<G1Affine> {
+        let empty_circuit = SumCircuit::<DIRECT> {
+            values: vec![Fp::from(0); 6],
+        };
+        let vk = keygen_vk(params, &empty_circuit).expect("keygen_vk should not fail");
+        keygen_pk(params, vk, &empty_circuit).expect("keygen_pk should not fail")
+    }
+
+    fn prove<const DIRECT: bool>(
+        params: &Params<G1Affine>,
+        pk: &ProvingKey<G1Affine>,
+        values: &[Fp],
+    ) -> Vec<u8> {
+        let circuit = SumCircuit::<DIRECT> {
+            values: values.to_vec(),
+        };
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(params, pk, &[circuit], &[&[values]], OsRng, &mut transcript)
+            .expect("proof generation should not fail");
+        transcript.finalize()
+    }
+
+    fn verify(
+        params_verifier: &ParamsVerifier<Bn256>,
+        pk: &ProvingKey<G1Affine>,
+        values: &[Fp],
+        proof: &[u8],
+    ) -> Result<(), Error> {
+        let strategy = SingleVerifier::new(params_verifier);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        verify_proof(
+            params_verifier,
+            pk.get_vk(),
+            strategy,
+            &[&[values]],
+            &mut transcript,
+        )
+    }
+
+    let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K);
+    let values: Vec<Fp> = (1..=6u64).map(Fp::from).collect();
+    let mut wrong_values = values.clone();
+    wrong_values[3] = Fp::from(7);
+
+    let committed_pk = keygen::<false>(&params);
+    let direct_pk = keygen::<true>(&params);
+    // The instance evaluation is pinned by the verifying key.
+    assert_ne!(committed_pk.get_vk().digest(), direct_pk.get_vk().digest());
+    // So it is kept when the verifying key is serialized.
+    for pk in [&committed_pk, &direct_pk] {
+        let mut vk_bytes = vec![];
+        pk.get_vk().store(&mut vk_bytes).unwrap();
+        let vk = VerifyingKey::<G1Affine>::fetch(&mut &vk_bytes[..]).unwrap();
+        assert_eq!(vk.digest(), pk.get_vk().digest());
+    }
+
+    let committed_proof = prove::<false>(&params, &committed_pk, &values);
+    let direct_proof = prove::<true>(&params, &direct_pk, &values);
+
+    // The committed instance columns need the Lagrange bases for the public
+    // inputs, while the directly evaluated ones need none.
+    let committed_verifier: ParamsVerifier<Bn256> = params.verifier(values.len()).unwrap();
+    let direct_verifier: ParamsVerifier<Bn256> = params.verifier(0).unwrap();
+
+    assert!(verify(
+        &committed_verifier,
+        &committed_pk,
+        &values,
+        &committed_proof
+    )
+    .is_ok());
+    assert!(verify(&direct_verifier, &direct_pk, &values, &direct_proof).is_ok());
+    assert!(verify(&direct_verifier, &direct_pk, &wrong_values, &direct_proof).is_err());
+
+    // The proof does not contain the evaluations of the two instance queries.
+    assert_eq!(committed_proof.len() - direct_proof.len(), 2 * 32);
+
+    let strategy = SingleVerifier::new(&direct_verifier);
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&direct_proof[..]);
+    assert_matches!(
+        verify_proof_with_instance_commitments(
+            &direct_verifier,
+            direct_pk.get_vk(),
+            strategy,
+            &[&[]],
+            &mut transcript,
+        ),
+        Err(Error::InvalidInstances)
+    );
+}
@@ -0,0 +1,173 @@
+use assert_matches::assert_matches;
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
+use halo2_proofs::helpers::{read_cs, write_cs};
+use halo2_proofs::plonk::{
+    create_proof, keygen_pk_from_parts, keygen_vk, keygen_vk_from_parts, verify_proof, Advice, Any,
+    Circuit, CircuitParts, Column, ConstraintSystem, Error, Fixed, Instance, Selector,
+    SingleVerifier,
+};
+use halo2_proofs::poly::{
+    commitment::{Params, ParamsVerifier},
+    Rotation,
+};
+use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255};
+use pairing::bn256::Fr as Fp;
+use pairing::bn256::{Bn256, G1Affine};
+use rand_core::OsRng;
+
+#[test]
+fn keygen_from_parts() {
+    const K: u32 = 4;
+    const SCALE: u64 = 3;
+
+    #[derive(Clone)]
+    struct ScaleConfig {
+        x: Column<Advice>,
+        y: Column<Advice>,
+        scale: Column<Fixed>,
+        output: Column<Instance>,
+        s: Selector,
+    }
+
+    /// Exposes `x` multiplied by the fixed `SCALE` as its public input.
+    #[derive(Clone)]
+    struct ScaleCircuit {
+        x: Fp,
+    }
+
+    impl Circuit<Fp> for ScaleCircuit {
+        type Config = ScaleConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { x: Fp::from(0) }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> ScaleConfig {
+            let x = meta.advice_column();
+            let y = meta.advice_column();
+            let scale = meta.fixed_column();
+            let output = meta.instance_column();
+            let s = meta.selector();
+            meta.enable_equality(y);
+            meta.enable_equality(output);
+
+            meta.create_gate("scale", |meta| {
+                let x = meta.query_advice(x, Rotation::cur());
+                let y = meta.query_advice(y, Rotation::cur());
+                let scale = meta.query_fixed(scale, Rotation::cur());
+                let s = meta.query_selector(s);
+                vec![s * (x * scale - y)]
+            });
+
+            ScaleConfig {
+                x,
+                y,
+                scale,
+                output,
+                s,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: ScaleConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let y = layouter.assign_region(
+                || "scale",
+                |mut region| {
+                    config.s.enable(&mut region, 0)?;
+                    region.assign_advice(|| "x", config.x, 0, || Ok(self.x))?;
+                    region.assign_fixed(|| "scale", config.scale, 0, || Ok(Fp::from(SCALE)))?;
+                    region.assign_advice(|| "y", config.y, 0, || Ok(self.x * Fp::from(SCALE)))
+                },
+            )?;
+            layouter.constrain_instance(y.cell(), config.output, 0)
+        }
+    }
+
+    // The parts of the circuit as an external compiler would emit them: the
+    // constraint system in its serialized form, the fixed column, the selector
+    // and the copy constraint that `synthesize` assigns.
+    let mut cs = ConstraintSystem::default();
+    ScaleCircuit::configure(&mut cs);
+    let mut cs_bytes = vec![];
+    write_cs::<G1Affine, _>(&cs, &mut cs_bytes).unwrap();
+    let parts = CircuitParts {
+        cs: read_cs::<G1Affine, _>(&mut &cs_bytes[..]).unwrap(),
+        fixed: vec![vec![Fp::from(SCALE)]],
+        selectors: vec![vec![true]],
+        copies: vec![(
+            Column::new(1, Any::Advice),
+            0,
+            Column::new(0, Any::Instance),
+            0,
+        )],
+    };
+
+    let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K);
+    let params_verifier: ParamsVerifier<Bn256> = params.verifier(1).unwrap();
+
+    // The keys generated from the parts are those of the circuit.
+    let circuit_vk = keygen_vk(&params, &ScaleCircuit { x: Fp::from(0) }).unwrap();
+    let vk = keygen_vk_from_parts(&params, &parts).unwrap();
+    assert_eq!(vk.digest(), circuit_vk.digest());
+    let pk = keygen_pk_from_parts(&params, vk, &parts).unwrap();
+
+    let x = Fp::from(5);
+    let y = x * Fp::from(SCALE);
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof(
+        &params,
+        &pk,
+        &[ScaleCircuit { x }],
+        &[&[&[y]]],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("proof generation should not fail");
+    let proof = transcript.finalize();
+
+    let strategy = SingleVerifier::new(&params_verifier);
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+    assert!(verify_proof(
+        &params_verifier,
+        &circuit_vk,
+        strategy,
+        &[&[&[y]]],
+        &mut transcript,
+    )
+    .is_ok());
+
+    // Parts that do not fit the constraint system are rejected.
+    let mut missing_fixed = parts.clone();
+    missing_fixed.fixed.clear();
+    assert_matches!(
+        keygen_vk_from_parts(&params, &missing_fixed),
+        Err(Error::InvalidCircuitParts(_))
+    );
+
+    let mut unusable_row = parts.clone();
+    unusable_row.selectors[0] = vec![true; 1 << K];
+    assert_matches!(
+        keygen_vk_from_parts(&params, &unusable_row),
+        Err(Error::NotEnoughRowsAvailable { current_k }) if current_k == K
+    );
+
+    let mut not_in_permutation = parts.clone();
+    not_in_permutation.copies[0].0 = Column::new(0, Any::Advice);
+    assert_matches!(
+        keygen_vk_from_parts(&params, &not_in_permutation),
+        Err(Error::ColumnNotInPermutation(_))
+    );
+
+    // The constraint system of a verifying key has its selectors compressed
+    // already.
+    let mut compressed = parts.clone();
+    compressed.cs = circuit_vk.cs.clone();
+    assert_matches!(
+        keygen_vk_from_parts(&params, &compressed),
+        Err(Error::InvalidCircuitParts(_))
+    );
+}
@@ -4,10 +4,15 @@ use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{Layouter, SimpleFloorPlanner},
     dev::MockProver,
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
-    poly::Rotation,
+    helpers::Serializable,
+    plonk::{
+        keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector,
+        VerifyingKey,
+    },
+    poly::{commitment::Params, Rotation},
 };
 use pairing::bn256::Fr as Fp;
+use pairing::bn256::{Bn256, G1Affine};
 
 #[test]
 fn lookup_any() {
@@ -206,5 +211,13 @@ fn lookup_any() {
     // If we pass in a public input containing only even numbers,
     // the odd number lookup will fail.
     let prover = MockProver::run(k, &circuit, vec![even_lookup]).unwrap();
-    assert!(prover.verify().is_err())
+    assert!(prover.verify().is_err());
+
+    // The lookups survive a round trip of the verifying key.
+    let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(k);
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk should not fail");
+    let mut vk_bytes = vec![];
+    vk.store(&mut vk_bytes).unwrap();
+    let read = VerifyingKey::<G1Affine>::fetch(&mut &vk_bytes[..]).unwrap();
+    assert_eq!(format!("{:?}", read), format!("{:?}", vk));
 }
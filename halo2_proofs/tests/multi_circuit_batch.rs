@@ -0,0 +1,192 @@
+use assert_matches::assert_matches;
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
+use halo2_proofs::plonk::{
+    create_proof, keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error,
+    MultiCircuitBatchVerifier, ProvingKey, Selector,
+};
+use halo2_proofs::poly::{
+    commitment::{Params, ParamsVerifier},
+    Rotation,
+};
+use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255};
+use pairing::bn256::Fr as Fp;
+use pairing::bn256::{Bn256, G1Affine};
+use rand_core::OsRng;
+
+#[test]
+fn multi_circuit_batch() {
+    const K: u32 = 4;
+
+    #[derive(Clone)]
+    struct MapConfig {
+        x: Column<Advice>,
+        s: Selector,
+    }
+
+    /// Maps `x` to the public input `x * x`, or to `x + x` if `DOUBLE` is set.
+    #[derive(Clone)]
+    struct MapCircuit<const DOUBLE: bool> {
+        x: Fp,
+    }
+
+    impl<const DOUBLE: bool> Circuit<Fp> for MapCircuit<DOUBLE> {
+        type Config = MapConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { x: Fp::from(0) }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> MapConfig {
+            let x = meta.advice_column();
+            let y = meta.instance_column();
+            let s = meta.selector();
+
+            meta.create_gate("map", |meta| {
+                let x = meta.query_advice(x, Rotation::cur());
+                let y = meta.query_instance(y, Rotation::cur());
+                let s = meta.query_selector(s);
+                let mapped = if DOUBLE { x.clone() + x } else { x.clone() * x };
+                vec![s * (mapped - y)]
+            });
+
+            MapConfig { x, s }
+        }
+
+        fn synthesize(
+            &self,
+            config: MapConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "map",
+                |mut region| {
+                    config.s.enable(&mut region, 0)?;
+                    region.assign_advice(|| "x", config.x, 0, || Ok(self.x))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn keygen<const DOUBLE: bool>(params: &Params<G1Affine>) -> ProvingKey<G1Affine> {
+        let empty_circuit = MapCircuit::<DOUBLE> { x: Fp::from(0) };
+        let vk = keygen_vk(params, &empty_circuit).expect("keygen_vk should not fail");
+        keygen_pk(params, vk, &empty_circuit).expect("keygen_pk should not fail")
+    }
+
+    fn prove<const DOUBLE: bool>(
+        params: &Params<G1Affine>,
+        pk: &ProvingKey<G1Affine>,
+        x: u64,
+    ) -> (Fp, Vec<u8>) {
+        let x = Fp::from(x);
+        let y = if DOUBLE { x + x } else { x * x };
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(
+            params,
+            pk,
+            &[MapCircuit::<DOUBLE> { x }],
+            &[&[&[y]]],
+            OsRng,
+            &mut transcript,
+        )
+        .expect("proof generation should not fail");
+        (y, transcript.finalize())
+    }
+
+    /// The verifier parameters, proving key, public input and proof of a proof.
+    type Entry<'a> = (
+        &'a ParamsVerifier<Bn256>,
+        &'a ProvingKey<G1Affine>,
+        Fp,
+        &'a [u8],
+    );
+
+    fn add<'a>(
+        batch: &mut MultiCircuitBatchVerifier<'a, Bn256>,
+        (params, pk, y, proof): Entry<'a>,
+    ) -> Result<usize, Error> {
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        batch.add_proof(params, pk.get_vk(), &[&[&[y]]], &mut transcript)
+    }
+
+    let params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K);
+    let squares_pk = keygen::<false>(&params);
+    let doubles_pk = keygen::<true>(&params);
+
+    // The two circuits are checked with different verifier parameters, which
+    // share the same `g2` and `s_g2`.
+    let squares_verifier: ParamsVerifier<Bn256> = params.verifier(1).unwrap();
+    let doubles_verifier: ParamsVerifier<Bn256> = params.verifier(2).unwrap();
+
+    let (square, square_proof) = prove::<false>(&params, &squares_pk, 3);
+    let (double, double_proof) = prove::<true>(&params, &doubles_pk, 5);
+    let squares = (&squares_verifier, &squares_pk, square, &square_proof[..]);
+    let doubles = (&doubles_verifier, &doubles_pk, double, &double_proof[..]);
+
+    let mut batch = MultiCircuitBatchVerifier::new();
+    for _ in 0..3 {
+        add(&mut batch, squares).unwrap();
+        add(&mut batch, doubles).unwrap();
+    }
+    assert_eq!(batch.len(), 6);
+    assert_eq!(batch.finalize(OsRng), Ok(()));
+
+    // A proof checked against the wrong public input fails the pairing check,
+    // and a truncated proof cannot be read; bisection finds both.
+    let wrong_input = (&doubles_verifier, &doubles_pk, square, &double_proof[..]);
+    let truncated_proof = &square_proof[..square_proof.len() - 1];
+    let truncated = (&squares_verifier, &squares_pk, square, truncated_proof);
+
+    let mut batch = MultiCircuitBatchVerifier::new();
+    for _ in 0..3 {
+        add(&mut batch, squares).unwrap();
+        add(&mut batch, doubles).unwrap();
+    }
+    let wrong_input = add(&mut batch, wrong_input).unwrap();
+    let truncated = add(&mut batch, truncated).unwrap();
+    add(&mut batch, squares).unwrap();
+    assert_eq!(batch.finalize(OsRng), Err(vec![wrong_input, truncated]));
+
+    // Parameters from another setup cannot be checked in the same batch.
+    let other_params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K);
+    let other_verifier: ParamsVerifier<Bn256> = other_params.verifier(1).unwrap();
+    let mut batch = MultiCircuitBatchVerifier::new();
+    add(&mut batch, squares).unwrap();
+    let other = (&other_verifier, &squares_pk, square, &square_proof[..]);
+    assert_matches!(add(&mut batch, other), Err(Error::IncompatibleParams));
+
+    // Nor can parameters that only differ from the others in their `s_g2`.
+    let mut tampered_verifier: ParamsVerifier<Bn256> = params.verifier(1).unwrap();
+    tampered_verifier.s_g2 = other_verifier.s_g2;
+    let mut batch = MultiCircuitBatchVerifier::new();
+    add(&mut batch, squares).unwrap();
+    let tampered = (&tampered_verifier, &squares_pk, square, &square_proof[..]);
+    assert_matches!(add(&mut batch, tampered), Err(Error::IncompatibleParams));
+
+    // Circuits of different sizes are checked in the same batch, with
+    // parameters of different sizes downsized from one setup.
+    let large_params: Params<G1Affine> = Params::<G1Affine>::unsafe_setup::<Bn256>(K + 1);
+    let small_params = large_params.downsize(K);
+    let large_pk = keygen::<false>(&large_params);
+    let small_pk = keygen::<true>(&small_params);
+    let large_verifier: ParamsVerifier<Bn256> = large_params.verifier(1).unwrap();
+    let small_verifier: ParamsVerifier<Bn256> = small_params.verifier(1).unwrap();
+    assert_ne!(large_verifier.k, small_verifier.k);
+
+    let (large_square, large_proof) = prove::<false>(&large_params, &large_pk, 7);
+    let (small_double, small_proof) = prove::<true>(&small_params, &small_pk, 11);
+    let mut batch = MultiCircuitBatchVerifier::new();
+    add(
+        &mut batch,
+        (&large_verifier, &large_pk, large_square, &large_proof[..]),
+    )
+    .unwrap();
+    add(
+        &mut batch,
+        (&small_verifier, &small_pk, small_double, &small_proof[..]),
+    )
+    .unwrap();
+    assert_eq!(batch.finalize(OsRng), Ok(()));
+}
@@ -5,9 +5,17 @@ use assert_matches::assert_matches;
 use halo2_proofs::arithmetic::FieldExt;
 use halo2_proofs::circuit::{Cell, Layouter, SimpleFloorPlanner};
 use halo2_proofs::dev::MockProver;
+use halo2_proofs::helpers::{AssignWitnessCollection, Serializable};
 use halo2_proofs::plonk::{
-    create_proof, keygen_pk, keygen_vk, verify_proof, Advice, BatchVerifier, Circuit, Column,
-    ConstraintSystem, Error, Fixed, SingleVerifier, TableColumn, VerifyingKey,
+    commit_instances, create_proof, create_proof_from_columns_checked, create_proof_from_witness,
+    create_proof_with_checkpoints, create_proof_with_control, create_witness,
+    create_witness_with_encoding, get_advice_commitments_from_transcript, keygen_pk, keygen_vk,
+    resume_proof, verify_proof, verify_proof_envelope, verify_proof_envelope_with_exposed_advice,
+    verify_proof_envelope_with_instance_commitments, verify_proof_with_instance_commitments,
+    witness_instances, Advice, BatchVerifier, CancelFlag, Checkpoint, Circuit, Column,
+    ConstraintSystem, EnvelopeMismatch, Error, Fixed, MemoryBudget, MultiopenScheme, NoControl,
+    ProofEnvelope, ProofView, ProverPhase, ProverPool, QuotientEvaluation, SingleVerifier,
+    TableColumn, TranscriptKind, VerifyingKey, WitnessEncoding,
 };
 
 use halo2_proofs::poly::{
@@ -15,8 +23,13 @@ use halo2_proofs::poly::{
     Rotation,
 };
 use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use rand_core::OsRng;
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
 
 use pairing::bn256::Fr as Fp;
 use pairing::bn256::{Bn256, G1Affine};
@@ -229,6 +242,8 @@ fn plonk_api() {
                 |mut region| {
                     let value = region.assign_advice(|| "value", self.config.a, 0, &mut f)?;
                     region.assign_fixed(|| "public", self.config.sp, 0, || Ok(FF::one()))?;
+                    // Every backend reports unassigned cells as unknown.
+                    assert_eq!(region.query_advice(self.config.b, 0)?, None);
 
                     Ok(value.cell())
                 },
@@ -438,6 +453,449 @@ fn plonk_api() {
     };
     assert_eq!(prover.verify(), Ok(()));
 
+    // Check that a synthesis error is returned rather than panicking.
+    {
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        assert_matches!(
+            create_proof_with_control(
+                &params,
+                &pk,
+                &[empty_circuit.clone()],
+                &[&[&[instance]]],
+                OsRng,
+                &mut transcript,
+                &NoControl,
+            ),
+            Err(Error::Synthesis)
+        );
+    }
+
+    // Check that a cancelled proof is aborted before it finishes.
+    {
+        let control = CancelFlag::new();
+        control.cancel();
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        assert_matches!(
+            create_proof_with_control(
+                &params,
+                &pk,
+                &[circuit.clone()],
+                &[&[&[instance]]],
+                OsRng,
+                &mut transcript,
+                &control,
+            ),
+            Err(Error::Cancelled)
+        );
+    }
+
+    // Check that a proof cancelled in the middle of a phase is aborted before
+    // it reaches the next one.
+    {
+        let phases = Arc::new(Mutex::new(vec![]));
+        let control = CancelFlag::new();
+        let control = {
+            let cancel = control.clone();
+            let phases = phases.clone();
+            control.with_progress(move |phase, _| {
+                phases.lock().unwrap().push(phase);
+                if phase == ProverPhase::Products {
+                    cancel.cancel();
+                }
+            })
+        };
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        assert_matches!(
+            create_proof_with_control(
+                &params,
+                &pk,
+                &[circuit.clone()],
+                &[&[&[instance]]],
+                OsRng,
+                &mut transcript,
+                &control,
+            ),
+            Err(Error::Cancelled)
+        );
+        assert_eq!(phases.lock().unwrap().last(), Some(&ProverPhase::Products));
+    }
+
+    // Check that the progress of a proof goes through every phase in order,
+    // from 0 to 1.
+    {
+        let reports = Arc::new(Mutex::new(vec![]));
+        let control = {
+            let reports = reports.clone();
+            CancelFlag::new().with_progress(move |phase, fraction| {
+                reports.lock().unwrap().push((phase, fraction));
+            })
+        };
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_with_control(
+            &params,
+            &pk,
+            &[circuit.clone()],
+            &[&[&[instance]]],
+            OsRng,
+            &mut transcript,
+            &control,
+        )
+        .expect("proof generation should not fail");
+
+        let reports = reports.lock().unwrap();
+        let mut phases: Vec<ProverPhase> = reports.iter().map(|(phase, _)| *phase).collect();
+        phases.dedup();
+        assert_eq!(
+            phases,
+            vec![
+                ProverPhase::Instance,
+                ProverPhase::Advice,
+                ProverPhase::LookupPermuted,
+                ProverPhase::Products,
+                ProverPhase::Quotient,
+                ProverPhase::Vanishing,
+                ProverPhase::Evaluation,
+                ProverPhase::Opening,
+            ]
+        );
+        assert_eq!(reports.first().unwrap().1, 0.0);
+        assert_eq!(reports.last().unwrap().1, 1.0);
+        // Steps within a phase may be reported out of order by the worker
+        // threads, but each phase only starts after the previous one ended.
+        for pair in reports.windows(2) {
+            if pair[0].0 != pair[1].0 {
+                assert!(pair[0].1 <= pair[1].1);
+            }
+        }
+    }
+
+    // Check that a proof resumed from any of its checkpoints is identical to
+    // the uninterrupted proof.
+    {
+        let mut checkpoints = vec![];
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_with_checkpoints(
+            &params,
+            &pk,
+            &[circuit.clone()],
+            &[&[&[instance]]],
+            StdRng::seed_from_u64(0),
+            &mut transcript,
+            &NoControl,
+            &mut |checkpoint| {
+                let mut buf = vec![];
+                checkpoint.write(&mut buf).unwrap();
+                checkpoints.push(buf);
+                Ok(())
+            },
+        )
+        .expect("proof generation should not fail");
+        let proof: Vec<u8> = transcript.finalize();
+        assert_eq!(checkpoints.len(), 3);
+
+        for (rounds, buf) in checkpoints.iter().enumerate() {
+            let checkpoint = Checkpoint::<G1Affine>::read(&mut &buf[..]).unwrap();
+            assert_eq!(checkpoint.rounds(), rounds + 1);
+
+            let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+            resume_proof(
+                &params,
+                &pk,
+                &[&[&[instance]]],
+                checkpoint,
+                &mut transcript,
+                &NoControl,
+                &mut |_| Ok(()),
+            )
+            .expect("resuming the proof should not fail");
+            assert_eq!(transcript.finalize(), proof);
+        }
+
+        // A checkpoint cannot be resumed for other instances.
+        let checkpoint = Checkpoint::<G1Affine>::read(&mut &checkpoints[0][..]).unwrap();
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        assert_matches!(
+            resume_proof(
+                &params,
+                &pk,
+                &[&[&[instance + Fp::one()]]],
+                checkpoint,
+                &mut transcript,
+                &NoControl,
+                &mut |_| Ok(()),
+            ),
+            Err(Error::InvalidCheckpoint)
+        );
+
+        // Nor can a checkpoint whose advice round is missing a commitment.
+        let mut buf = checkpoints[0].clone();
+        let len = u32::fetch(&mut &buf[76..80]).unwrap();
+        buf[76..80].copy_from_slice(&(len - 1).to_le_bytes());
+        // Drop the last (compressed) point of the round.
+        let end = 80 + 32 * len as usize;
+        buf.drain(end - 32..end);
+        let checkpoint = Checkpoint::<G1Affine>::read(&mut &buf[..]).unwrap();
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        assert_matches!(
+            resume_proof(
+                &params,
+                &pk,
+                &[&[&[instance]]],
+                checkpoint,
+                &mut transcript,
+                &NoControl,
+                &mut |_| Ok(()),
+            ),
+            Err(Error::InvalidCheckpoint)
+        );
+
+        // A proof that spills all of its polynomials is also identical.
+        let control = CancelFlag::new().with_memory_budget(MemoryBudget::new(0));
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_with_control(
+            &params,
+            &pk,
+            &[circuit.clone()],
+            &[&[&[instance]]],
+            StdRng::seed_from_u64(0),
+            &mut transcript,
+            &control,
+        )
+        .expect("proof generation should not fail");
+        assert_eq!(transcript.finalize(), proof);
+
+        // So is a proof created on a bounded thread pool, whatever its number
+        // of threads.
+        for threads in [1, 2, 3] {
+            let pool = ProverPool::new(1, threads, false).unwrap();
+            let pooled_proof = pool.run(|| {
+                let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+                create_proof(
+                    &params,
+                    &pk,
+                    &[circuit.clone()],
+                    &[&[&[instance]]],
+                    StdRng::seed_from_u64(0),
+                    &mut transcript,
+                )
+                .expect("proof generation should not fail");
+                transcript.finalize()
+            });
+
+            let strategy = SingleVerifier::new(&params_verifier);
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&pooled_proof[..]);
+            assert!(verify_proof(
+                &params_verifier,
+                pk.get_vk(),
+                strategy,
+                &[&[&[instance]]],
+                &mut transcript,
+            )
+            .is_ok());
+            assert_eq!(pooled_proof, proof);
+        }
+
+        // So is a proof that evaluates the quotient polynomial by coset.
+        let control = CancelFlag::new().with_quotient_evaluation(QuotientEvaluation::ByCoset);
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_with_control(
+            &params,
+            &pk,
+            &[circuit.clone()],
+            &[&[&[instance]]],
+            StdRng::seed_from_u64(0),
+            &mut transcript,
+            &control,
+        )
+        .expect("proof generation should not fail");
+        assert_eq!(transcript.finalize(), proof);
+
+        // And one that recomputes the extended cosets from spilled
+        // polynomials, one column at a time.
+        let control = CancelFlag::new()
+            .with_memory_budget(MemoryBudget::new(0))
+            .with_quotient_evaluation(QuotientEvaluation::Extended);
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_with_control(
+            &params,
+            &pk,
+            &[circuit.clone()],
+            &[&[&[instance]]],
+            StdRng::seed_from_u64(0),
+            &mut transcript,
+            &control,
+        )
+        .expect("proof generation should not fail");
+        assert_eq!(transcript.finalize(), proof);
+    }
+
+    // Check that a proof can be created from a bundle of stored witnesses,
+    // and that a corrupted witness is rejected.
+    {
+        let path = std::env::temp_dir().join(format!("halo2-witness-{}", std::process::id()));
+        let mut fd = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        for _ in 0..2 {
+            create_witness(&params, &pk, &circuit, &[&[instance]], &mut fd)
+                .expect("witness generation should not fail");
+        }
+        assert_eq!(
+            witness_instances(&params, &pk, &mut fd).unwrap(),
+            vec![vec![pubinputs.clone()]; 2]
+        );
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_from_witness(&params, &pk, OsRng, &mut transcript, &mut fd)
+            .expect("proof generation should not fail");
+        let proof: Vec<u8> = transcript.finalize();
+
+        let strategy = SingleVerifier::new(&params_verifier);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+        assert!(verify_proof(
+            &params_verifier,
+            pk.get_vk(),
+            strategy,
+            &[&[&pubinputs[..]], &[&pubinputs[..]]],
+            &mut transcript,
+        )
+        .is_ok());
+
+        // Flip a bit of the last advice column.
+        let len = fd.metadata().unwrap().len();
+        let mut byte = [0u8; 1];
+        fd.seek(SeekFrom::Start(len - 1)).unwrap();
+        fd.read_exact(&mut byte).unwrap();
+        byte[0] ^= 1;
+        fd.seek(SeekFrom::Start(len - 1)).unwrap();
+        fd.write_all(&byte).unwrap();
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        assert_matches!(
+            create_proof_from_witness(&params, &pk, OsRng, &mut transcript, &mut fd),
+            Err(Error::InvalidWitness(_))
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // Check that a compact witness is smaller than a raw one, and yields the
+    // same proof.
+    {
+        let mut proofs = vec![];
+        let mut sizes = vec![];
+        for encoding in vec![WitnessEncoding::Raw, WitnessEncoding::Compact] {
+            let path = std::env::temp_dir().join(format!(
+                "halo2-witness-{:?}-{}",
+                encoding,
+                std::process::id()
+            ));
+            let mut fd = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)
+                .unwrap();
+            create_witness_with_encoding(&params, &pk, &circuit, &[&[instance]], encoding, &mut fd)
+                .expect("witness generation should not fail");
+            sizes.push(fd.metadata().unwrap().len());
+
+            let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+            create_proof_from_witness(
+                &params,
+                &pk,
+                StdRng::seed_from_u64(0),
+                &mut transcript,
+                &mut fd,
+            )
+            .expect("proof generation should not fail");
+            proofs.push(transcript.finalize());
+
+            std::fs::remove_file(&path).unwrap();
+        }
+        assert!(sizes[1] < sizes[0]);
+        assert_eq!(proofs[0], proofs[1]);
+    }
+
+    // Check that a proof created from raw advice columns matches the proof
+    // created from the same witness, and that the checking mode rejects
+    // columns that do not satisfy the circuit.
+    {
+        let path = std::env::temp_dir().join(format!("halo2-columns-{}", std::process::id()));
+        let mut fd = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        create_witness(&params, &pk, &circuit, &[&[instance]], &mut fd)
+            .expect("witness generation should not fail");
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_from_witness(
+            &params,
+            &pk,
+            StdRng::seed_from_u64(0),
+            &mut transcript,
+            &mut fd,
+        )
+        .expect("proof generation should not fail");
+        let proof: Vec<u8> = transcript.finalize();
+
+        fd.seek(SeekFrom::Start(0)).unwrap();
+        let witness = AssignWitnessCollection::fetch_witness(&params, &pk, &mut fd).unwrap();
+
+        // Values that are not in their canonical representation are rejected,
+        // here the instance value that follows the header of the 5 advice
+        // columns and the instance column.
+        fd.seek(SeekFrom::Start(92 + 44 * 5 + 4 + 4 + 32)).unwrap();
+        fd.write_all(&[0xff; 32]).unwrap();
+        fd.seek(SeekFrom::Start(0)).unwrap();
+        assert_matches!(
+            AssignWitnessCollection::fetch_witness(&params, &pk, &mut fd),
+            Err(Error::InvalidWitness(_))
+        );
+        std::fs::remove_file(&path).unwrap();
+        let usable_rows = (1 << K) - (pk.get_vk().cs.blinding_factors() + 1);
+        let mut advice: Vec<Vec<Fp>> = witness
+            .advice
+            .iter()
+            .map(|column| column[..][..usable_rows].to_vec())
+            .collect();
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof_from_columns_checked(
+            &params,
+            &pk,
+            vec![advice.clone()],
+            &[&[&[instance]]],
+            StdRng::seed_from_u64(0),
+            &mut transcript,
+        )
+        .expect("proof generation should not fail");
+        assert_eq!(transcript.finalize(), proof);
+
+        let row = advice[0].iter().position(|v| *v != Fp::zero()).unwrap();
+        advice[0][row] += Fp::one();
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        assert_matches!(
+            create_proof_from_columns_checked(
+                &params,
+                &pk,
+                vec![advice],
+                &[&[&[instance]]],
+                OsRng,
+                &mut transcript,
+            ),
+            Err(Error::ConstraintSystemFailure)
+        );
+    }
+
     for _ in 0..10 {
         let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
         // Create a proof
@@ -466,6 +924,181 @@ fn plonk_api() {
             .is_ok());
         }
 
+        // Test verification from precomputed instance commitments, which does not
+        // need verifier parameters that cover the public inputs.
+        {
+            let instance_commitments = commit_instances(
+                &params,
+                pk.get_vk(),
+                &[&[&pubinputs[..]], &[&pubinputs[..]]],
+            )
+            .unwrap();
+            let instance_commitments: Vec<_> =
+                instance_commitments.iter().map(|c| &c[..]).collect();
+            let params_verifier: ParamsVerifier<Bn256> = params.verifier(0).unwrap();
+            let strategy = SingleVerifier::new(&params_verifier);
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            assert!(verify_proof_with_instance_commitments(
+                &params_verifier,
+                pk.get_vk(),
+                strategy,
+                &instance_commitments,
+                &mut transcript,
+            )
+            .is_ok());
+        }
+
+        // Test the structured view of the proof, which must consume exactly the
+        // bytes of the proof.
+        {
+            let view = ProofView::parse(pk.get_vk(), &proof, 2).unwrap();
+            assert_eq!(view.instances.len(), 2);
+            assert_eq!(view.instances[0].advice_commitments.len(), 5);
+            assert_eq!(view.instances[0].advice_commitments[1].0, "Advice[1]");
+            assert_eq!(view.instances[0].lookups.len(), 1);
+            assert_eq!(view.instances[0].lookups[0].name, "lookup");
+            assert!(!view.instances[0].permutation_products.is_empty());
+            assert!(!view.multiopen.is_empty());
+
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            let advice_commitments =
+                get_advice_commitments_from_transcript::<Bn256, _, _>(pk.get_vk(), &mut transcript)
+                    .unwrap();
+            let viewed: Vec<_> = view.instances[0]
+                .advice_commitments
+                .iter()
+                .map(|(_, commitment)| *commitment)
+                .collect();
+            assert_eq!(viewed, advice_commitments);
+
+            let json = view.to_json();
+            assert!(json.starts_with("{\"instances\":[{\"advice_commitments\":["));
+            assert!(json.contains("\"name\":\"lookup\""));
+
+            assert!(ProofView::parse(pk.get_vk(), &proof[..proof.len() - 1], 2).is_err());
+            let mut extended = proof.clone();
+            extended.push(0);
+            assert!(ProofView::parse(pk.get_vk(), &extended, 2).is_err());
+        }
+
+        // Test the proof envelope, which is checked before the proof.
+        {
+            let instances: &[&[&[Fp]]] = &[&[&pubinputs[..]], &[&pubinputs[..]]];
+            let envelope = ProofEnvelope::new(pk.get_vk(), TranscriptKind::Blake2b, instances);
+            assert_eq!(envelope.num_instances(), 2);
+            let sealed = envelope.seal(&proof);
+
+            let verify = |instances: &[&[&[Fp]]], sealed: &[u8]| {
+                let strategy = SingleVerifier::new(&params_verifier);
+                verify_proof_envelope(&params_verifier, pk.get_vk(), strategy, instances, sealed)
+            };
+            assert!(verify(instances, &sealed).is_ok());
+
+            assert_matches!(
+                verify(&[&[&pubinputs[..]]], &sealed),
+                Err(Error::EnvelopeMismatch(EnvelopeMismatch::Instances))
+            );
+
+            // The scheme tag follows the magic, version, digest, k and transcript.
+            let mut other_scheme = sealed.clone();
+            other_scheme[4 + 4 + 32 + 4 + 1] = match MultiopenScheme::current() {
+                MultiopenScheme::Gwc => 1,
+                MultiopenScheme::Shplonk => 0,
+            };
+            assert_matches!(
+                verify(instances, &other_scheme),
+                Err(Error::EnvelopeMismatch(EnvelopeMismatch::Scheme { .. }))
+            );
+
+            let mut other_k = sealed.clone();
+            other_k[4 + 4 + 32] ^= 1;
+            assert_matches!(
+                verify(instances, &other_k),
+                Err(Error::EnvelopeMismatch(EnvelopeMismatch::K { .. }))
+            );
+
+            let mut other_vk = sealed.clone();
+            other_vk[4 + 4] ^= 1;
+            assert_matches!(
+                verify(instances, &other_vk),
+                Err(Error::EnvelopeMismatch(EnvelopeMismatch::VerifyingKey))
+            );
+
+            assert_matches!(verify(instances, &proof), Err(Error::InvalidEnvelope(_)));
+
+            // The envelope is also checked before the proof when the exposed
+            // advice commitments are returned.
+            let verify_exposed = |sealed: &[u8]| {
+                let strategy = SingleVerifier::new(&params_verifier);
+                verify_proof_envelope_with_exposed_advice(
+                    &params_verifier,
+                    pk.get_vk(),
+                    strategy,
+                    instances,
+                    sealed,
+                )
+            };
+            let (_, exposed) = verify_exposed(&sealed).unwrap();
+            assert_eq!(exposed.num_instances(), 2);
+            assert_matches!(
+                verify_exposed(&other_vk),
+                Err(Error::EnvelopeMismatch(EnvelopeMismatch::VerifyingKey))
+            );
+
+            // And when the instances are given by their commitments, whose
+            // number must match the envelope.
+            let instance_commitments = commit_instances(&params, pk.get_vk(), instances).unwrap();
+            let instance_commitments: Vec<_> =
+                instance_commitments.iter().map(|c| &c[..]).collect();
+            let params_verifier: ParamsVerifier<Bn256> = params.verifier(0).unwrap();
+            let verify_committed = |instance_commitments: &[&[G1Affine]], sealed: &[u8]| {
+                let strategy = SingleVerifier::new(&params_verifier);
+                verify_proof_envelope_with_instance_commitments(
+                    &params_verifier,
+                    pk.get_vk(),
+                    strategy,
+                    instance_commitments,
+                    sealed,
+                )
+            };
+            assert!(verify_committed(&instance_commitments, &sealed).is_ok());
+            assert_matches!(
+                verify_committed(&instance_commitments[..1], &sealed),
+                Err(Error::EnvelopeMismatch(EnvelopeMismatch::Instances))
+            );
+            assert_matches!(
+                verify_committed(&instance_commitments, &other_k),
+                Err(Error::EnvelopeMismatch(EnvelopeMismatch::K { .. }))
+            );
+        }
+
+        // Test the self-describing verifying key, which is read without the
+        // circuit.
+        {
+            let mut vk_buffer = vec![];
+            pk.get_vk().store(&mut vk_buffer).unwrap();
+            let vk = VerifyingKey::<G1Affine>::fetch(&mut &vk_buffer[..]).unwrap();
+            assert_eq!(format!("{:?}", vk), format!("{:?}", pk.get_vk()));
+            assert_eq!(vk.digest(), pk.get_vk().digest());
+
+            let strategy = SingleVerifier::new(&params_verifier);
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            assert!(verify_proof(
+                &params_verifier,
+                &vk,
+                strategy,
+                &[&[&pubinputs[..]], &[&pubinputs[..]]],
+                &mut transcript,
+            )
+            .is_ok());
+
+            // Keys in another format or version are rejected.
+            assert!(VerifyingKey::<G1Affine>::fetch(&mut &vk_buffer[4..]).is_err());
+            let mut other_version = vk_buffer.clone();
+            other_version[4] += 1;
+            assert!(VerifyingKey::<G1Affine>::fetch(&mut &other_version[..]).is_err());
+        }
+
         //
         // Test batch-verifier strategy.
         //
@@ -485,14 +1118,17 @@ fn plonk_api() {
             .unwrap();
 
             // Write and then read the verification key in between (to check round-trip
-            // serialization).
-            // TODO: Figure out whether https://github.com/zcash/halo2/issues/449 should
-            // be caught by this, or if it is caused by downstream changes to halo2.
+            // serialization). The key is written in the self-describing format, so
+            // the key read has the compressed selectors of the key written.
             let mut vk_buffer = vec![];
             pk.get_vk().write(&mut vk_buffer).unwrap();
+            let mut stored = vec![];
+            pk.get_vk().store(&mut stored).unwrap();
+            assert_eq!(vk_buffer, stored);
             let vk =
                 VerifyingKey::<G1Affine>::read::<_, MyCircuit<Fp>>(&mut &vk_buffer[..], &params)
                     .unwrap();
+            assert_eq!(vk.digest(), pk.get_vk().digest());
 
             // "Second" proof (just the first proof again).
             let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);